@@ -0,0 +1,106 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Byte stream utilities.
+
+use std::hash::Hasher;
+use std::io::Write;
+
+/// An io [Write](Write) adapter which updates a [Hasher] with every byte written while
+/// forwarding writes to an underlying writer.
+///
+/// Only the bytes accepted by the underlying writer are fed to the hasher, with one
+/// [Hasher::write] call per accepted write. [Hasher] does not guarantee that adjacent writes
+/// are merged, so the resulting hash depends on the write boundaries and not only on the
+/// written content.
+pub struct HashingWriter<W: Write, H: Hasher> {
+    inner: W,
+    hasher: H,
+}
+
+impl<W: Write, H: Hasher> HashingWriter<W, H> {
+    /// Creates a new [HashingWriter](HashingWriter).
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the underlying writer to forward writes to.
+    /// * `hasher`: the hasher to update.
+    ///
+    /// returns: HashingWriter<W, H>
+    pub fn new(inner: W, hasher: H) -> Self {
+        Self { inner, hasher }
+    }
+
+    /// Returns the hash of all bytes written so far.
+    pub fn hash(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Extracts the underlying writer and the hash of all bytes written.
+    pub fn finalize(self) -> (W, u64) {
+        let hash = self.hasher.finish();
+        (self.inner, hash)
+    }
+}
+
+impl<W: Write, H: Hasher> Write for HashingWriter<W, H> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.write(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::HashingWriter;
+    use std::hash::{DefaultHasher, Hasher};
+    use std::io::Write;
+
+    #[test]
+    fn basic() {
+        let mut w = HashingWriter::new(Vec::new(), DefaultHasher::new());
+        w.write_all(b"hello ").unwrap();
+        w.write_all(b"world").unwrap();
+        let (buf, hash) = w.finalize();
+        let mut expected = DefaultHasher::new();
+        expected.write(b"hello ");
+        expected.write(b"world");
+        assert_eq!(buf, b"hello world");
+        assert_eq!(hash, expected.finish());
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// A typed handle to a value stored in an [Arena].
+///
+/// Handles are not generational: when an [Arena] is configured with a free list, a handle to a
+/// removed value may later refer to a different value.
+pub struct Handle<T> {
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn new(index: usize) -> Self {
+        Self {
+            index,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the raw slot index of this handle.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Handle({})", self.index)
+    }
+}
+
+/// An append-only arena allocator addressed by typed [Handle]s.
+///
+/// Iteration always happens in slot order, which is insertion order unless a free list is used,
+/// in which case newly inserted values may fill slots of previously removed values.
+#[derive(Clone, Debug)]
+pub struct Arena<T> {
+    items: Vec<Option<T>>,
+    free_list: Option<Vec<usize>>,
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Arena<T> {
+    /// Creates a new empty [Arena] which never reuses slots of removed values.
+    pub fn new() -> Arena<T> {
+        Arena {
+            items: Vec::new(),
+            free_list: None,
+            len: 0,
+        }
+    }
+
+    /// Creates a new empty [Arena] with a given capacity which never reuses slots of removed
+    /// values.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity`: the capacity of the new [Arena].
+    pub fn with_capacity(capacity: usize) -> Arena<T> {
+        Arena {
+            items: Vec::with_capacity(capacity),
+            free_list: None,
+            len: 0,
+        }
+    }
+
+    /// Creates a new empty [Arena] which reuses slots of removed values.
+    pub fn with_free_list() -> Arena<T> {
+        Arena {
+            items: Vec::new(),
+            free_list: Some(Vec::new()),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values in this [Arena].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true when this [Arena] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a new value in this [Arena].
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to be inserted.
+    ///
+    /// returns: Handle<T> the handle to the inserted value.
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        self.len += 1;
+        if let Some(index) = self.free_list.as_mut().and_then(|v| v.pop()) {
+            self.items[index] = Some(value);
+            return Handle::new(index);
+        }
+        self.items.push(Some(value));
+        Handle::new(self.items.len() - 1)
+    }
+
+    /// Removes a value from this [Arena].
+    ///
+    /// Returns None if the handle does not point to a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle`: the handle of the value to remove.
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let value = self.items.get_mut(handle.index)?.take()?;
+        self.len -= 1;
+        if let Some(free_list) = &mut self.free_list {
+            free_list.push(handle.index);
+        }
+        Some(value)
+    }
+
+    /// Gets a value from its handle.
+    ///
+    /// Returns None if the handle does not point to a value.
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        self.items.get(handle.index)?.as_ref()
+    }
+
+    /// Gets a mutable value from its handle.
+    ///
+    /// Returns None if the handle does not point to a value.
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        self.items.get_mut(handle.index)?.as_mut()
+    }
+
+    /// Gets 2 mutable values at the same time.
+    ///
+    /// Returns None if any of the handles does not point to a value or if both handles are equal.
+    ///
+    /// # Arguments
+    ///
+    /// * `a`: the handle of the first value.
+    /// * `b`: the handle of the second value.
+    pub fn get_pair_mut(&mut self, a: Handle<T>, b: Handle<T>) -> Option<(&mut T, &mut T)> {
+        if a.index == b.index || a.index >= self.items.len() || b.index >= self.items.len() {
+            return None;
+        }
+        let (first, second) = if a.index < b.index {
+            let (left, right) = self.items.split_at_mut(b.index);
+            (&mut left[a.index], &mut right[0])
+        } else {
+            let (left, right) = self.items.split_at_mut(a.index);
+            (&mut right[0], &mut left[b.index])
+        };
+        Some((first.as_mut()?, second.as_mut()?))
+    }
+
+    /// Returns an iterator over all values with their handles, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| Some((Handle::new(i), v.as_ref()?)))
+    }
+
+    /// Returns a mutable iterator over all values with their handles, in slot order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<T>, &mut T)> {
+        self.items
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, v)| Some((Handle::new(i), v.as_mut()?)))
+    }
+}
+
+impl<T> std::ops::Index<Handle<T>> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, index: Handle<T>) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T> std::ops::IndexMut<Handle<T>> for Arena<T> {
+    fn index_mut(&mut self, index: Handle<T>) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::Arena;
+
+    #[test]
+    fn basic() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        let c = arena.insert(3);
+        assert_eq!(arena.remove(b), Some(2));
+        let d = arena.insert(4);
+        assert_ne!(b, d);
+        assert_eq!(arena.iter().map(|(_, v)| *v).collect::<Vec<_>>(), [1, 3, 4]);
+        let (x, y) = arena.get_pair_mut(c, a).unwrap();
+        std::mem::swap(x, y);
+        assert_eq!(arena[a], 3);
+        assert_eq!(arena[c], 1);
+        assert!(arena.get_pair_mut(a, a).is_none());
+        assert!(arena.get_pair_mut(a, b).is_none());
+    }
+
+    #[test]
+    fn free_list() {
+        let mut arena = Arena::with_free_list();
+        let a = arena.insert("a");
+        arena.insert("b");
+        arena.remove(a);
+        assert_eq!(arena.insert("c"), a);
+        assert_eq!(arena.len(), 2);
+        assert_eq!(
+            arena.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            ["c", "b"]
+        );
+    }
+}
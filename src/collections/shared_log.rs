@@ -0,0 +1,192 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+const BASE_BITS: u32 = 5;
+const BASE: usize = 1 << BASE_BITS;
+const SEGMENTS: usize = (usize::BITS - BASE_BITS) as usize;
+
+type Segment<T> = Box<[OnceLock<T>]>;
+
+fn locate(index: usize) -> (usize, usize) {
+    let i = index / BASE + 1;
+    let segment = (usize::BITS - 1 - i.leading_zeros()) as usize;
+    (segment, index - BASE * ((1 << segment) - 1))
+}
+
+/// A concurrent append-only log.
+///
+/// Many producers can [push](SharedLog::push) while many readers iterate using their own
+/// [Cursor]; readers never block writers. Storage is made of segments of doubling sizes which are
+/// allocated on demand and never moved, such that references to items remain valid for the
+/// lifetime of the log.
+pub struct SharedLog<T> {
+    next: AtomicUsize,
+    segments: [OnceLock<Segment<T>>; SEGMENTS],
+}
+
+impl<T> Default for SharedLog<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SharedLog<T> {
+    /// Creates a new empty log.
+    pub fn new() -> SharedLog<T> {
+        SharedLog {
+            next: AtomicUsize::new(0),
+            segments: std::array::from_fn(|_| OnceLock::new()),
+        }
+    }
+
+    fn slot(&self, index: usize) -> Option<&OnceLock<T>> {
+        let (segment, offset) = locate(index);
+        self.segments[segment].get().map(|v| &v[offset])
+    }
+
+    /// Appends an item at the end of this log.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the item to append.
+    ///
+    /// returns: usize the position of the item in the log.
+    pub fn push(&self, value: T) -> usize {
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        let (segment, offset) = locate(index);
+        let segment = self.segments[segment]
+            .get_or_init(|| (0..BASE << segment).map(|_| OnceLock::new()).collect());
+        let _ = segment[offset].set(value);
+        index
+    }
+
+    /// Gets an item from its position.
+    ///
+    /// Returns None if the position was not yet written.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+        self.slot(index)?.get()
+    }
+
+    /// Returns the number of positions reserved by producers, including items which may still
+    /// be in the process of being written.
+    pub fn len(&self) -> usize {
+        self.next.load(Ordering::Relaxed)
+    }
+
+    /// Returns true when this log is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates a new reader cursor starting at the beginning of this log.
+    pub fn cursor(&self) -> Cursor<'_, T> {
+        self.cursor_at(0)
+    }
+
+    /// Creates a new reader cursor starting at a given position.
+    pub fn cursor_at(&self, position: usize) -> Cursor<'_, T> {
+        Cursor {
+            log: self,
+            position,
+        }
+    }
+}
+
+/// A reader cursor over a [SharedLog].
+///
+/// The cursor stops at the first item which is not yet written, preserving log order; calling
+/// [next](Iterator::next) again later resumes reading once producers have caught up.
+#[derive(Clone)]
+pub struct Cursor<'a, T> {
+    log: &'a SharedLog<T>,
+    position: usize,
+}
+
+impl<T> Cursor<'_, T> {
+    /// Returns the position of the next item to be read.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl<'a, T> Iterator for Cursor<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.log.get(self.position)?;
+        self.position += 1;
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::SharedLog;
+
+    #[test]
+    fn basic() {
+        let log = SharedLog::new();
+        let mut cursor = log.cursor();
+        assert!(cursor.next().is_none());
+        for i in 0..100 {
+            assert_eq!(log.push(i), i);
+        }
+        assert_eq!(cursor.by_ref().take(40).count(), 40);
+        assert_eq!(cursor.next(), Some(&40));
+        assert_eq!(cursor.count(), 59);
+        assert_eq!(log.cursor_at(99).next(), Some(&99));
+    }
+
+    #[test]
+    fn concurrent() {
+        let log = SharedLog::new();
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let log = &log;
+                s.spawn(move || {
+                    for i in 0..1000 {
+                        log.push(t * 1000 + i);
+                    }
+                });
+            }
+            let mut cursor = log.cursor();
+            while cursor.position() < 4000 {
+                cursor.next();
+            }
+        });
+        let mut items: Vec<_> = log.cursor().copied().collect();
+        items.sort();
+        assert_eq!(items, (0..4000).collect::<Vec<_>>());
+    }
+}
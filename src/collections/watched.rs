@@ -0,0 +1,188 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// A wrapper around a container which tracks mutations with a monotonically increasing version.
+///
+/// Every mutation performed through [modify](Watched::modify) bumps the version and records the
+/// affected key, such that consumers can cheaply poll what changed since the last version they
+/// observed using [changed_since](Watched::changed_since).
+#[derive(Clone, Debug)]
+pub struct Watched<M, K> {
+    inner: M,
+    version: u64,
+    changes: Vec<(u64, K)>,
+}
+
+impl<M: Default, K> Default for Watched<M, K> {
+    fn default() -> Self {
+        Self::new(M::default())
+    }
+}
+
+impl<M, K> Watched<M, K> {
+    /// Creates a new [Watched](Watched) wrapper at version 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the container to watch.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            version: 0,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the underlying container.
+    pub fn get(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns the current version.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Extracts the underlying container.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Mutates the underlying container and records the mutation.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the key affected by the mutation.
+    /// * `f`: the function performing the mutation.
+    ///
+    /// returns: R the result of the mutation function.
+    pub fn modify<R, F: FnOnce(&mut M) -> R>(&mut self, key: K, f: F) -> R {
+        self.record(key);
+        f(&mut self.inner)
+    }
+
+    fn record(&mut self, key: K) {
+        self.version += 1;
+        self.changes.push((self.version, key));
+    }
+
+    /// Discards the change records up to and including the given version, to bound memory
+    /// usage once all consumers have observed it.
+    pub fn compact(&mut self, version: u64) {
+        let pos = self.changes.partition_point(|(v, _)| *v <= version);
+        self.changes.drain(..pos);
+    }
+}
+
+impl<M, K: Hash + Eq> Watched<M, K> {
+    /// Returns the keys affected by mutations which happened after the given version, without
+    /// duplicates, in order of first change.
+    ///
+    /// Changes which were discarded by [compact](Watched::compact) are not reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `version`: the last version observed by the consumer.
+    pub fn changed_since(&self, version: u64) -> Vec<&K> {
+        let pos = self.changes.partition_point(|(v, _)| *v <= version);
+        let mut set = HashSet::new();
+        self.changes[pos..]
+            .iter()
+            .map(|(_, k)| k)
+            .filter(|k| set.insert(*k))
+            .collect()
+    }
+}
+
+#[cfg(feature = "index-map")]
+mod index_map {
+    use crate::collections::Watched;
+    use crate::index_map::{Index, IndexMap};
+    use std::hash::BuildHasher;
+
+    type Key<V> = <<V as Index>::Key as ToOwned>::Owned;
+
+    impl<V: Index, S: BuildHasher> Watched<IndexMap<V, S>, Key<V>>
+    where
+        V::Key: ToOwned,
+    {
+        /// Inserts a new item in the watched [IndexMap] and records the change.
+        ///
+        /// Like [IndexMap::insert], nothing is changed nor recorded if an element with the same
+        /// key already exists.
+        ///
+        /// # Arguments
+        ///
+        /// * `value`: the value to be inserted.
+        pub fn insert(&mut self, value: V) {
+            let key = value.index().to_owned();
+            if self.inner.try_insert(value).is_ok() {
+                self.record(key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::Watched;
+
+    #[test]
+    fn basic() {
+        let mut watched = Watched::new(Vec::new());
+        watched.modify("a", |v| v.push(1));
+        let version = watched.version();
+        watched.modify("b", |v| v.push(2));
+        watched.modify("a", |v| v[0] = 3);
+        assert_eq!(watched.changed_since(0), [&"a", &"b"]);
+        assert_eq!(watched.changed_since(version), [&"b", &"a"]);
+        assert!(watched.changed_since(watched.version()).is_empty());
+        watched.compact(version);
+        assert_eq!(watched.changed_since(0), [&"b", &"a"]);
+        assert_eq!(watched.get(), &[3, 2]);
+    }
+
+    #[cfg(feature = "index-map")]
+    #[test]
+    fn index_map() {
+        use crate::index_map::tests::Named;
+        use crate::index_map::IndexMap;
+
+        let mut watched = Watched::new(IndexMap::new());
+        watched.insert(Named("a", 1));
+        assert_eq!(watched.changed_since(0), [&String::from("a")]);
+        let version = watched.version();
+        watched.insert(Named("a", 2));
+        assert_eq!(watched.version(), version);
+        assert!(watched.changed_since(version).is_empty());
+        assert_eq!(watched.get().get("a").map(|v| v.1), Some(1));
+    }
+}
@@ -28,7 +28,44 @@
 
 //! This module contains tools to simplify parsing environment variables.
 
+mod bitflags;
+mod cached;
+mod dirs;
+pub mod file;
+mod flags;
+mod resolve;
+mod set;
+mod snapshot;
+mod tree;
+mod units;
+mod validate;
+mod variant;
+
+pub use bitflags::FlagsError;
+pub use cached::CachedEnv;
+pub use dirs::Dirs;
+pub use flags::{flag_variable_name, FeatureFlags};
+pub use resolve::{Origin, Resolved};
+pub use set::{check, remove, set, set_bool, set_path, SetError};
+pub use snapshot::{snapshot, snapshot_prefix, Change, Snapshot};
+pub use tree::{tree, Tree};
+pub use units::{parse_duration, parse_size, UnitError};
+pub use validate::{conflicts, conflicts_in, require, require_in, ConflictError, RequireError};
+pub use variant::{EnumError, VariantList};
+
+use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
+use std::ops::BitOr;
+use std::str::FromStr;
+use std::time::Duration;
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "off" | "OFF" | "FALSE" | "false" | "0" => Some(false),
+        "on" | "ON" | "TRUE" | "true" | "1" => Some(true),
+        _ => None,
+    }
+}
 
 /// Gets the content of an environment variable.
 ///
@@ -48,9 +85,204 @@ pub fn get<T: AsRef<OsStr>>(name: T) -> Option<String> {
 ///
 /// Returns None if the variable does not exist or the format is unrecognized.
 pub fn get_bool<T: AsRef<OsStr>>(name: T) -> Option<bool> {
-    match &*get(name)? {
-        "off" | "OFF" | "FALSE" | "false" | "0" => Some(false),
-        "on" | "ON" | "TRUE" | "true" | "1" => Some(true),
-        _ => None,
+    parse_bool(&get(name)?)
+}
+
+/// Gets an environment variable parsed using [FromStr].
+///
+/// Returns None if the variable does not exist or could not be parsed.
+pub fn get_parse<R: FromStr, T: AsRef<OsStr>>(name: T) -> Option<R> {
+    get(name)?.parse().ok()
+}
+
+/// Gets a duration environment variable (ex: `500ms`, `1h30m`).
+///
+/// Returns None if the variable does not exist. See [parse_duration] for the format.
+pub fn get_duration<T: AsRef<OsStr>>(name: T) -> Option<Result<Duration, UnitError>> {
+    get(name).map(|v| parse_duration(&v))
+}
+
+/// Gets a size environment variable in bytes (ex: `64K`, `2MiB`).
+///
+/// Returns None if the variable does not exist. See [parse_size] for the format.
+pub fn get_size<T: AsRef<OsStr>>(name: T) -> Option<Result<u64, UnitError>> {
+    get(name).map(|v| parse_size(&v))
+}
+
+/// Gets an environment variable parsed against a fixed set of accepted values.
+///
+/// Returns None if the variable does not exist or is not valid UTF-8.
+pub fn get_enum<R: VariantList, T: AsRef<OsStr>>(name: T) -> Option<Result<R, EnumError>> {
+    Process.get_enum(name)
+}
+
+/// Gets a list of flags separated by commas or whitespace (ex: `render,net`) combined into a
+/// bitmask using a table of accepted flags. Flags are matched ignoring ASCII case.
+///
+/// Returns None if the variable does not exist or is not valid UTF-8.
+pub fn get_flags<F: Copy + Default + BitOr<Output = F>, T: AsRef<OsStr>>(
+    name: T,
+    table: &[(&'static str, F)],
+) -> Option<Result<F, FlagsError>> {
+    Process.get_flags(name, table)
+}
+
+/// Resolves a value from an explicit value (ex: parsed from command line arguments), then an
+/// environment variable parsed using [FromStr], then a default.
+///
+/// A variable which could not be parsed is ignored. The returned [Resolved] records which source
+/// won, so that it can be reported.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::env::resolve;
+///
+/// let port = resolve(None::<u16>, "BP3D_PORT_EXAMPLE", 8080);
+/// assert_eq!(port.value, 8080);
+/// println!("port {} from {}", port.value, port.origin);
+/// ```
+pub fn resolve<R: FromStr, T: AsRef<OsStr>>(
+    explicit: Option<R>,
+    name: T,
+    default: R,
+) -> Resolved<R> {
+    Process.resolve(explicit, name, default)
+}
+
+/// A source of variables which can be layered on top of other sources.
+pub trait Source {
+    /// Gets the content of a variable from this source.
+    ///
+    /// Returns None if the variable does not exist in this source.
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString>;
+
+    /// Gets the content of a variable from this source.
+    ///
+    /// Returns None if the variable does not exist or is not valid UTF-8.
+    fn get<T: AsRef<OsStr>>(&self, name: T) -> Option<String> {
+        self.get_os(name).and_then(|v| v.into_string().ok())
+    }
+
+    /// Gets a boolean variable from this source.
+    ///
+    /// Returns None if the variable does not exist or the format is unrecognized.
+    fn get_bool<T: AsRef<OsStr>>(&self, name: T) -> Option<bool> {
+        parse_bool(&self.get(name)?)
+    }
+
+    /// Gets a variable from this source parsed using [FromStr].
+    ///
+    /// Returns None if the variable does not exist or could not be parsed.
+    fn get_parse<R: FromStr, T: AsRef<OsStr>>(&self, name: T) -> Option<R> {
+        self.get(name)?.parse().ok()
+    }
+
+    /// Gets a duration variable from this source (ex: `500ms`, `1h30m`).
+    ///
+    /// Returns None if the variable does not exist. See [parse_duration] for the format.
+    fn get_duration<T: AsRef<OsStr>>(&self, name: T) -> Option<Result<Duration, UnitError>> {
+        self.get(name).map(|v| parse_duration(&v))
+    }
+
+    /// Gets a size variable in bytes from this source (ex: `64K`, `2MiB`).
+    ///
+    /// Returns None if the variable does not exist. See [parse_size] for the format.
+    fn get_size<T: AsRef<OsStr>>(&self, name: T) -> Option<Result<u64, UnitError>> {
+        self.get(name).map(|v| parse_size(&v))
+    }
+
+    /// Gets a variable from this source parsed against a fixed set of accepted values.
+    ///
+    /// Returns None if the variable does not exist or is not valid UTF-8.
+    fn get_enum<R: VariantList, T: AsRef<OsStr>>(&self, name: T) -> Option<Result<R, EnumError>> {
+        let value = self.get(name.as_ref())?;
+        Some(variant::parse_enum(&name.as_ref().to_string_lossy(), value))
+    }
+
+    /// Gets a list of flags from this source combined into a bitmask, see [get_flags].
+    ///
+    /// Returns None if the variable does not exist or is not valid UTF-8.
+    fn get_flags<F: Copy + Default + BitOr<Output = F>, T: AsRef<OsStr>>(
+        &self,
+        name: T,
+        table: &[(&'static str, F)],
+    ) -> Option<Result<F, FlagsError>> {
+        let value = self.get(name.as_ref())?;
+        let name = name.as_ref().to_string_lossy();
+        Some(bitflags::parse_flags(&name, &value, table))
+    }
+
+    /// Resolves a value from an explicit value, then a variable from this source, then a
+    /// default, see [resolve].
+    fn resolve<R: FromStr, T: AsRef<OsStr>>(
+        &self,
+        explicit: Option<R>,
+        name: T,
+        default: R,
+    ) -> Resolved<R> {
+        resolve::resolve_in(self, explicit, name, default)
+    }
+
+    /// Layers this source on top of another source, such that variables in this source
+    /// override variables in `other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: the source to fallback to when a variable is not found in this source.
+    ///
+    /// returns: Layered<Self, S>
+    fn over<S: Source>(self, other: S) -> Layered<Self, S>
+    where
+        Self: Sized,
+    {
+        Layered {
+            top: self,
+            bottom: other,
+        }
+    }
+}
+
+/// The process environment as a [Source].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Process;
+
+impl Source for Process {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        get_os(name)
+    }
+}
+
+/// A [Source] made of 2 layered sources.
+#[derive(Clone, Debug)]
+pub struct Layered<A, B> {
+    top: A,
+    bottom: B,
+}
+
+impl<A: Source, B: Source> Source for Layered<A, B> {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        self.top
+            .get_os(name.as_ref())
+            .or_else(|| self.bottom.get_os(name))
+    }
+}
+
+impl<S: Source> Source for &S {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        (**self).get_os(name)
+    }
+}
+
+impl<S: Source> Source for Option<S> {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        self.as_ref().and_then(|v| v.get_os(name))
+    }
+}
+
+impl Source for HashMap<String, String> {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        let name = name.as_ref().to_str()?;
+        HashMap::get(self, name).map(OsString::from)
     }
 }
@@ -0,0 +1,103 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+use std::ops::BitOr;
+
+/// The error returned when a flag list contains an unknown flag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlagsError {
+    /// The name of the variable.
+    pub name: String,
+
+    /// The unknown flag.
+    pub token: String,
+
+    /// The accepted flags.
+    pub allowed: Vec<&'static str>,
+}
+
+impl Display for FlagsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown flag '{}' in {}, expected any of: {}",
+            self.token,
+            self.name,
+            self.allowed.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for FlagsError {}
+
+pub(super) fn parse_flags<F: Copy + Default + BitOr<Output = F>>(
+    name: &str,
+    value: &str,
+    table: &[(&'static str, F)],
+) -> Result<F, FlagsError> {
+    let mut flags = F::default();
+    let tokens = value.split(|c: char| c == ',' || c.is_whitespace());
+    for token in tokens.filter(|v| !v.is_empty()) {
+        let (_, flag) = table
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(token))
+            .ok_or_else(|| FlagsError {
+                name: name.into(),
+                token: token.into(),
+                allowed: table.iter().map(|(name, _)| *name).collect(),
+            })?;
+        flags = flags | *flag;
+    }
+    Ok(flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::Source;
+    use std::collections::HashMap;
+
+    const TABLE: &[(&str, u32)] = &[("render", 1), ("net", 2), ("audio", 4)];
+
+    #[test]
+    fn flags() {
+        let source: HashMap<String, String> =
+            [("FLAGS", "render, NET"), ("EMPTY", ""), ("BAD", "net,gpu")]
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect();
+        assert_eq!(source.get_flags("FLAGS", TABLE), Some(Ok(3)));
+        assert_eq!(source.get_flags("EMPTY", TABLE), Some(Ok(0)));
+        assert_eq!(source.get_flags("MISSING", TABLE), None);
+        let err = source.get_flags("BAD", TABLE).unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown flag 'gpu' in BAD, expected any of: render, net, audio"
+        );
+    }
+}
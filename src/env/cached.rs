@@ -0,0 +1,129 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::env::{Process, Source};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::sync::RwLock;
+
+/// A [Source] which caches the variables of another source (by default the process
+/// environment), such that each variable is read only once.
+///
+/// This is intended for hot paths in long-running programs; use [invalidate](CachedEnv::invalidate)
+/// or [refresh](CachedEnv::refresh) to observe changes to the underlying source.
+#[derive(Debug, Default)]
+pub struct CachedEnv<S = Process> {
+    source: S,
+    cache: RwLock<HashMap<OsString, Option<OsString>>>,
+}
+
+impl CachedEnv {
+    /// Creates a new cache over the process environment.
+    pub fn new() -> CachedEnv {
+        CachedEnv::with_source(Process)
+    }
+}
+
+impl<S: Source> CachedEnv<S> {
+    /// Creates a new cache over the given source.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: the source to cache.
+    pub fn with_source(source: S) -> CachedEnv<S> {
+        CachedEnv {
+            source,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Invalidates a single cached variable, such that it is read again from the underlying
+    /// source on next access.
+    pub fn invalidate<T: AsRef<OsStr>>(&self, name: T) {
+        let mut cache = self.cache.write().unwrap_or_else(|e| e.into_inner());
+        cache.remove(name.as_ref());
+    }
+
+    /// Invalidates all cached variables.
+    pub fn refresh(&self) {
+        let mut cache = self.cache.write().unwrap_or_else(|e| e.into_inner());
+        cache.clear();
+    }
+}
+
+impl<S: Source> Source for CachedEnv<S> {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        let name = name.as_ref();
+        {
+            let cache = self.cache.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(value) = cache.get(name) {
+                return value.clone();
+            }
+        }
+        let value = self.source.get_os(name);
+        let mut cache = self.cache.write().unwrap_or_else(|e| e.into_inner());
+        cache.insert(name.into(), value.clone());
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{CachedEnv, Source};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::ffi::{OsStr, OsString};
+
+    struct Counting(RefCell<usize>, HashMap<String, String>);
+
+    impl Source for Counting {
+        fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+            *self.0.borrow_mut() += 1;
+            self.1.get_os(name)
+        }
+    }
+
+    #[test]
+    fn cached() {
+        let mut vars = HashMap::new();
+        vars.insert(String::from("A"), String::from("on"));
+        let env = CachedEnv::with_source(Counting(RefCell::new(0), vars));
+        assert_eq!(env.get_bool("A"), Some(true));
+        assert_eq!(env.get("A").as_deref(), Some("on"));
+        assert_eq!(env.get("B"), None);
+        assert_eq!(env.get("B"), None);
+        assert_eq!(*env.source.0.borrow(), 2);
+        env.invalidate("A");
+        env.get("A");
+        assert_eq!(*env.source.0.borrow(), 3);
+        env.refresh();
+        env.get("A");
+        env.get("B");
+        assert_eq!(*env.source.0.borrow(), 5);
+    }
+}
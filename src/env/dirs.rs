@@ -0,0 +1,118 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::env::{Process, Source};
+use std::path::PathBuf;
+
+/// The well-known directories of the current user, resolved only from environment variables.
+///
+/// On Unix, the XDG base directory variables are used with their standard fallbacks under
+/// `HOME` (and `~/Library` on macOS); on Windows, `APPDATA` and `LOCALAPPDATA` are used. The
+/// filesystem is never accessed, so the returned directories may not exist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dirs {
+    /// The directory for user-specific non-essential data (ex: `~/.cache`).
+    pub cache: PathBuf,
+
+    /// The directory for user-specific configuration files (ex: `~/.config`).
+    pub config: PathBuf,
+
+    /// The directory for user-specific data files (ex: `~/.local/share`).
+    pub data: PathBuf,
+}
+
+fn absolute<S: Source>(source: &S, name: &str) -> Option<PathBuf> {
+    source
+        .get_os(name)
+        .map(PathBuf::from)
+        .filter(|v| v.is_absolute())
+}
+
+impl Dirs {
+    /// Resolves the well-known directories from the process environment.
+    ///
+    /// Returns None if a required variable (ex: `HOME`) is missing or not absolute.
+    pub fn resolve() -> Option<Dirs> {
+        Dirs::resolve_from(&Process)
+    }
+
+    /// Resolves the well-known directories from a [Source].
+    ///
+    /// Returns None if a required variable (ex: `HOME`) is missing or not absolute.
+    pub fn resolve_from<S: Source>(source: &S) -> Option<Dirs> {
+        if cfg!(windows) {
+            let roaming = absolute(source, "APPDATA")?;
+            let local = absolute(source, "LOCALAPPDATA")?;
+            Some(Dirs {
+                cache: local.clone(),
+                config: roaming.clone(),
+                data: roaming,
+            })
+        } else if cfg!(target_os = "macos") {
+            let library = absolute(source, "HOME")?.join("Library");
+            Some(Dirs {
+                cache: absolute(source, "XDG_CACHE_HOME").unwrap_or_else(|| library.join("Caches")),
+                config: absolute(source, "XDG_CONFIG_HOME")
+                    .unwrap_or_else(|| library.join("Application Support")),
+                data: absolute(source, "XDG_DATA_HOME")
+                    .unwrap_or_else(|| library.join("Application Support")),
+            })
+        } else {
+            let home = absolute(source, "HOME");
+            let dir = |name, fallback| {
+                absolute(source, name).or_else(|| home.as_ref().map(|v| v.join(fallback)))
+            };
+            Some(Dirs {
+                cache: dir("XDG_CACHE_HOME", ".cache")?,
+                config: dir("XDG_CONFIG_HOME", ".config")?,
+                data: dir("XDG_DATA_HOME", ".local/share")?,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::Dirs;
+    use std::collections::HashMap;
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn xdg() {
+        let mut vars = HashMap::new();
+        vars.insert(String::from("HOME"), String::from("/home/user"));
+        vars.insert(String::from("XDG_CACHE_HOME"), String::from("/tmp/cache"));
+        vars.insert(String::from("XDG_DATA_HOME"), String::from("relative"));
+        let dirs = Dirs::resolve_from(&vars).unwrap();
+        assert_eq!(dirs.cache.to_str(), Some("/tmp/cache"));
+        assert_eq!(dirs.config.to_str(), Some("/home/user/.config"));
+        assert_eq!(dirs.data.to_str(), Some("/home/user/.local/share"));
+        vars.remove("HOME");
+        assert!(Dirs::resolve_from(&vars).is_none());
+    }
+}
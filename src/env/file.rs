@@ -0,0 +1,355 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Loader for per-profile variable override files (ex: `bp3d.env.toml`).
+//!
+//! The format is a small subset of TOML/INI:
+//!
+//! ```text
+//! # Comments start with '#' or ';', including after a value.
+//! [default]
+//! BP3D_LOG = "info"
+//!
+//! [dev]
+//! BP3D_LOG = debug ; more verbose
+//! ```
+//!
+//! Quoted values are taken literally, without escapes.
+//!
+//! Variables declared before any section belong to the `default` profile.
+//!
+//! This module also loads `.env` files, see [DotEnv].
+
+use crate::env::{Layered, Process, Source};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::fmt::Display;
+use std::path::Path;
+
+/// The name of the profile which all other profiles inherit from.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Possible errors when loading a profiles file.
+#[derive(Debug)]
+pub enum Error {
+    /// An Io error.
+    Io(std::io::Error),
+
+    /// A line could not be parsed; includes the 1-based line number.
+    Syntax(usize),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "io error: {}", e),
+            Error::Syntax(line) => write!(f, "syntax error at line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A single profile: a set of variables.
+#[derive(Clone, Debug, Default)]
+pub struct Profile(HashMap<String, String>);
+
+impl Profile {
+    /// Returns an iterator over all variables declared in this profile.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (&**k, &**v))
+    }
+}
+
+impl Source for Profile {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        self.0.get_os(name)
+    }
+}
+
+/// A set of profiles loaded from an override file.
+#[derive(Clone, Debug, Default)]
+pub struct Profiles(HashMap<String, Profile>);
+
+fn parse_profile_value(value: &str) -> Option<&str> {
+    match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let (value, rest) = value[1..].split_once(quote)?;
+            let rest = rest.trim_start();
+            (rest.is_empty() || rest.starts_with('#') || rest.starts_with(';')).then_some(value)
+        }
+        Some('#' | ';') => Some(""),
+        _ => {
+            let end = [" #", " ;"]
+                .iter()
+                .filter_map(|v| value.find(v))
+                .min()
+                .unwrap_or(value.len());
+            Some(value[..end].trim_end())
+        }
+    }
+}
+
+impl Profiles {
+    /// Parses a profiles file from its content.
+    ///
+    /// # Arguments
+    ///
+    /// * `content`: the content of the file to parse.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](Error) if a line could not be parsed.
+    pub fn parse(content: &str) -> Result<Profiles, Error> {
+        let mut profiles = Profiles::default();
+        let mut current = DEFAULT_PROFILE;
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name.strip_suffix(']').ok_or(Error::Syntax(i + 1))?.trim();
+                if name.is_empty() {
+                    return Err(Error::Syntax(i + 1));
+                }
+                current = name;
+                profiles.0.entry(current.into()).or_default();
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or(Error::Syntax(i + 1))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(Error::Syntax(i + 1));
+            }
+            let value = parse_profile_value(value.trim()).ok_or(Error::Syntax(i + 1))?;
+            profiles
+                .0
+                .entry(current.into())
+                .or_default()
+                .0
+                .insert(key.into(), value.into());
+        }
+        Ok(profiles)
+    }
+
+    /// Gets a profile by name.
+    ///
+    /// Returns None if the profile does not exist.
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.0.get(name)
+    }
+
+    /// Selects a profile, returning a [Source] which looks up variables in the named profile
+    /// first and falls back to the `default` profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: the name of the profile to select (ex: `dev` or `release`).
+    pub fn select(&self, name: &str) -> impl Source + '_ {
+        self.get(name).over(self.get(DEFAULT_PROFILE))
+    }
+}
+
+/// Loads a profiles file.
+///
+/// # Arguments
+///
+/// * `path`: the path to the file to load.
+///
+/// # Errors
+///
+/// Returns an [Error](Error) if the file could not be read or parsed.
+pub fn load_profiles<P: AsRef<Path>>(path: P) -> Result<Profiles, Error> {
+    let content = std::fs::read_to_string(path).map_err(Error::Io)?;
+    Profiles::parse(&content)
+}
+
+/// Variables loaded from a `.env` file.
+///
+/// The format is the common subset supported by dotenv implementations:
+///
+/// ```text
+/// # Comments start with '#', including after an unquoted value or in place of it.
+/// export BP3D_LOG=debug
+/// BP3D_NAME="my tool" # double quotes support \n, \t, \" and \\ escapes
+/// BP3D_PATTERN='$literal\n'
+/// ```
+///
+/// Quoted values must end on the same line and no variable expansion is performed.
+#[derive(Clone, Debug, Default)]
+pub struct DotEnv(HashMap<String, String>);
+
+fn parse_dotenv_value(value: &str) -> Option<String> {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some('\'') => {
+            let (value, rest) = chars.as_str().split_once('\'')?;
+            check_trailing(rest)?;
+            Some(value.into())
+        }
+        Some('"') => {
+            let mut out = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => out.push(match chars.next()? {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        c => c,
+                    }),
+                    c => out.push(c),
+                }
+            }
+            check_trailing(chars.as_str())?;
+            Some(out)
+        }
+        Some('#') => Some(String::new()),
+        _ => {
+            let end = value.find(" #").unwrap_or(value.len());
+            Some(value[..end].trim_end().into())
+        }
+    }
+}
+
+fn check_trailing(rest: &str) -> Option<()> {
+    let rest = rest.trim_start();
+    (rest.is_empty() || rest.starts_with('#')).then_some(())
+}
+
+impl DotEnv {
+    /// Parses a `.env` file from its content.
+    ///
+    /// When a variable is declared multiple times, the last declaration wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `content`: the content of the file to parse.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](Error) if a line could not be parsed.
+    pub fn parse(content: &str) -> Result<DotEnv, Error> {
+        let mut vars = HashMap::new();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=').ok_or(Error::Syntax(i + 1))?;
+            let key = key.trim();
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return Err(Error::Syntax(i + 1));
+            }
+            let value = parse_dotenv_value(value.trim_start()).ok_or(Error::Syntax(i + 1))?;
+            vars.insert(key.into(), value);
+        }
+        Ok(DotEnv(vars))
+    }
+
+    /// Returns an iterator over all variables declared in this file.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (&**k, &**v))
+    }
+}
+
+impl Source for DotEnv {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        self.0.get_os(name)
+    }
+}
+
+/// Loads a `.env` file and layers it under the process environment, such that variables set
+/// in the process environment override the ones declared in the file.
+///
+/// The process environment itself is not modified.
+///
+/// # Arguments
+///
+/// * `path`: the path to the file to load.
+///
+/// # Errors
+///
+/// Returns an [Error](Error) if the file could not be read or parsed.
+pub fn load_dotenv<P: AsRef<Path>>(path: P) -> Result<Layered<Process, DotEnv>, Error> {
+    let content = std::fs::read_to_string(path).map_err(Error::Io)?;
+    Ok(Process.over(DotEnv::parse(&content)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::file::{DotEnv, Profiles};
+    use crate::env::Source;
+    use std::collections::HashMap;
+
+    #[test]
+    fn basic() {
+        let profiles = Profiles::parse(
+            "# comment\nA = 1\n[default]\nB = \"default\"\n[dev]\nB = 'dev'\nC=on\n",
+        )
+        .unwrap();
+        let dev = profiles.select("dev");
+        assert_eq!(dev.get("A").as_deref(), Some("1"));
+        assert_eq!(dev.get("B").as_deref(), Some("dev"));
+        assert_eq!(dev.get_bool("C"), Some(true));
+        let release = profiles.select("release");
+        assert_eq!(release.get("B").as_deref(), Some("default"));
+        assert_eq!(release.get("C"), None);
+        assert!(Profiles::parse("[dev\n").is_err());
+        assert!(Profiles::parse("novalue\n").is_err());
+        assert!(Profiles::parse("D = \"x\" y\n").is_err());
+        let profiles = Profiles::parse("A = debug # note\nB = 'a # b' ; note\nC = ;\n").unwrap();
+        let default = profiles.select("default");
+        assert_eq!(default.get("A").as_deref(), Some("debug"));
+        assert_eq!(default.get("B").as_deref(), Some("a # b"));
+        assert_eq!(default.get("C").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn dotenv() {
+        let env = DotEnv::parse(
+            "# comment\nexport A=1\nB = plain value # note\nC=\"a \\\"b\\\"\\n\" # x\nD='$x\\n'\nE=\nF=#x\n",
+        )
+        .unwrap();
+        assert_eq!(env.get("A").as_deref(), Some("1"));
+        assert_eq!(env.get("B").as_deref(), Some("plain value"));
+        assert_eq!(env.get("C").as_deref(), Some("a \"b\"\n"));
+        assert_eq!(env.get("D").as_deref(), Some("$x\\n"));
+        assert_eq!(env.get("E").as_deref(), Some(""));
+        assert_eq!(env.get("F").as_deref(), Some(""));
+        let process = HashMap::from([("A".to_string(), "process".to_string())]);
+        let layered = process.over(env);
+        assert_eq!(layered.get("A").as_deref(), Some("process"));
+        assert_eq!(layered.get("B").as_deref(), Some("plain value"));
+        assert!(DotEnv::parse("A=\"open\n").is_err());
+        assert!(DotEnv::parse("A='x' y\n").is_err());
+        assert!(DotEnv::parse("A B=1\n").is_err());
+    }
+}
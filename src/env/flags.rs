@@ -0,0 +1,137 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::env::{Process, Source};
+use std::collections::HashMap;
+
+/// A set of boolean feature flags read once from environment variables.
+///
+/// Each flag is read from a variable named after the prefix followed by the flag name in upper
+/// case with `-` and `.` replaced by `_` (ex: flag `fast-path` with prefix `BP3D_FEATURE_` is
+/// read from `BP3D_FEATURE_FAST_PATH`), such that all tools use the same spelling.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::env::FeatureFlags;
+///
+/// const DEFAULTS: &[(&str, bool)] = &[("fast-path", true), ("experimental", false)];
+/// let flags = FeatureFlags::load("BP3D_FEATURE_", DEFAULTS);
+/// if flags.is_enabled("fast-path") {
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FeatureFlags(HashMap<&'static str, bool>);
+
+/// Returns the name of the environment variable used for a flag.
+///
+/// # Arguments
+///
+/// * `prefix`: the prefix of all flag variables (ex: `BP3D_FEATURE_`).
+/// * `name`: the name of the flag (ex: `fast-path`).
+pub fn flag_variable_name(prefix: &str, name: &str) -> String {
+    let mut var = String::with_capacity(prefix.len() + name.len());
+    var.push_str(prefix);
+    var.extend(name.chars().map(|c| match c {
+        '-' | '.' => '_',
+        c => c.to_ascii_uppercase(),
+    }));
+    var
+}
+
+impl FeatureFlags {
+    /// Reads all flags from the process environment.
+    ///
+    /// Flags whose variable is not set or not a recognized boolean keep their default value.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix`: the prefix of all flag variables (ex: `BP3D_FEATURE_`).
+    /// * `defaults`: the table of all known flags with their default value.
+    pub fn load(prefix: &str, defaults: &[(&'static str, bool)]) -> FeatureFlags {
+        FeatureFlags::load_from(Process, prefix, defaults)
+    }
+
+    /// Reads all flags from a [Source].
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: the source to read from.
+    /// * `prefix`: the prefix of all flag variables (ex: `BP3D_FEATURE_`).
+    /// * `defaults`: the table of all known flags with their default value.
+    pub fn load_from<S: Source>(
+        source: S,
+        prefix: &str,
+        defaults: &[(&'static str, bool)],
+    ) -> FeatureFlags {
+        FeatureFlags(
+            defaults
+                .iter()
+                .map(|(name, default)| {
+                    let value = source.get_bool(flag_variable_name(prefix, name));
+                    (*name, value.unwrap_or(*default))
+                })
+                .collect(),
+        )
+    }
+
+    /// Checks if a flag is enabled; unknown flags are always disabled.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.0.get(name).copied().unwrap_or_default()
+    }
+
+    /// Returns an iterator over all known flags with their value.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, bool)> + '_ {
+        self.0.iter().map(|(k, v)| (*k, *v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{flag_variable_name, FeatureFlags};
+    use std::collections::HashMap;
+
+    #[test]
+    fn flags() {
+        assert_eq!(
+            flag_variable_name("APP_", "fast-path.v2"),
+            "APP_FAST_PATH_V2"
+        );
+        let mut vars = HashMap::new();
+        vars.insert(String::from("APP_A"), String::from("off"));
+        vars.insert(String::from("APP_B"), String::from("1"));
+        vars.insert(String::from("APP_C"), String::from("maybe"));
+        let flags =
+            FeatureFlags::load_from(&vars, "APP_", &[("a", true), ("b", false), ("c", true)]);
+        assert!(!flags.is_enabled("a"));
+        assert!(flags.is_enabled("b"));
+        assert!(flags.is_enabled("c"));
+        assert!(!flags.is_enabled("d"));
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::env::Source;
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The source a [Resolved] value was taken from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Origin {
+    /// The value was given explicitly (ex: from command line arguments).
+    Explicit,
+
+    /// The value was read from a variable, with its name.
+    Variable(String),
+
+    /// No value was found, the default was used.
+    Default,
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Explicit => f.write_str("explicit value"),
+            Origin::Variable(name) => write!(f, "variable {}", name),
+            Origin::Default => f.write_str("default value"),
+        }
+    }
+}
+
+/// A value resolved from an explicit value, a variable or a default, see [resolve].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resolved<T> {
+    /// The resolved value.
+    pub value: T,
+
+    /// Where the value was taken from.
+    pub origin: Origin,
+}
+
+impl<T> Resolved<T> {
+    /// Extracts the resolved value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+pub(super) fn resolve_in<R: FromStr, T: AsRef<OsStr>, S: Source + ?Sized>(
+    source: &S,
+    explicit: Option<R>,
+    name: T,
+    default: R,
+) -> Resolved<R> {
+    if let Some(value) = explicit {
+        return Resolved {
+            value,
+            origin: Origin::Explicit,
+        };
+    }
+    match source.get_parse(name.as_ref()) {
+        Some(value) => Resolved {
+            value,
+            origin: Origin::Variable(name.as_ref().to_string_lossy().into_owned()),
+        },
+        None => Resolved {
+            value: default,
+            origin: Origin::Default,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{Origin, Source};
+    use std::collections::HashMap;
+
+    #[test]
+    fn chain() {
+        let vars = HashMap::from([
+            ("PORT".to_string(), "9000".to_string()),
+            ("BAD".to_string(), "x".to_string()),
+        ]);
+        let v = vars.resolve(Some(1u16), "PORT", 8080);
+        assert_eq!((v.value, v.origin), (1, Origin::Explicit));
+        let v = vars.resolve(None::<u16>, "PORT", 8080);
+        assert_eq!(v.value, 9000);
+        assert_eq!(v.origin.to_string(), "variable PORT");
+        let v = vars.resolve(None::<u16>, "BAD", 8080);
+        assert_eq!((v.value, v.origin), (8080, Origin::Default));
+        assert_eq!(vars.resolve(None, "NONE", 8080u16).into_inner(), 8080);
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// The error returned when a variable cannot be set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SetError {
+    /// The name is empty or contains an `=` or NUL character.
+    InvalidName,
+
+    /// The value contains a NUL character.
+    InvalidValue,
+
+    /// A path contains the platform path list separator.
+    InvalidPath,
+}
+
+impl Display for SetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetError::InvalidName => f.write_str("invalid variable name"),
+            SetError::InvalidValue => f.write_str("variable value contains a NUL character"),
+            SetError::InvalidPath => f.write_str("path contains a path list separator"),
+        }
+    }
+}
+
+impl std::error::Error for SetError {}
+
+fn contains(value: &OsStr, byte: u8) -> bool {
+    value.as_encoded_bytes().contains(&byte)
+}
+
+/// Checks that a variable can be set to a value without panicking.
+pub fn check<N: AsRef<OsStr>, V: AsRef<OsStr>>(name: N, value: V) -> Result<(), SetError> {
+    let name = name.as_ref();
+    if name.is_empty() || contains(name, b'=') || contains(name, 0) {
+        return Err(SetError::InvalidName);
+    }
+    if contains(value.as_ref(), 0) {
+        return Err(SetError::InvalidValue);
+    }
+    Ok(())
+}
+
+/// Sets an environment variable after validating its name and value.
+///
+/// # Safety
+///
+/// This calls [std::env::set_var] which is not thread-safe on most platforms other than
+/// Windows: no other thread may read or write the environment (including through libc
+/// functions such as `getaddrinfo`) while this function runs. Prefer
+/// [Command::env](std::process::Command::env) when configuring child processes.
+///
+/// # Errors
+///
+/// Returns a [SetError] if the name or value is invalid, in which case the environment is left
+/// untouched.
+pub unsafe fn set<N: AsRef<OsStr>, V: AsRef<OsStr>>(name: N, value: V) -> Result<(), SetError> {
+    check(&name, &value)?;
+    std::env::set_var(name, value);
+    Ok(())
+}
+
+/// Sets a boolean environment variable, as `1` or `0` such that it can be read back with
+/// [get_bool](crate::env::get_bool).
+///
+/// # Safety
+///
+/// See [set].
+pub unsafe fn set_bool<N: AsRef<OsStr>>(name: N, value: bool) -> Result<(), SetError> {
+    set(name, if value { "1" } else { "0" })
+}
+
+/// Sets a list of paths environment variable (ex: `PATH`), joined with the platform path list
+/// separator.
+///
+/// # Safety
+///
+/// See [set].
+///
+/// # Errors
+///
+/// Returns [InvalidPath](SetError::InvalidPath) if one of the paths contains the separator.
+pub unsafe fn set_path<N: AsRef<OsStr>, I: IntoIterator<Item = P>, P: AsRef<Path>>(
+    name: N,
+    paths: I,
+) -> Result<(), SetError> {
+    let value = std::env::join_paths(paths.into_iter().map(|v| v.as_ref().to_path_buf()))
+        .map_err(|_| SetError::InvalidPath)?;
+    set(name, value)
+}
+
+/// Removes an environment variable.
+///
+/// # Safety
+///
+/// See [set].
+pub unsafe fn remove<N: AsRef<OsStr>>(name: N) -> Result<(), SetError> {
+    check(&name, "")?;
+    std::env::remove_var(name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{check, set_bool, set_path, SetError};
+
+    #[test]
+    fn validation() {
+        assert_eq!(check("", "a"), Err(SetError::InvalidName));
+        assert_eq!(check("A=B", "a"), Err(SetError::InvalidName));
+        assert_eq!(check("A", "a\0"), Err(SetError::InvalidValue));
+        assert!(check("A", "").is_ok());
+        let res = unsafe { set_path("BP3D_UTIL_SET_PATH", ["a", "b:c;d"]) };
+        assert_eq!(res, Err(SetError::InvalidPath));
+    }
+
+    #[test]
+    fn round_trip() {
+        unsafe { set_bool("BP3D_UTIL_SET_BOOL", true) }.unwrap();
+        assert_eq!(crate::env::get_bool("BP3D_UTIL_SET_BOOL"), Some(true));
+        unsafe { crate::env::remove("BP3D_UTIL_SET_BOOL") }.unwrap();
+        assert_eq!(crate::env::get_bool("BP3D_UTIL_SET_BOOL"), None);
+    }
+}
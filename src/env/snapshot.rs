@@ -0,0 +1,163 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::env::Source;
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+
+/// A change between 2 [Snapshot]s.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Change<'a> {
+    /// A variable was added, with its new value.
+    Added(&'a OsStr, &'a OsStr),
+
+    /// A variable was removed, with its old value.
+    Removed(&'a OsStr, &'a OsStr),
+
+    /// A variable was changed.
+    Changed {
+        /// The name of the variable.
+        name: &'a OsStr,
+
+        /// The old value of the variable.
+        old: &'a OsStr,
+
+        /// The new value of the variable.
+        new: &'a OsStr,
+    },
+}
+
+/// A point-in-time copy of a set of environment variables, sorted by name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<OsString, OsString>);
+
+impl Snapshot {
+    /// Returns the number of variables in this snapshot.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true when this snapshot is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over all variables in this snapshot, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&OsStr, &OsStr)> {
+        self.0.iter().map(|(k, v)| (&**k, &**v))
+    }
+
+    /// Computes the list of changes needed to go from this snapshot to `other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: the newer snapshot to compare with.
+    ///
+    /// returns: Vec<Change> the changes sorted by variable name.
+    pub fn diff<'a>(&'a self, other: &'a Snapshot) -> Vec<Change<'a>> {
+        let mut changes = Vec::new();
+        for (name, old) in &self.0 {
+            match other.0.get(name) {
+                None => changes.push(Change::Removed(name, old)),
+                Some(new) if new != old => changes.push(Change::Changed { name, old, new }),
+                _ => (),
+            }
+        }
+        for (name, new) in &other.0 {
+            if !self.0.contains_key(name) {
+                changes.push(Change::Added(name, new));
+            }
+        }
+        changes.sort_by_key(|v| match v {
+            Change::Added(name, _) | Change::Removed(name, _) => *name,
+            Change::Changed { name, .. } => *name,
+        });
+        changes
+    }
+}
+
+impl FromIterator<(OsString, OsString)> for Snapshot {
+    fn from_iter<T: IntoIterator<Item = (OsString, OsString)>>(iter: T) -> Self {
+        Snapshot(iter.into_iter().collect())
+    }
+}
+
+impl Source for Snapshot {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        self.0.get(name.as_ref()).cloned()
+    }
+}
+
+/// Captures all variables of the process environment.
+pub fn snapshot() -> Snapshot {
+    std::env::vars_os().collect()
+}
+
+/// Captures all variables of the process environment whose name starts with the given prefix.
+///
+/// # Arguments
+///
+/// * `prefix`: the prefix of the variables to capture (ex: `BP3D_`).
+pub fn snapshot_prefix<T: AsRef<OsStr>>(prefix: T) -> Snapshot {
+    let prefix = prefix.as_ref().as_encoded_bytes();
+    std::env::vars_os()
+        .filter(|(k, _)| k.as_encoded_bytes().starts_with(prefix))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{Change, Snapshot};
+    use std::ffi::{OsStr, OsString};
+
+    fn snap(vars: &[(&str, &str)]) -> Snapshot {
+        vars.iter()
+            .map(|(k, v)| (OsString::from(k), OsString::from(v)))
+            .collect()
+    }
+
+    #[test]
+    fn diff() {
+        let a = snap(&[("A", "1"), ("B", "2"), ("C", "3")]);
+        let b = snap(&[("B", "2"), ("C", "4"), ("D", "5")]);
+        let s = OsStr::new;
+        assert_eq!(
+            a.diff(&b),
+            [
+                Change::Removed(s("A"), s("1")),
+                Change::Changed {
+                    name: s("C"),
+                    old: s("3"),
+                    new: s("4")
+                },
+                Change::Added(s("D"), s("5"))
+            ]
+        );
+        assert!(a.diff(&a).is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::env::{flag_variable_name, snapshot_prefix, Snapshot, Source};
+use std::collections::BTreeSet;
+use std::ffi::{OsStr, OsString};
+use std::sync::Arc;
+
+/// A hierarchical view over flat variables, where each level of the hierarchy is separated by
+/// `_` (ex: `["logging", "level"]` maps to `APP_LOGGING_LEVEL`).
+///
+/// Names are converted to variable names by upper-casing them and replacing `-` and `.` with
+/// `_`. A [Tree] is also a [Source] whose variable names are relative to the tree, such that
+/// all typed getters are available on sections.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::env::{Snapshot, Source, Tree};
+///
+/// let vars: Snapshot = [("APP_LOGGING_LEVEL", "debug"), ("APP_HTTP_PORT", "8080")]
+///     .into_iter()
+///     .map(|(k, v)| (k.into(), v.into()))
+///     .collect();
+/// let tree = Tree::new(vars, "APP");
+/// assert_eq!(tree.get_path(["logging", "level"]).as_deref(), Some("debug"));
+/// assert_eq!(tree.section("http").get_parse::<u16, _>("port"), Some(8080));
+/// assert_eq!(tree.sections(), ["HTTP", "LOGGING"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tree {
+    vars: Arc<Snapshot>,
+    prefix: String,
+}
+
+/// Captures all variables of the process environment under a prefix (ex: `APP`) as a [Tree].
+pub fn tree(prefix: &str) -> Tree {
+    let prefix = root_prefix(prefix);
+    Tree {
+        vars: Arc::new(snapshot_prefix(&prefix)),
+        prefix,
+    }
+}
+
+fn root_prefix(prefix: &str) -> String {
+    let mut prefix = flag_variable_name("", prefix);
+    if !prefix.is_empty() && !prefix.ends_with('_') {
+        prefix.push('_');
+    }
+    prefix
+}
+
+impl Tree {
+    /// Creates a new [Tree] from a [Snapshot] of variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `vars`: the variables.
+    /// * `prefix`: the prefix of the root of the tree (ex: `APP`), a trailing `_` is optional.
+    pub fn new(vars: Snapshot, prefix: &str) -> Tree {
+        Tree {
+            vars: Arc::new(vars),
+            prefix: root_prefix(prefix),
+        }
+    }
+
+    /// Returns the prefix of all variables of this tree (ex: `APP_LOGGING_`).
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the name of the variable for a path relative to this tree.
+    pub fn variable_name<'a, I: IntoIterator<Item = &'a str>>(&self, path: I) -> String {
+        let mut name = self.prefix.clone();
+        for (i, segment) in path.into_iter().enumerate() {
+            if i > 0 {
+                name.push('_');
+            }
+            name = flag_variable_name(&name, segment);
+        }
+        name
+    }
+
+    /// Gets the value of a variable from a path relative to this tree.
+    ///
+    /// Returns None if the variable does not exist or is not valid UTF-8.
+    pub fn get_path<'a, I: IntoIterator<Item = &'a str>>(&self, path: I) -> Option<String> {
+        self.vars.get(self.variable_name(path))
+    }
+
+    /// Returns the sub-tree of a section of this tree.
+    pub fn section(&self, name: &str) -> Tree {
+        Tree {
+            vars: self.vars.clone(),
+            prefix: flag_variable_name(&self.prefix, name) + "_",
+        }
+    }
+
+    /// Returns the sorted names of all sections directly under this tree.
+    ///
+    /// A section is the first `_` separated segment of a variable name after the prefix of
+    /// this tree, when it is followed by at least another segment.
+    pub fn sections(&self) -> Vec<String> {
+        let prefix = self.prefix.as_bytes();
+        let sections: BTreeSet<&str> = self
+            .vars
+            .iter()
+            .filter_map(|(name, _)| name.to_str())
+            .filter(|name| name.as_bytes().starts_with(prefix))
+            .filter_map(|name| name[prefix.len()..].split_once('_'))
+            .map(|(section, _)| section)
+            .filter(|section| !section.is_empty())
+            .collect();
+        sections.into_iter().map(String::from).collect()
+    }
+}
+
+impl Source for Tree {
+    fn get_os<T: AsRef<OsStr>>(&self, name: T) -> Option<OsString> {
+        let name = flag_variable_name(&self.prefix, &name.as_ref().to_string_lossy());
+        self.vars.get_os(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{Snapshot, Source, Tree};
+
+    #[test]
+    fn navigation() {
+        let vars: Snapshot = [
+            ("APP_LOGGING_LEVEL", "debug"),
+            ("APP_LOGGING_FILE_PATH", "/var/log/app"),
+            ("APP_PORT", "80"),
+            ("OTHER_X_Y", "1"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect();
+        let tree = Tree::new(vars, "app_");
+        assert_eq!(tree.prefix(), "APP_");
+        assert_eq!(
+            tree.variable_name(["log-file", "path"]),
+            "APP_LOG_FILE_PATH"
+        );
+        assert_eq!(tree.get_path(["port"]).as_deref(), Some("80"));
+        assert_eq!(tree.sections(), ["LOGGING"]);
+        let logging = tree.section("logging");
+        assert_eq!(logging.get("level").as_deref(), Some("debug"));
+        assert_eq!(logging.sections(), ["FILE"]);
+        assert_eq!(
+            logging.section("file").get("path").as_deref(),
+            Some("/var/log/app")
+        );
+        assert!(tree.get_path(["missing"]).is_none());
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// An error when parsing a duration or a size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnitError {
+    /// The value is empty.
+    Empty,
+
+    /// A number is missing or invalid.
+    InvalidNumber,
+
+    /// A unit is missing or unrecognized.
+    InvalidUnit(String),
+
+    /// The value does not fit in the target type.
+    Overflow,
+}
+
+impl Display for UnitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnitError::Empty => f.write_str("empty value"),
+            UnitError::InvalidNumber => f.write_str("missing or invalid number"),
+            UnitError::InvalidUnit(unit) if unit.is_empty() => f.write_str("missing unit"),
+            UnitError::InvalidUnit(unit) => write!(f, "unknown unit '{}'", unit),
+            UnitError::Overflow => f.write_str("value too large"),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+fn split_number(value: &str) -> Result<(u64, &str), UnitError> {
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    if end == 0 {
+        return Err(UnitError::InvalidNumber);
+    }
+    let number = value[..end].parse().map_err(|_| UnitError::Overflow)?;
+    Ok((number, &value[end..]))
+}
+
+/// Parses a duration made of one or more components with a unit (ex: `500ms`, `2m`, `1h30m`).
+///
+/// Supported units are `ns`, `us`, `ms`, `s`, `m`, `h` and `d`. The value `0` is accepted
+/// without a unit.
+///
+/// # Arguments
+///
+/// * `value`: the value to parse.
+///
+/// returns: Result<Duration, UnitError>
+pub fn parse_duration(value: &str) -> Result<Duration, UnitError> {
+    let mut value = value.trim();
+    if value.is_empty() {
+        return Err(UnitError::Empty);
+    }
+    if value == "0" {
+        return Ok(Duration::ZERO);
+    }
+    let mut total = Duration::ZERO;
+    while !value.is_empty() {
+        let (number, rest) = split_number(value)?;
+        let end = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let component = match &rest[..end] {
+            "ns" => Duration::from_nanos(number),
+            "us" => Duration::from_micros(number),
+            "ms" => Duration::from_millis(number),
+            "s" => Duration::from_secs(number),
+            "m" => number
+                .checked_mul(60)
+                .map(Duration::from_secs)
+                .ok_or(UnitError::Overflow)?,
+            "h" => number
+                .checked_mul(3600)
+                .map(Duration::from_secs)
+                .ok_or(UnitError::Overflow)?,
+            "d" => number
+                .checked_mul(86400)
+                .map(Duration::from_secs)
+                .ok_or(UnitError::Overflow)?,
+            unit => return Err(UnitError::InvalidUnit(unit.into())),
+        };
+        total = total.checked_add(component).ok_or(UnitError::Overflow)?;
+        value = &rest[end..];
+    }
+    Ok(total)
+}
+
+/// Parses a size in bytes with an optional unit (ex: `512`, `64K`, `2MiB`, `1GB`).
+///
+/// Units are case-insensitive: `B` is bytes, `K`, `M`, `G` and `T` and their `KiB`, `MiB`,
+/// `GiB` and `TiB` forms are powers of 1024, while `KB`, `MB`, `GB` and `TB` are powers of 1000.
+///
+/// # Arguments
+///
+/// * `value`: the value to parse.
+///
+/// returns: Result<u64, UnitError>
+pub fn parse_size(value: &str) -> Result<u64, UnitError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(UnitError::Empty);
+    }
+    let (number, unit) = split_number(value)?;
+    let unit = unit.trim_start();
+    let multiplier: u64 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        _ => return Err(UnitError::InvalidUnit(unit.into())),
+    };
+    number.checked_mul(multiplier).ok_or(UnitError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{parse_duration, parse_size, UnitError};
+    use std::time::Duration;
+
+    #[test]
+    fn duration() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration(" 0 "), Ok(Duration::ZERO));
+        assert_eq!(parse_duration(""), Err(UnitError::Empty));
+        assert_eq!(parse_duration("10"), Err(UnitError::InvalidUnit("".into())));
+        assert_eq!(
+            parse_duration("1x"),
+            Err(UnitError::InvalidUnit("x".into()))
+        );
+        assert_eq!(parse_duration("h"), Err(UnitError::InvalidNumber));
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(parse_size("64K"), Ok(65536));
+        assert_eq!(parse_size("2MiB"), Ok(2 << 20));
+        assert_eq!(parse_size("1 kb"), Ok(1000));
+        assert_eq!(parse_size("12"), Ok(12));
+        assert_eq!(parse_size("99999999T"), Err(UnitError::Overflow));
+        assert_eq!(
+            parse_size("1.5G"),
+            Err(UnitError::InvalidUnit(".5G".into()))
+        );
+    }
+}
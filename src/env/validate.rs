@@ -0,0 +1,199 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::env::{Process, Source};
+use std::fmt::{Display, Formatter};
+
+/// The error returned when mutually exclusive variables are set simultaneously.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConflictError {
+    /// The pairs of conflicting variables which are both set.
+    pub pairs: Vec<(&'static str, &'static str)>,
+}
+
+impl Display for ConflictError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("conflicting environment variables: ")?;
+        for (i, (a, b)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} and {} cannot be set simultaneously", a, b)?;
+        }
+        f.write_str(" (unset one of each pair)")
+    }
+}
+
+impl std::error::Error for ConflictError {}
+
+/// Checks that no pair of mutually exclusive variables is set simultaneously in a [Source].
+///
+/// # Arguments
+///
+/// * `source`: the source to check.
+/// * `pairs`: the pairs of mutually exclusive variables.
+///
+/// # Errors
+///
+/// Returns a [ConflictError] listing all pairs of variables which are both set.
+pub fn conflicts_in<S: Source>(
+    source: S,
+    pairs: &[(&'static str, &'static str)],
+) -> Result<(), ConflictError> {
+    let pairs: Vec<_> = pairs
+        .iter()
+        .filter(|(a, b)| source.get_os(a).is_some() && source.get_os(b).is_some())
+        .copied()
+        .collect();
+    match pairs.is_empty() {
+        true => Ok(()),
+        false => Err(ConflictError { pairs }),
+    }
+}
+
+/// Checks that no pair of mutually exclusive environment variables is set simultaneously.
+///
+/// # Arguments
+///
+/// * `pairs`: the pairs of mutually exclusive variables (ex: `[("BP3D_LOG", "BP3D_QUIET")]`).
+///
+/// # Errors
+///
+/// Returns a [ConflictError] listing all pairs of variables which are both set.
+pub fn conflicts(pairs: &[(&'static str, &'static str)]) -> Result<(), ConflictError> {
+    conflicts_in(Process, pairs)
+}
+
+/// The error returned when required variables are missing or invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequireError {
+    /// The required variables which are not set.
+    pub missing: Vec<&'static str>,
+
+    /// The required variables which are set but not valid UTF-8.
+    pub invalid: Vec<&'static str>,
+}
+
+impl Display for RequireError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid environment")?;
+        if !self.missing.is_empty() {
+            write!(f, "; missing variables: {}", self.missing.join(", "))?;
+        }
+        if !self.invalid.is_empty() {
+            write!(f, "; non UTF-8 variables: {}", self.invalid.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RequireError {}
+
+/// Gets all required variables from a [Source] at once.
+///
+/// # Arguments
+///
+/// * `source`: the source to read from.
+/// * `names`: the names of the required variables.
+///
+/// returns: Result<Vec<String>, RequireError> the values in the same order as `names`.
+///
+/// # Errors
+///
+/// Returns a [RequireError] listing all variables which are missing or invalid.
+pub fn require_in<S: Source>(
+    source: S,
+    names: &[&'static str],
+) -> Result<Vec<String>, RequireError> {
+    let mut values = Vec::with_capacity(names.len());
+    let mut err = RequireError {
+        missing: Vec::new(),
+        invalid: Vec::new(),
+    };
+    for name in names {
+        match source.get_os(name).map(|v| v.into_string()) {
+            Some(Ok(v)) => values.push(v),
+            Some(Err(_)) => err.invalid.push(name),
+            None => err.missing.push(name),
+        }
+    }
+    match err.missing.is_empty() && err.invalid.is_empty() {
+        true => Ok(values),
+        false => Err(err),
+    }
+}
+
+/// Gets all required environment variables at once, such that a single report lists every
+/// missing variable.
+///
+/// This is designed to be combined with
+/// [expect_exit](crate::result::ResultExt::expect_exit) at startup.
+///
+/// # Arguments
+///
+/// * `names`: the names of the required variables (ex: `["DATABASE_URL", "API_KEY"]`).
+///
+/// returns: Result<Vec<String>, RequireError> the values in the same order as `names`.
+///
+/// # Errors
+///
+/// Returns a [RequireError] listing all variables which are missing or invalid.
+pub fn require(names: &[&'static str]) -> Result<Vec<String>, RequireError> {
+    require_in(Process, names)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{conflicts_in, require_in};
+    use std::collections::HashMap;
+
+    #[test]
+    fn conflicts() {
+        let mut vars = HashMap::new();
+        vars.insert(String::from("LOG"), String::from("1"));
+        assert!(conflicts_in(&vars, &[("LOG", "QUIET")]).is_ok());
+        vars.insert(String::from("QUIET"), String::from("1"));
+        let err = conflicts_in(&vars, &[("LOG", "QUIET"), ("A", "B")]).unwrap_err();
+        assert_eq!(err.pairs, [("LOG", "QUIET")]);
+        assert!(err.to_string().contains("LOG and QUIET"));
+    }
+
+    #[test]
+    fn require() {
+        let mut vars = HashMap::new();
+        vars.insert(String::from("A"), String::from("1"));
+        vars.insert(String::from("B"), String::from("2"));
+        assert_eq!(require_in(&vars, &["B", "A"]).unwrap(), ["2", "1"]);
+        let err = require_in(&vars, &["A", "C", "D"]).unwrap_err();
+        assert_eq!(err.missing, ["C", "D"]);
+        assert_eq!(
+            err.to_string(),
+            "invalid environment; missing variables: C, D"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A type parsed from a fixed set of accepted strings, used by
+/// [get_enum](crate::env::get_enum) to list the allowed values in errors.
+pub trait VariantList: FromStr {
+    /// The accepted values, as displayed to users.
+    const VARIANTS: &'static [&'static str];
+}
+
+/// The error returned when a variable does not match any of the accepted values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnumError {
+    /// The name of the variable.
+    pub name: String,
+
+    /// The invalid value.
+    pub value: String,
+
+    /// The accepted values.
+    pub allowed: &'static [&'static str],
+}
+
+impl Display for EnumError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid value '{}' for {}, expected one of: {}",
+            self.value,
+            self.name,
+            self.allowed.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for EnumError {}
+
+pub(super) fn parse_enum<T: VariantList>(name: &str, value: String) -> Result<T, EnumError> {
+    value.parse().map_err(|_| EnumError {
+        name: name.into(),
+        value,
+        allowed: T::VARIANTS,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::{EnumError, Source, VariantList};
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    #[derive(Debug, PartialEq)]
+    enum Mode {
+        Fast,
+        Safe,
+    }
+
+    impl FromStr for Mode {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "fast" => Ok(Mode::Fast),
+                "safe" => Ok(Mode::Safe),
+                _ => Err(()),
+            }
+        }
+    }
+
+    impl VariantList for Mode {
+        const VARIANTS: &'static [&'static str] = &["fast", "safe"];
+    }
+
+    #[test]
+    fn get_enum() {
+        let mut vars = HashMap::new();
+        vars.insert(String::from("MODE"), String::from("safe"));
+        vars.insert(String::from("BAD"), String::from("slow"));
+        assert_eq!(vars.get_enum::<Mode, _>("MODE"), Some(Ok(Mode::Safe)));
+        assert_eq!(vars.get_enum::<Mode, _>("NONE"), None);
+        let err: EnumError = vars.get_enum::<Mode, _>("BAD").unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value 'slow' for BAD, expected one of: fast, safe"
+        );
+    }
+}
@@ -47,4 +47,20 @@ macro_rules! extension {
             $($tokens)*
         }
     };
+    (
+        $(#[$meta: meta])*
+        pub extension $name: ident: [$ty: ty] {
+            $($tokens: tt)*
+        }
+    ) => {
+        mod sealing {
+            pub trait Sealed {}
+        }
+        impl sealing::Sealed for [$ty] {}
+
+        $(#[$meta])*
+        pub trait $name: sealing::Sealed {
+            $($tokens)*
+        }
+    };
 }
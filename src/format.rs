@@ -28,8 +28,53 @@
 
 //! Formatting utilities.
 
+mod escape;
+mod grouped;
+mod indent;
+mod lazy;
+mod level;
+mod numeric;
+mod pad;
+mod prefix;
+mod quoted;
+mod scratch;
+mod tee;
+mod timestamp;
+mod utf16;
+
+pub use escape::{CEscaped, JsonEscaped, ShellQuoted};
+pub use grouped::Grouped;
+pub use indent::{IndentGuard, IndentWriter};
+pub use lazy::DisplayFn;
+pub use level::{Level, ParseLevelError};
+pub use numeric::EngNotation;
+pub use pad::{Align, Padded};
+pub use prefix::PrefixWriter;
+pub use quoted::Quoted;
+pub use scratch::ScratchStr;
+pub use tee::{CountingWrite, HashingWrite, TeeWrite};
+pub use timestamp::{write_rfc3339, Timestamp};
+pub use utf16::{Utf16Lossy, Wtf8Lossy};
+
 use std::mem::MaybeUninit;
 
+/// The result of a write into a [FixedBufStr].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WriteResult {
+    /// The number of bytes written into the buffer.
+    pub written: usize,
+
+    /// The number of bytes which did not fit in the buffer and were dropped.
+    pub remaining: usize,
+}
+
+impl WriteResult {
+    /// Returns true if all bytes were written.
+    pub fn is_complete(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
 /// Fixed length string buffer.
 #[derive(Clone, Debug)]
 pub struct FixedBufStr<const N: usize> {
@@ -78,15 +123,14 @@ impl<const N: usize> FixedBufStr<N> {
         buffer
     }
 
-    /// Appends a raw byte buffer at the end of this string buffer.
-    ///
-    /// Returns the number of bytes written.
+    /// Appends a raw byte buffer at the end of this string buffer, truncating it if it does not
+    /// fit.
     ///
     /// # Arguments
     ///
     /// * `buf`: the raw byte buffer to append.
     ///
-    /// returns: usize
+    /// returns: WriteResult
     ///
     /// # Safety
     ///
@@ -96,7 +140,7 @@ impl<const N: usize> FixedBufStr<N> {
     ///   result in UB.
     //type inference works so why should the code look awfully more complex?
     #[allow(clippy::missing_transmute_annotations)]
-    pub unsafe fn write(&mut self, buf: &[u8]) -> usize {
+    pub unsafe fn write(&mut self, buf: &[u8]) -> WriteResult {
         let len = std::cmp::min(buf.len(), N - self.len);
         unsafe {
             std::ptr::copy_nonoverlapping(
@@ -106,17 +150,144 @@ impl<const N: usize> FixedBufStr<N> {
             );
         }
         self.len += len;
-        len
+        WriteResult {
+            written: len,
+            remaining: buf.len() - len,
+        }
+    }
+
+    /// Appends a string at the end of this string buffer only if it fits entirely.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the string to append.
+    ///
+    /// returns: WriteResult with nothing written if the string does not fit.
+    pub fn write_all_or_nothing(&mut self, value: &str) -> WriteResult {
+        if value.len() > N - self.len {
+            return WriteResult {
+                written: 0,
+                remaining: value.len(),
+            };
+        }
+        // SAFETY: value is a str.
+        unsafe { self.write(value.as_bytes()) }
+    }
+
+    /// Appends a string truncated on a character boundary if it does not fit.
+    fn write_truncated(&mut self, value: &str) -> WriteResult {
+        let mut len = std::cmp::min(value.len(), N - self.len);
+        while !value.is_char_boundary(len) {
+            len -= 1;
+        }
+        // SAFETY: value is truncated on a character boundary.
+        unsafe { self.write(&value.as_bytes()[..len]) };
+        WriteResult {
+            written: len,
+            remaining: value.len() - len,
+        }
+    }
+
+    /// Appends untrusted bytes at the end of this string buffer, replacing invalid UTF-8
+    /// sequences with U+FFFD, as done by [String::from_utf8_lossy].
+    ///
+    /// The result is truncated on a character boundary if it does not fit.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf`: the bytes to append.
+    ///
+    /// returns: WriteResult counting bytes of the lossy UTF-8 output.
+    pub fn write_lossy(&mut self, buf: &[u8]) -> WriteResult {
+        let mut res = WriteResult::default();
+        for chunk in buf.utf8_chunks() {
+            let mut append = |value: &str| {
+                let written = match res.remaining {
+                    0 => self.write_truncated(value),
+                    _ => WriteResult {
+                        written: 0,
+                        remaining: value.len(),
+                    },
+                };
+                res.written += written.written;
+                res.remaining += written.remaining;
+            };
+            append(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                append("\u{FFFD}");
+            }
+        }
+        res
     }
 }
 
 impl<const N: usize> std::fmt::Write for FixedBufStr<N> {
+    /// Appends a string, truncated on a character boundary if it does not fit.
     fn write_str(&mut self, value: &str) -> std::fmt::Result {
-        unsafe { self.write(value.as_bytes()) };
+        self.write_truncated(value);
         Ok(())
     }
 }
 
+impl<const N: usize> std::ops::Deref for FixedBufStr<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for FixedBufStr<N> {
+    fn as_ref(&self) -> &str {
+        self.str()
+    }
+}
+
+impl<const N: usize> std::borrow::Borrow<str> for FixedBufStr<N> {
+    fn borrow(&self) -> &str {
+        self.str()
+    }
+}
+
+impl<const N: usize> std::fmt::Display for FixedBufStr<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.str())
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<FixedBufStr<M>> for FixedBufStr<N> {
+    fn eq(&self, other: &FixedBufStr<M>) -> bool {
+        self.str() == other.str()
+    }
+}
+
+impl<const N: usize> Eq for FixedBufStr<N> {}
+
+impl<const N: usize> PartialEq<str> for FixedBufStr<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for FixedBufStr<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.str() == *other
+    }
+}
+
+impl<const N: usize> std::hash::Hash for FixedBufStr<N> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.str().hash(state)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for FixedBufStr<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.str())
+    }
+}
+
 /// An io [Write](std::io::Write) to fmt [Write](std::fmt::Write).
 ///
 /// This may look like a hack but is a requirement for pathological APIs such as presented by the
@@ -143,12 +314,11 @@ impl<W: std::fmt::Write> IoToFmt<W> {
 
 impl<W: std::fmt::Write> std::io::Write for IoToFmt<W> {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        let str = std::str::from_utf8(buf)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let str = std::str::from_utf8(buf).map_err(std::io::Error::other)?;
         self.0
             .write_str(str)
             .map(|_| str.len())
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            .map_err(std::io::Error::other)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
@@ -170,4 +340,39 @@ mod tests {
         let _ = write!(msg, " test");
         assert_eq!(msg.str(), "this is a test");
     }
+
+    #[test]
+    fn traits() {
+        let msg: FixedBufStr<8> = FixedBufStr::from_str("abc");
+        assert_eq!(msg, "abc");
+        assert_eq!(msg, FixedBufStr::<4>::from_str("abc"));
+        assert_eq!(format!("[{:>5}]", msg), "[  abc]");
+        assert!(msg.starts_with("ab"));
+        let set: std::collections::HashSet<FixedBufStr<8>> = [msg].into_iter().collect();
+        assert!(set.contains("abc"));
+    }
+
+    #[test]
+    fn write_result() {
+        let mut msg: FixedBufStr<4> = FixedBufStr::new();
+        let res = unsafe { msg.write(b"abcdef") };
+        assert_eq!((res.written, res.remaining), (4, 2));
+        let mut msg: FixedBufStr<4> = FixedBufStr::new();
+        assert!(msg.write_all_or_nothing("abc").is_complete());
+        assert_eq!(msg.write_all_or_nothing("de").written, 0);
+        let _ = msg.write_str("é");
+        assert_eq!(msg.str(), "abc");
+    }
+
+    #[test]
+    fn write_lossy() {
+        let mut buf: FixedBufStr<16> = FixedBufStr::new();
+        let res = buf.write_lossy(b"ab\xFFcd\xE2\x82");
+        assert_eq!(buf.str(), "ab\u{FFFD}cd\u{FFFD}");
+        assert_eq!((res.written, res.remaining), (10, 0));
+        let mut buf: FixedBufStr<4> = FixedBufStr::new();
+        let res = buf.write_lossy(b"ab\xFFcd");
+        assert_eq!(buf.str(), "ab");
+        assert_eq!((res.written, res.remaining), (2, 5));
+    }
 }
@@ -0,0 +1,174 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter, Write};
+
+/// Escapes a string to be embedded in a JSON string literal.
+///
+/// The surrounding quotes are not written.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::JsonEscaped;
+///
+/// let msg = format!("\"{}\"", JsonEscaped("say \"hi\"\n"));
+/// assert_eq!(msg, r#""say \"hi\"\n""#);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct JsonEscaped<'a>(pub &'a str);
+
+impl Display for JsonEscaped<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut start = 0;
+        for (pos, c) in self.0.char_indices() {
+            let escape = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                '\u{8}' => "\\b",
+                '\u{c}' => "\\f",
+                c if (c as u32) < 0x20 => "",
+                _ => continue,
+            };
+            f.write_str(&self.0[start..pos])?;
+            if escape.is_empty() {
+                write!(f, "\\u{:04x}", c as u32)?;
+            } else {
+                f.write_str(escape)?;
+            }
+            start = pos + c.len_utf8();
+        }
+        f.write_str(&self.0[start..])
+    }
+}
+
+/// Quotes a string to be passed as a single argument to a POSIX shell.
+///
+/// Strings made only of characters which are never interpreted by the shell are written as is,
+/// all other strings are enclosed in single quotes.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::ShellQuoted;
+///
+/// assert_eq!(ShellQuoted("file.txt").to_string(), "file.txt");
+/// assert_eq!(ShellQuoted("it's here").to_string(), r#"'it'\''s here'"#);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ShellQuoted<'a>(pub &'a str);
+
+fn is_shell_safe(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ',' | ':' | '+' | '=' | '@')
+}
+
+impl Display for ShellQuoted<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.0.is_empty() && self.0.chars().all(is_shell_safe) {
+            return f.write_str(self.0);
+        }
+        f.write_char('\'')?;
+        let mut parts = self.0.split('\'');
+        if let Some(part) = parts.next() {
+            f.write_str(part)?;
+        }
+        for part in parts {
+            f.write_str("'\\''")?;
+            f.write_str(part)?;
+        }
+        f.write_char('\'')
+    }
+}
+
+/// Escapes a byte string to be embedded in a C string literal.
+///
+/// Printable ASCII characters are written as is, common control characters use their short
+/// escape sequence and all other bytes are written as 3 digits octal escapes. The surrounding
+/// quotes are not written.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::CEscaped;
+///
+/// assert_eq!(CEscaped(b"a\"b\n\xff").to_string(), r#"a\"b\n\377"#);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct CEscaped<'a>(pub &'a [u8]);
+
+impl Display for CEscaped<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut start = 0;
+        for (pos, b) in self.0.iter().enumerate() {
+            let escape = match b {
+                b'"' => "\\\"",
+                b'\\' => "\\\\",
+                b'\n' => "\\n",
+                b'\r' => "\\r",
+                b'\t' => "\\t",
+                0x20..=0x7E => continue,
+                _ => "",
+            };
+            // SAFETY: everything before pos which was not escaped is printable ASCII.
+            f.write_str(unsafe { std::str::from_utf8_unchecked(&self.0[start..pos]) })?;
+            if escape.is_empty() {
+                write!(f, "\\{:03o}", b)?;
+            } else {
+                f.write_str(escape)?;
+            }
+            start = pos + 1;
+        }
+        f.write_str(unsafe { std::str::from_utf8_unchecked(&self.0[start..]) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{CEscaped, JsonEscaped, ShellQuoted};
+
+    #[test]
+    fn json() {
+        assert_eq!(JsonEscaped("é\u{1}\\").to_string(), "é\\u0001\\\\");
+        assert_eq!(JsonEscaped("plain").to_string(), "plain");
+    }
+
+    #[test]
+    fn shell() {
+        assert_eq!(ShellQuoted("").to_string(), "''");
+        assert_eq!(ShellQuoted("a b").to_string(), "'a b'");
+        assert_eq!(ShellQuoted("'").to_string(), "''\\'''");
+    }
+
+    #[test]
+    fn c() {
+        assert_eq!(CEscaped(b"\0\t12").to_string(), "\\000\\t12");
+    }
+}
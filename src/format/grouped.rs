@@ -0,0 +1,162 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::format::FixedBufStr;
+use std::fmt::{Display, Formatter, Write};
+
+/// A [Display] adapter writing a number with its integer digits grouped by 3 using a
+/// configurable separator, independently of any locale.
+///
+/// This never allocates and can be used with any fmt [Write] target, including
+/// [FixedBufStr].
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::Grouped;
+///
+/// assert_eq!(Grouped::new(1234567u64).to_string(), "1,234,567");
+/// assert_eq!(Grouped::new(-1234i64).separator('_').to_string(), "-1_234");
+/// assert_eq!(Grouped::new(1234.5f64).precision(2).to_string(), "1,234.50");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Grouped<T> {
+    value: T,
+    separator: char,
+    precision: usize,
+}
+
+impl<T> Grouped<T> {
+    /// Creates a new [Grouped] adapter using `,` as separator.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the number to display.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            separator: ',',
+            precision: 0,
+        }
+    }
+
+    /// Sets the group separator (ex: `_`, `'` or a narrow no-break space).
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+}
+
+impl Grouped<f64> {
+    /// Sets the number of decimals, clamped to 18. Decimals are not grouped.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+}
+
+fn write_grouped(f: &mut Formatter<'_>, value: &str, separator: char) -> std::fmt::Result {
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(v) => ("-", v),
+        None => ("", value),
+    };
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (digits, rest) = value.split_at(end);
+    f.write_str(sign)?;
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            f.write_char(separator)?;
+        }
+        f.write_char(c)?;
+    }
+    f.write_str(rest)
+}
+
+macro_rules! impl_grouped {
+    ($($t: ty => $append: ident),*) => {
+        $(
+            impl Display for Grouped<$t> {
+                fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                    let mut buf: FixedBufStr<40> = FixedBufStr::new();
+                    buf.$append(self.value as _);
+                    write_grouped(f, buf.str(), self.separator)
+                }
+            }
+        )*
+    };
+}
+
+impl_grouped!(
+    u8 => append_u64, u16 => append_u64, u32 => append_u64, u64 => append_u64,
+    usize => append_u64, u128 => append_u128,
+    i8 => append_i64, i16 => append_i64, i32 => append_i64, i64 => append_i64,
+    isize => append_i64, i128 => append_i128
+);
+
+impl Display for Grouped<f64> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Large enough for the longest finite f64 with the maximum precision.
+        let mut buf: FixedBufStr<344> = FixedBufStr::new();
+        buf.append_f64(self.value, self.precision);
+        write_grouped(f, buf.str(), self.separator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{FixedBufStr, Grouped};
+    use std::fmt::Write;
+
+    #[test]
+    fn integers() {
+        assert_eq!(Grouped::new(0u8).to_string(), "0");
+        assert_eq!(Grouped::new(999u32).to_string(), "999");
+        assert_eq!(Grouped::new(1000u32).to_string(), "1,000");
+        assert_eq!(
+            Grouped::new(i128::MIN).separator('_').to_string(),
+            "-170_141_183_460_469_231_731_687_303_715_884_105_728"
+        );
+        let mut buf: FixedBufStr<16> = FixedBufStr::new();
+        let _ = write!(buf, "{}", Grouped::new(123456789usize));
+        assert_eq!(buf.str(), "123,456,789");
+    }
+
+    #[test]
+    fn float() {
+        assert_eq!(
+            Grouped::new(-9876543.21).precision(1).to_string(),
+            "-9,876,543.2"
+        );
+        assert_eq!(Grouped::new(2500.5).precision(0).to_string(), "2,500");
+        assert_eq!(Grouped::new(1234.125).precision(2).to_string(), "1,234.12");
+        assert_eq!(Grouped::new(f64::INFINITY).to_string(), "inf");
+        assert_eq!(Grouped::new(1e21).to_string().len(), 29);
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::format::PrefixWriter;
+use std::fmt::Write;
+use std::ops::{Deref, DerefMut};
+
+/// A fmt [Write](Write) adapter which inserts an indentation prefix at the start of each
+/// non-empty line, tracking the indentation level across partial-line writes.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::IndentWriter;
+/// use std::fmt::Write;
+///
+/// let mut w = IndentWriter::new(String::new(), "  ");
+/// let _ = writeln!(w, "root");
+/// {
+///     let mut w = w.indent();
+///     let _ = write!(w, "child");
+///     let _ = writeln!(w, " 1\n\nchild 2");
+/// }
+/// let _ = writeln!(w, "end");
+/// assert_eq!(w.into_inner(), "root\n  child 1\n\n  child 2\nend\n");
+/// ```
+pub struct IndentWriter<W: Write> {
+    // The indentation is written by write_str as it depends on the level when a line starts.
+    inner: PrefixWriter<'static, W>,
+    unit: &'static str,
+    level: usize,
+}
+
+impl<W: Write> IndentWriter<W> {
+    /// Creates a new [IndentWriter](IndentWriter) starting at level 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the underlying writer.
+    /// * `unit`: the prefix inserted once per indentation level (ex: 4 spaces).
+    ///
+    /// returns: IndentWriter<W>
+    pub fn new(inner: W, unit: &'static str) -> Self {
+        Self {
+            inner: PrefixWriter::new(inner, ""),
+            unit,
+            level: 0,
+        }
+    }
+
+    /// Returns the current indentation level.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Increases the indentation level; lines already started are not affected.
+    pub fn push(&mut self) {
+        self.level += 1;
+    }
+
+    /// Decreases the indentation level, saturating at 0.
+    pub fn pop(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    /// Increases the indentation level until the returned guard is dropped.
+    pub fn indent(&mut self) -> IndentGuard<'_, W> {
+        self.push();
+        IndentGuard(self)
+    }
+
+    /// Extracts the underlying [Write](Write).
+    pub fn into_inner(self) -> W {
+        self.inner.into_inner()
+    }
+}
+
+impl<W: Write> Write for IndentWriter<W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let (unit, level) = (self.unit, self.level);
+        self.inner.write_str_with(s, |w, line, start| {
+            if start {
+                for _ in 0..level {
+                    w.write_str(unit)?;
+                }
+            }
+            w.write_str(line)
+        })
+    }
+}
+
+/// A guard which restores the indentation level of an [IndentWriter] when dropped.
+///
+/// See [indent](IndentWriter::indent).
+pub struct IndentGuard<'a, W: Write>(&'a mut IndentWriter<W>);
+
+impl<W: Write> Deref for IndentGuard<'_, W> {
+    type Target = IndentWriter<W>;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<W: Write> DerefMut for IndentGuard<'_, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+impl<W: Write> Write for IndentGuard<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.write_str(s)
+    }
+}
+
+impl<W: Write> Drop for IndentGuard<'_, W> {
+    fn drop(&mut self) {
+        self.0.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::IndentWriter;
+    use std::fmt::Write;
+
+    #[test]
+    fn nested() {
+        let mut w = IndentWriter::new(String::new(), "\t");
+        let _ = w.write_str("a {");
+        {
+            let mut w = w.indent();
+            let _ = w.write_str("\nb {");
+            {
+                let mut w = w.indent();
+                assert_eq!(w.level(), 2);
+                let _ = w.write_str("\nc");
+            }
+            let _ = w.write_str("\n}");
+        }
+        let _ = w.write_str("\n}");
+        assert_eq!(w.level(), 0);
+        w.pop();
+        assert_eq!(w.into_inner(), "a {\n\tb {\n\t\tc\n\t}\n}");
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Debug, Display, Formatter};
+
+/// A [Display] adapter calling a closure each time it is written, such that expensive
+/// formatting is deferred until the value is actually written (and skipped if it never is).
+///
+/// See [lazy_format](crate::lazy_format) for a shorthand using format strings.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::DisplayFn;
+///
+/// let items = [1, 2, 3];
+/// let list = DisplayFn(|f| {
+///     for (i, item) in items.iter().enumerate() {
+///         if i > 0 {
+///             f.write_str(", ")?;
+///         }
+///         write!(f, "#{}", item)?;
+///     }
+///     Ok(())
+/// });
+/// assert_eq!(list.to_string(), "#1, #2, #3");
+/// ```
+#[derive(Copy, Clone)]
+pub struct DisplayFn<F: Fn(&mut Formatter<'_>) -> std::fmt::Result>(pub F);
+
+impl<F: Fn(&mut Formatter<'_>) -> std::fmt::Result> Display for DisplayFn<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        (self.0)(f)
+    }
+}
+
+impl<F: Fn(&mut Formatter<'_>) -> std::fmt::Result> Debug for DisplayFn<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        (self.0)(f)
+    }
+}
+
+/// Creates a [DisplayFn](crate::format::DisplayFn) formatting its arguments only when written,
+/// with the same syntax as [format].
+///
+/// The arguments are borrowed, not moved, such that the result cannot outlive them.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::lazy_format;
+/// use bp3d_util::format::FixedBufStr;
+/// use std::fmt::Write;
+///
+/// let name = "world";
+/// let message = lazy_format!("hello {}", name);
+/// let mut buf: FixedBufStr<8> = FixedBufStr::new();
+/// write!(buf, "{}", message).unwrap();
+/// assert_eq!(buf.str(), "hello wo");
+/// ```
+#[macro_export]
+macro_rules! lazy_format {
+    ($($arg: tt)*) => {
+        $crate::format::DisplayFn(|f: &mut core::fmt::Formatter<'_>| write!(f, $($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    #[test]
+    fn lazy() {
+        let calls = Cell::new(0);
+        let value = lazy_format!("{}", {
+            calls.set(calls.get() + 1);
+            42
+        });
+        assert_eq!(calls.get(), 0);
+        assert_eq!(value.to_string(), "42");
+        assert_eq!(format!("{:?}", value), "42");
+        assert_eq!(calls.get(), 2);
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A log level shared across BP3D tools, ordered from the most verbose to the most severe.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// Trace level (`0`).
+    Trace,
+
+    /// Debug level (`1`).
+    Debug,
+
+    /// Info level (`2`).
+    Info,
+
+    /// Warning level (`3`).
+    Warning,
+
+    /// Error level (`4`).
+    Error,
+}
+
+impl Level {
+    /// Returns the name of this level in lower case.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Trace => "trace",
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// The error returned when a [Level] could not be parsed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseLevelError;
+
+impl Display for ParseLevelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unknown log level, expected one of trace, debug, info, warning, error or 0-4")
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    /// Parses a level from its name (case-insensitive, `warn` and `err` are accepted) or from its
+    /// number (`0` for trace to `4` for error).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const NAMES: [(&str, Level); 12] = [
+            ("0", Level::Trace),
+            ("1", Level::Debug),
+            ("2", Level::Info),
+            ("3", Level::Warning),
+            ("4", Level::Error),
+            ("trace", Level::Trace),
+            ("debug", Level::Debug),
+            ("info", Level::Info),
+            ("warn", Level::Warning),
+            ("warning", Level::Warning),
+            ("err", Level::Error),
+            ("error", Level::Error),
+        ];
+        let s = s.trim();
+        NAMES
+            .iter()
+            .find(|(name, _)| s.eq_ignore_ascii_case(name))
+            .map(|(_, level)| *level)
+            .ok_or(ParseLevelError)
+    }
+}
+
+#[cfg(feature = "env")]
+impl crate::env::VariantList for Level {
+    const VARIANTS: &'static [&'static str] = &["trace", "debug", "info", "warning", "error"];
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::Level;
+
+    #[test]
+    fn parse() {
+        assert_eq!("WARN".parse(), Ok(Level::Warning));
+        assert_eq!(" info ".parse(), Ok(Level::Info));
+        assert_eq!("0".parse(), Ok(Level::Trace));
+        assert_eq!("Error".parse(), Ok(Level::Error));
+        assert!("5".parse::<Level>().is_err());
+        assert!(Level::Trace < Level::Error);
+        assert_eq!(format!("{:<6}|", Level::Info), "info  |");
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::format::FixedBufStr;
+use std::fmt::{Display, Formatter};
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// Divides a positive value by 10^exp, splitting the division for exponents which are not
+/// representable by f64.
+fn scale_down(value: f64, exp: i32) -> f64 {
+    match exp < -300 {
+        true => value * 1e300 / 10f64.powi(exp + 300),
+        false => value / 10f64.powi(exp),
+    }
+}
+
+/// Formats a finite value in scientific notation, or engineering notation when `step` is 3.
+fn format_sci(value: f64, precision: usize, step: i32, buf: &mut [u8; 32]) -> &[u8] {
+    let abs = value.abs();
+    let scale = 10u128.pow(precision as u32);
+    let limit = 10u128.pow(step as u32) * scale;
+    let mut exp = 0;
+    let mut scaled = 0;
+    if abs != 0.0 {
+        exp = abs.log10().floor() as i32;
+        // log10 may be off by one close to powers of 10.
+        let mantissa = scale_down(abs, exp);
+        if mantissa >= 10.0 {
+            exp += 1;
+        } else if mantissa < 1.0 {
+            exp -= 1;
+        }
+        exp -= exp.rem_euclid(step);
+        scaled = (scale_down(abs, exp) * scale as f64).round_ties_even() as u128;
+        if scaled >= limit {
+            exp += step;
+            scaled = (scale_down(abs, exp) * scale as f64).round_ties_even() as u128;
+        }
+    }
+    let mut pos = 0;
+    let mut push = |bytes: &[u8]| {
+        buf[pos..pos + bytes.len()].copy_from_slice(bytes);
+        pos += bytes.len();
+    };
+    if value.is_sign_negative() {
+        push(b"-");
+    }
+    let mut digits = [0; 40];
+    push(format_u128(scaled / scale, &mut digits));
+    if precision > 0 {
+        let mut frac = [b'0'; 18];
+        let mut value = scaled % scale;
+        for i in (0..precision).rev() {
+            frac[i] = b'0' + (value % 10) as u8;
+            value /= 10;
+        }
+        push(b".");
+        push(&frac[..precision]);
+    }
+    push(if exp < 0 { b"e-" } else { b"e+" });
+    let exp = exp.unsigned_abs();
+    if exp < 10 {
+        push(b"0");
+    }
+    push(format_u128(exp as u128, &mut digits));
+    &buf[..pos]
+}
+
+fn format_u128(mut value: u128, buf: &mut [u8; 40]) -> &[u8] {
+    let mut pos = buf.len();
+    // Use 64 bits arithmetic once the value fits as 128 bits division is slow.
+    while value > u64::MAX as u128 {
+        pos -= 1;
+        buf[pos] = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+    let mut value = value as u64;
+    loop {
+        pos -= 1;
+        buf[pos] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    &buf[pos..]
+}
+
+impl<const N: usize> FixedBufStr<N> {
+    fn append_ascii(&mut self, buf: &[u8]) -> usize {
+        // SAFETY: all callers pass ASCII bytes which are valid UTF-8.
+        unsafe { self.write(buf).written }
+    }
+
+    /// Appends an unsigned integer in decimal without going through [core::fmt].
+    ///
+    /// Returns the number of bytes written.
+    pub fn append_u64(&mut self, value: u64) -> usize {
+        self.append_u128(value as u128)
+    }
+
+    /// Appends a signed integer in decimal without going through [core::fmt].
+    ///
+    /// Returns the number of bytes written.
+    pub fn append_i64(&mut self, value: i64) -> usize {
+        self.append_i128(value as i128)
+    }
+
+    /// Appends a 128 bits unsigned integer in decimal without going through [core::fmt].
+    ///
+    /// Returns the number of bytes written.
+    pub fn append_u128(&mut self, value: u128) -> usize {
+        let mut buf = [0; 40];
+        self.append_ascii(format_u128(value, &mut buf))
+    }
+
+    /// Appends a 128 bits signed integer in decimal without going through [core::fmt].
+    ///
+    /// Returns the number of bytes written.
+    pub fn append_i128(&mut self, value: i128) -> usize {
+        let mut buf = [0; 40];
+        let digits = format_u128(value.unsigned_abs(), &mut buf);
+        match value < 0 {
+            true => self.append_ascii(b"-") + self.append_ascii(digits),
+            false => self.append_ascii(digits),
+        }
+    }
+
+    /// Appends an address in lower case hexadecimal prefixed with `0x`, without going through
+    /// [core::fmt].
+    ///
+    /// Returns the number of bytes written.
+    pub fn append_ptr<T: ?Sized>(&mut self, ptr: *const T) -> usize {
+        let mut value = ptr as *const () as usize;
+        let mut buf = [0; 2 + 2 * size_of::<usize>()];
+        let mut pos = buf.len();
+        loop {
+            pos -= 1;
+            buf[pos] = HEX[value & 0xF];
+            value >>= 4;
+            if value == 0 {
+                break;
+            }
+        }
+        pos -= 2;
+        buf[pos..pos + 2].copy_from_slice(b"0x");
+        self.append_ascii(&buf[pos..])
+    }
+
+    /// Appends a float with a fixed number of decimals without going through [core::fmt].
+    ///
+    /// The fast path is taken for finite values which fit in 128 bits once scaled; other values
+    /// fall back to [core::fmt]. Exact ties are rounded half to even as done by [core::fmt]
+    /// (ex: `2.5` with 0 decimals gives `2`); the scaling is computed in floating point, so the
+    /// last decimal may still differ from [core::fmt] for values which cannot be represented
+    /// exactly.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to append.
+    /// * `precision`: the number of decimals, clamped to 18.
+    ///
+    /// returns: usize the number of bytes written.
+    pub fn append_f64(&mut self, value: f64, precision: usize) -> usize {
+        let precision = precision.min(18);
+        let scale = 10u64.pow(precision as u32);
+        let scaled = (value.abs() * scale as f64).round_ties_even();
+        if !scaled.is_finite() || scaled >= u128::MAX as f64 {
+            let len = self.len;
+            let _ = std::fmt::Write::write_fmt(self, format_args!("{:.*}", precision, value));
+            return self.len - len;
+        }
+        let scaled = scaled as u128;
+        let mut len = 0;
+        if value.is_sign_negative() {
+            len += self.append_ascii(b"-");
+        }
+        len += self.append_u128(scaled / scale as u128);
+        if precision > 0 {
+            let mut buf = [b'0'; 19];
+            buf[0] = b'.';
+            let mut frac = (scaled % scale as u128) as u64;
+            for i in (1..=precision).rev() {
+                buf[i] = b'0' + (frac % 10) as u8;
+                frac /= 10;
+            }
+            len += self.append_ascii(&buf[..=precision]);
+        }
+        len
+    }
+
+    /// Appends a float in scientific notation without going through [core::fmt].
+    ///
+    /// The output follows C's `%e` format: one integer digit, `precision` decimals and an
+    /// exponent with an explicit sign and at least 2 digits (ex: `1.500e+03`), such that values
+    /// of the same sign and with an exponent in -99..=99 have the same width. Exact ties are
+    /// rounded half to even like [core::fmt]. Non-finite values are written as `NaN`, `inf` or
+    /// `-inf`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to append.
+    /// * `precision`: the number of decimals, clamped to 17.
+    ///
+    /// returns: usize the number of bytes written.
+    pub fn append_f64_sci(&mut self, value: f64, precision: usize) -> usize {
+        if !value.is_finite() {
+            let len = self.len;
+            let _ = std::fmt::Write::write_fmt(self, format_args!("{}", value));
+            return self.len - len;
+        }
+        let mut buf = [0; 32];
+        self.append_ascii(format_sci(value, precision.min(17), 1, &mut buf))
+    }
+}
+
+/// A [Display] adapter writing a float in engineering notation: the exponent is always a
+/// multiple of 3 and the mantissa is in the range [1, 1000).
+///
+/// The exponent is written with an explicit sign and at least 2 digits. Width and alignment
+/// flags are honored which allows aligning columns (ex: `{:>12}`).
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::EngNotation;
+///
+/// assert_eq!(EngNotation::new(12345.0).to_string(), "12.345e+03");
+/// assert_eq!(EngNotation::new(-0.0042).precision(1).to_string(), "-4.2e-03");
+/// assert_eq!(format!("{:>10}", EngNotation::new(1.0).precision(2)), "  1.00e+00");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct EngNotation {
+    value: f64,
+    precision: usize,
+}
+
+impl EngNotation {
+    /// Creates a new [EngNotation] adapter with 3 decimals.
+    pub fn new(value: f64) -> Self {
+        Self {
+            value,
+            precision: 3,
+        }
+    }
+
+    /// Sets the number of decimals, clamped to 17.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision.min(17);
+        self
+    }
+}
+
+impl Display for EngNotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.value.is_finite() {
+            return f.pad(match self.value {
+                v if v.is_nan() => "NaN",
+                v if v > 0.0 => "inf",
+                _ => "-inf",
+            });
+        }
+        let mut buf = [0; 32];
+        let bytes = format_sci(self.value, self.precision, 3, &mut buf);
+        // SAFETY: format_sci only writes ASCII bytes.
+        f.pad(unsafe { std::str::from_utf8_unchecked(bytes) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{EngNotation, FixedBufStr};
+
+    #[test]
+    fn integers() {
+        let mut buf: FixedBufStr<128> = FixedBufStr::new();
+        buf.append_u128(u128::MAX);
+        buf.append_ascii(b" ");
+        buf.append_i128(i128::MIN);
+        buf.append_ascii(b" ");
+        buf.append_i64(0);
+        assert_eq!(buf.str(), format!("{} {} 0", u128::MAX, i128::MIN));
+    }
+
+    #[test]
+    fn ptr() {
+        let value = 42;
+        let mut buf: FixedBufStr<32> = FixedBufStr::new();
+        buf.append_ptr(&value);
+        assert_eq!(buf.str(), format!("{:p}", &value));
+    }
+
+    #[test]
+    fn float() {
+        let cases = [
+            (1.5, 2),
+            (-0.125, 3),
+            (3.0, 0),
+            (-0.0001, 2),
+            (1e300, 1),
+            (2.5, 0),
+            (0.5, 0),
+            (3.5, 0),
+            (-2.5, 0),
+            (0.125, 2),
+            (0.375, 2),
+        ];
+        for (value, precision) in cases {
+            let mut buf: FixedBufStr<512> = FixedBufStr::new();
+            buf.append_f64(value, precision);
+            assert_eq!(buf.str(), format!("{:.*}", precision, value));
+        }
+        let mut buf: FixedBufStr<8> = FixedBufStr::new();
+        buf.append_f64(f64::NAN, 2);
+        assert_eq!(buf.str(), "NaN");
+    }
+
+    #[test]
+    fn sci() {
+        let cases = [
+            (1500.0, 3, "1.500e+03"),
+            (-0.000123, 2, "-1.23e-04"),
+            (0.0, 1, "0.0e+00"),
+            (9.996, 2, "1.00e+01"),
+            (2.5, 0, "2e+00"),
+            (3.5, 0, "4e+00"),
+            (1e300, 0, "1e+300"),
+            (5e-324, 1, "4.9e-324"),
+            (f64::NEG_INFINITY, 2, "-inf"),
+        ];
+        for (value, precision, expected) in cases {
+            let mut buf: FixedBufStr<32> = FixedBufStr::new();
+            buf.append_f64_sci(value, precision);
+            assert_eq!(buf.str(), expected);
+        }
+    }
+
+    #[test]
+    fn eng() {
+        assert_eq!(EngNotation::new(999.9996).to_string(), "1.000e+03");
+        assert_eq!(EngNotation::new(0.5).precision(0).to_string(), "500e-03");
+        assert_eq!(EngNotation::new(f64::NAN).to_string(), "NaN");
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter, Write};
+
+/// Where to put the padding of a [Padded] value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Align {
+    /// Pads on the right.
+    Left,
+
+    /// Pads on the left.
+    Right,
+
+    /// Pads on both sides, with the extra fill character on the right.
+    Center,
+}
+
+/// A [Display] adapter padding a value to a fixed width counted in UTF-8 characters.
+///
+/// Unlike the standard width and fill format parameters, the fill character and width can be
+/// chosen at runtime. Values longer than the width are written as-is.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::{FixedBufStr, Padded};
+/// use std::fmt::Write;
+///
+/// let mut buf: FixedBufStr<32> = FixedBufStr::new();
+/// let _ = write!(buf, "[{}]", Padded::pad_left("é", 4).fill('.'));
+/// assert_eq!(buf.str(), "[...é]");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Padded<T> {
+    value: T,
+    width: usize,
+    fill: char,
+    align: Align,
+}
+
+impl<T: Display> Padded<T> {
+    /// Creates a new [Padded] adapter filling with spaces.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to pad.
+    /// * `width`: the minimum width in characters.
+    /// * `align`: where to put the padding.
+    pub fn new(value: T, width: usize, align: Align) -> Self {
+        Self {
+            value,
+            width,
+            fill: ' ',
+            align,
+        }
+    }
+
+    /// Pads a value on the left (right aligns it).
+    pub fn pad_left(value: T, width: usize) -> Self {
+        Self::new(value, width, Align::Right)
+    }
+
+    /// Pads a value on the right (left aligns it).
+    pub fn pad_right(value: T, width: usize) -> Self {
+        Self::new(value, width, Align::Left)
+    }
+
+    /// Centers a value.
+    pub fn center(value: T, width: usize) -> Self {
+        Self::new(value, width, Align::Center)
+    }
+
+    /// Sets the fill character.
+    pub fn fill(mut self, fill: char) -> Self {
+        self.fill = fill;
+        self
+    }
+}
+
+struct CharCounter(usize);
+
+impl Write for CharCounter {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+impl<T: Display> Display for Padded<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut counter = CharCounter(0);
+        write!(counter, "{}", self.value)?;
+        let padding = self.width.saturating_sub(counter.0);
+        let (left, right) = match self.align {
+            Align::Left => (0, padding),
+            Align::Right => (padding, 0),
+            Align::Center => (padding / 2, padding - padding / 2),
+        };
+        for _ in 0..left {
+            f.write_char(self.fill)?;
+        }
+        write!(f, "{}", self.value)?;
+        for _ in 0..right {
+            f.write_char(self.fill)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::Padded;
+
+    #[test]
+    fn basic() {
+        assert_eq!(Padded::pad_left(42, 5).to_string(), "   42");
+        assert_eq!(Padded::pad_right("ab", 4).fill('-').to_string(), "ab--");
+        assert_eq!(Padded::center("ab", 5).fill('*').to_string(), "*ab**");
+        assert_eq!(Padded::center("日本", 4).to_string(), " 日本 ");
+        assert_eq!(Padded::pad_left("toolong", 3).to_string(), "toolong");
+    }
+}
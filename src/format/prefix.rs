@@ -0,0 +1,168 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// A fmt [Write](Write) adapter which inserts a prefix at the start of every line (ex: `> ` to
+/// quote a block in markdown or `// ` to embed text in a source file), tracking line starts
+/// across partial-line writes.
+///
+/// Empty lines receive the prefix without its trailing whitespace, such that no trailing
+/// whitespace is generated. The prefix of a line is only written once the line has content or
+/// ends, so text ending with a new line does not leave a dangling prefix.
+///
+/// This is the line tracking core of [Quoted](crate::format::Quoted) and
+/// [IndentWriter](crate::format::IndentWriter).
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::PrefixWriter;
+/// use std::fmt::Write;
+///
+/// let depth = 2;
+/// let mut w = PrefixWriter::new(String::new(), ">".repeat(depth) + " ");
+/// let _ = write!(w, "first");
+/// let _ = writeln!(w, " line\n\nsecond line");
+/// assert_eq!(w.into_inner(), ">> first line\n>>\n>> second line\n");
+/// ```
+pub struct PrefixWriter<'a, W: Write> {
+    inner: W,
+    prefix: Cow<'a, str>,
+    line_start: bool,
+}
+
+impl<'a, W: Write> PrefixWriter<'a, W> {
+    /// Creates a new [PrefixWriter](PrefixWriter).
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the underlying writer.
+    /// * `prefix`: the prefix inserted at the start of every line, borrowed or computed at
+    ///   runtime.
+    ///
+    /// returns: PrefixWriter<W>
+    pub fn new(inner: W, prefix: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            inner,
+            prefix: prefix.into(),
+            line_start: true,
+        }
+    }
+
+    /// Returns the prefix inserted at the start of every line.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Extracts the underlying [Write](Write).
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes a string, passing each non-empty line segment to `write_line` after the prefix
+    /// when it starts a new line.
+    ///
+    /// `write_line` receives the underlying writer, the segment, which never contains a new
+    /// line, and whether the segment starts a new line.
+    pub(super) fn write_str_with(
+        &mut self,
+        s: &str,
+        mut write_line: impl FnMut(&mut W, &str, bool) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(line) = lines.next() {
+            self.write_line(line, &mut write_line)?;
+        }
+        for line in lines {
+            if self.line_start {
+                self.inner.write_str(self.prefix.trim_end())?;
+            }
+            self.inner.write_char('\n')?;
+            self.line_start = true;
+            self.write_line(line, &mut write_line)?;
+        }
+        Ok(())
+    }
+
+    fn write_line(
+        &mut self,
+        line: &str,
+        write_line: &mut impl FnMut(&mut W, &str, bool) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        if line.is_empty() {
+            return Ok(());
+        }
+        let start = self.line_start;
+        if start {
+            self.inner.write_str(&self.prefix)?;
+            self.line_start = false;
+        }
+        write_line(&mut self.inner, line, start)
+    }
+}
+
+impl<W: Write> Write for PrefixWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.write_str_with(s, |w, line, _| w.write_str(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{IndentWriter, PrefixWriter};
+    use std::fmt::Write;
+
+    #[test]
+    fn prefix() {
+        let mut w = PrefixWriter::new(String::new(), "// ");
+        let _ = w.write_str("a");
+        let _ = w.write_str("b\n");
+        let _ = w.write_str("\n");
+        let _ = w.write_str("c");
+        assert_eq!(w.get_ref(), "// ab\n//\n// c");
+        let mut w = PrefixWriter::new(IndentWriter::new(String::new(), "  "), "> ");
+        let _ = writeln!(w, "x");
+        assert_eq!(w.into_inner().into_inner(), "> x\n");
+    }
+
+    #[test]
+    fn runtime_prefix() {
+        let name = String::from("build");
+        let mut w = PrefixWriter::new(String::new(), format!("[{}] ", name));
+        let _ = write!(w, "step 1\nstep 2");
+        assert_eq!(w.prefix(), "[build] ");
+        assert_eq!(w.into_inner(), "[build] step 1\n[build] step 2");
+    }
+}
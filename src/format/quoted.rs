@@ -0,0 +1,140 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::format::PrefixWriter;
+use std::fmt::Write;
+
+/// A fmt [Write](Write) adapter which prefixes each line with a quote marker and optionally
+/// truncates long lines, to embed a nested report (ex: a child process output) legibly inside
+/// another report.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::Quoted;
+/// use std::fmt::Write;
+///
+/// let mut quoted = Quoted::new(String::new(), "| ").max_width(8);
+/// let _ = write!(quoted, "error: file not found\nexit code 1");
+/// assert_eq!(quoted.into_inner(), "| error: f…\n| exit cod…");
+/// ```
+pub struct Quoted<W: Write> {
+    inner: PrefixWriter<'static, W>,
+    max_width: Option<usize>,
+    column: usize,
+}
+
+impl<W: Write> Quoted<W> {
+    /// Creates a new [Quoted](Quoted) adapter.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the underlying writer.
+    /// * `marker`: the quote marker to insert at the start of each line (ex: `> `).
+    ///
+    /// returns: Quoted<W>
+    pub fn new(inner: W, marker: &'static str) -> Self {
+        Self {
+            inner: PrefixWriter::new(inner, marker),
+            max_width: None,
+            column: 0,
+        }
+    }
+
+    /// Truncates lines longer than the given number of characters (excluding the marker); the
+    /// truncation is indicated by a trailing `…`.
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Extracts the underlying [Write](Write).
+    pub fn into_inner(self) -> W {
+        self.inner.into_inner()
+    }
+}
+
+fn write_truncated<W: Write>(
+    w: &mut W,
+    line: &str,
+    max_width: usize,
+    column: &mut usize,
+) -> std::fmt::Result {
+    if *column > max_width {
+        return Ok(());
+    }
+    match line.char_indices().nth(max_width - *column) {
+        Some((pos, _)) => {
+            *column = max_width + 1;
+            w.write_str(&line[..pos])?;
+            w.write_char('…')
+        }
+        None => {
+            *column += line.chars().count();
+            w.write_str(line)
+        }
+    }
+}
+
+impl<W: Write> Write for Quoted<W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let (max_width, column) = (self.max_width, &mut self.column);
+        self.inner.write_str_with(s, |w, line, start| {
+            if start {
+                *column = 0;
+            }
+            match max_width {
+                Some(max_width) => write_truncated(w, line, max_width, column),
+                None => w.write_str(line),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::Quoted;
+    use std::fmt::Write;
+
+    #[test]
+    fn basic() {
+        let mut quoted = Quoted::new(String::new(), "> ");
+        let _ = quoted.write_str("a\n\nb");
+        let _ = quoted.write_str("c\n");
+        assert_eq!(quoted.into_inner(), "> a\n>\n> bc\n");
+    }
+
+    #[test]
+    fn truncate() {
+        let mut quoted = Quoted::new(String::new(), "> ").max_width(4);
+        let _ = quoted.write_str("ab");
+        let _ = quoted.write_str("cdé");
+        let _ = quoted.write_str("fg\nabcd\n");
+        assert_eq!(quoted.into_inner(), "> abcd…\n> abcd\n");
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::cell::{Cell, RefCell};
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+
+const DEFAULT_BUFFER_SIZE: usize = 1024;
+const DEFAULT_POOL_DEPTH: usize = 4;
+
+thread_local! {
+    static CONFIG: Cell<(usize, usize)> = const { Cell::new((DEFAULT_BUFFER_SIZE, DEFAULT_POOL_DEPTH)) };
+    static POOL: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A bounded string buffer borrowed from a per-thread pool.
+///
+/// The buffer is returned to the pool of the current thread when dropped, such that hot paths can
+/// reuse the same allocation instead of placing large [FixedBufStr](crate::format::FixedBufStr)
+/// arrays on the stack. Like [FixedBufStr](crate::format::FixedBufStr), writes past the buffer
+/// size are truncated.
+#[derive(Debug)]
+pub struct ScratchStr {
+    buffer: String,
+    max_len: usize,
+}
+
+impl ScratchStr {
+    /// Configures the scratch buffer pool of the current thread.
+    ///
+    /// Idle pooled buffers are discarded and buffers currently borrowed are only returned to the
+    /// pool on release if they fit in `buffer_size`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_size`: the maximum size in bytes of each buffer (defaults to 1024).
+    /// * `pool_depth`: the maximum number of idle buffers kept in the pool (defaults to 4).
+    pub fn configure(buffer_size: usize, pool_depth: usize) {
+        CONFIG.with(|v| v.set((buffer_size, pool_depth)));
+        POOL.with_borrow_mut(|v| v.clear());
+    }
+
+    /// Borrows a cleared buffer from the pool of the current thread, allocating a new one if the
+    /// pool is empty.
+    pub fn get() -> ScratchStr {
+        let (max_len, _) = CONFIG.get();
+        let buffer = POOL
+            .with_borrow_mut(|v| v.pop())
+            .unwrap_or_else(|| String::with_capacity(max_len));
+        ScratchStr { buffer, max_len }
+    }
+
+    /// Extracts the string from this buffer.
+    pub fn str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Clears this buffer.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+impl Deref for ScratchStr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl Display for ScratchStr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.buffer)
+    }
+}
+
+impl std::fmt::Write for ScratchStr {
+    fn write_str(&mut self, value: &str) -> std::fmt::Result {
+        let mut len = std::cmp::min(value.len(), self.max_len - self.buffer.len());
+        while !value.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.buffer.push_str(&value[..len]);
+        Ok(())
+    }
+}
+
+impl Drop for ScratchStr {
+    fn drop(&mut self) {
+        let (max_len, depth) = CONFIG.get();
+        if self.buffer.capacity() > max_len {
+            return;
+        }
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        // The thread-local may already be destroyed if dropped during thread teardown.
+        let _ = POOL.try_with(|v| {
+            let mut pool = v.borrow_mut();
+            if pool.len() < depth {
+                pool.push(buffer);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::ScratchStr;
+    use std::fmt::Write;
+
+    #[test]
+    fn basic() {
+        ScratchStr::configure(8, 1);
+        let ptr = {
+            let mut buf = ScratchStr::get();
+            let _ = write!(buf, "this is a test");
+            assert_eq!(buf.str(), "this is ");
+            buf.as_ptr()
+        };
+        let mut buf = ScratchStr::get();
+        assert!(buf.is_empty());
+        assert_eq!(buf.as_ptr(), ptr);
+        let _ = write!(buf, "1234567é");
+        assert_eq!(buf.str(), "1234567");
+    }
+
+    #[test]
+    fn configure() {
+        ScratchStr::configure(64, 2);
+        let large = ScratchStr::get();
+        drop(ScratchStr::get());
+        ScratchStr::configure(8, 2);
+        drop(large);
+        assert!(ScratchStr::get().buffer.capacity() < 64);
+    }
+}
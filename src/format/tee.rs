@@ -0,0 +1,219 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::Write;
+use std::hash::Hasher;
+
+/// A fmt [Write](Write) adapter which duplicates everything written into 2 underlying writers.
+///
+/// Both writers always receive the data, even if the first one fails; the first error is then
+/// returned.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::{FixedBufStr, TeeWrite};
+/// use std::fmt::Write;
+///
+/// let mut tee = TeeWrite(FixedBufStr::<4>::new(), String::new());
+/// let _ = write!(tee, "hello {}", 42);
+/// assert_eq!(tee.0.str(), "hell");
+/// assert_eq!(tee.1, "hello 42");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TeeWrite<W1, W2>(pub W1, pub W2);
+
+impl<W1: Write, W2: Write> Write for TeeWrite<W1, W2> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let first = self.0.write_str(s);
+        let second = self.1.write_str(s);
+        first.and(second)
+    }
+}
+
+/// A fmt [Write](Write) adapter which counts the bytes and characters written to an underlying
+/// writer.
+///
+/// Only data accepted by the underlying writer is counted.
+#[derive(Clone, Debug, Default)]
+pub struct CountingWrite<W> {
+    inner: W,
+    bytes: usize,
+    chars: usize,
+}
+
+impl<W: Write> CountingWrite<W> {
+    /// Creates a new [CountingWrite](CountingWrite).
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the underlying writer.
+    ///
+    /// returns: CountingWrite<W>
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            bytes: 0,
+            chars: 0,
+        }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the number of characters written so far.
+    pub fn chars(&self) -> usize {
+        self.chars
+    }
+
+    /// Resets both counters to zero (ex: at the start of a new line).
+    pub fn reset(&mut self) {
+        self.bytes = 0;
+        self.chars = 0;
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Extracts the underlying [Write](Write).
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for CountingWrite<W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.inner.write_str(s)?;
+        self.bytes += s.len();
+        self.chars += s.chars().count();
+        Ok(())
+    }
+}
+
+/// A fmt [Write](Write) adapter which updates a [Hasher] with the text written while
+/// forwarding writes to an underlying writer, to fingerprint generated text without a second
+/// pass.
+///
+/// This is the fmt counterpart of `bytes::HashingWriter` and follows the same rules: only the
+/// text accepted by the underlying writer is fed to the hasher, with one [Hasher::write] call
+/// per write, such that the hash depends on the write boundaries and not only on the text.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::HashingWrite;
+/// use std::hash::DefaultHasher;
+/// use std::fmt::Write;
+///
+/// let mut w = HashingWrite::new(String::new(), DefaultHasher::new());
+/// let _ = write!(w, "key = {}", 42);
+/// let (text, fingerprint) = w.finalize();
+/// assert_eq!(text, "key = 42");
+/// # let _ = fingerprint;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HashingWrite<W, H> {
+    inner: W,
+    hasher: H,
+}
+
+impl<W: Write, H: Hasher> HashingWrite<W, H> {
+    /// Creates a new [HashingWrite](HashingWrite).
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the underlying writer to forward writes to.
+    /// * `hasher`: the hasher to update.
+    ///
+    /// returns: HashingWrite<W, H>
+    pub fn new(inner: W, hasher: H) -> Self {
+        Self { inner, hasher }
+    }
+
+    /// Returns the hash of all text written so far.
+    pub fn hash(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Extracts the underlying [Write](Write) and the hash of all text written.
+    pub fn finalize(self) -> (W, u64) {
+        let hash = self.hasher.finish();
+        (self.inner, hash)
+    }
+}
+
+impl<W: Write, H: Hasher> Write for HashingWrite<W, H> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.inner.write_str(s)?;
+        self.hasher.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{CountingWrite, HashingWrite, TeeWrite};
+    use std::fmt::Write;
+    use std::hash::{DefaultHasher, Hasher};
+
+    #[test]
+    fn counting() {
+        let mut w = CountingWrite::new(TeeWrite(String::new(), String::new()));
+        let _ = write!(w, "é{}", 12);
+        assert_eq!(w.bytes(), 4);
+        assert_eq!(w.chars(), 3);
+        w.reset();
+        assert_eq!(w.bytes(), 0);
+        let tee = w.into_inner();
+        assert_eq!(tee.0, "é12");
+        assert_eq!(tee.1, "é12");
+    }
+
+    #[test]
+    fn hashing() {
+        let mut w = HashingWrite::new(String::new(), DefaultHasher::new());
+        let _ = w.write_str("ab");
+        let _ = w.write_str("cd");
+        let mut expected = DefaultHasher::new();
+        expected.write(b"ab");
+        expected.write(b"cd");
+        assert_eq!(w.hash(), expected.finish());
+        let (text, hash) = w.finalize();
+        assert_eq!(hash, expected.finish());
+        assert_eq!(text, "abcd");
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::civil::civil_from_days;
+use std::fmt::{Display, Formatter, Write};
+
+/// Writes a unix timestamp as an RFC3339 date and time (ex: `2025-01-02T03:04:05+01:00`).
+///
+/// # Arguments
+///
+/// * `w`: the writer to write the timestamp to.
+/// * `secs`: the number of seconds since the unix epoch (UTC).
+/// * `offset`: the offset from UTC in seconds of the local time to write, None to write the
+///   time in UTC with the `Z` suffix. Seconds of the offset are ignored as RFC3339 offsets only
+///   have a minute precision.
+///
+/// Years outside `0000..=9999` are written with a sign, as allowed by ISO8601 expanded years.
+///
+/// # Errors
+///
+/// Returns an error if the local time is not representable as an [i64] number of seconds.
+pub fn write_rfc3339<W: Write>(w: &mut W, secs: i64, offset: Option<i32>) -> std::fmt::Result {
+    let local = secs
+        .checked_add(offset.unwrap_or(0) as i64)
+        .ok_or(std::fmt::Error)?;
+    let (year, month, day) = civil_from_days(local.div_euclid(86400));
+    let time = local.rem_euclid(86400);
+    if (0..=9999).contains(&year) {
+        write!(w, "{:04}", year)?;
+    } else {
+        write!(w, "{:+05}", year)?;
+    }
+    write!(
+        w,
+        "-{:02}-{:02}T{:02}:{:02}:{:02}",
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )?;
+    match offset {
+        None => w.write_char('Z'),
+        Some(offset) => {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let minutes = offset.unsigned_abs() / 60;
+            write!(w, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+        }
+    }
+}
+
+/// A unix timestamp which displays as an RFC3339 date and time, see [write_rfc3339].
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::Timestamp;
+///
+/// assert_eq!(Timestamp::new(1735783445).to_string(), "2025-01-02T02:04:05Z");
+/// assert_eq!(
+///     Timestamp::new(1735783445).with_offset(3600).to_string(),
+///     "2025-01-02T03:04:05+01:00"
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Timestamp {
+    secs: i64,
+    offset: Option<i32>,
+}
+
+impl Timestamp {
+    /// Creates a new [Timestamp](Timestamp) displayed in UTC.
+    ///
+    /// # Arguments
+    ///
+    /// * `secs`: the number of seconds since the unix epoch.
+    ///
+    /// returns: Timestamp
+    pub fn new(secs: i64) -> Self {
+        Self { secs, offset: None }
+    }
+
+    /// Displays this timestamp in the local time at the given offset from UTC in seconds (ex:
+    /// the offset found in a TZIF file for this timestamp).
+    pub fn with_offset(self, offset: i32) -> Self {
+        Self {
+            offset: Some(offset),
+            ..self
+        }
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_rfc3339(f, self.secs, self.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{write_rfc3339, Timestamp};
+
+    #[test]
+    fn rfc3339() {
+        assert_eq!(Timestamp::new(0).to_string(), "1970-01-01T00:00:00Z");
+        assert_eq!(Timestamp::new(-1).to_string(), "1969-12-31T23:59:59Z");
+        assert_eq!(
+            Timestamp::new(951782400).with_offset(0).to_string(),
+            "2000-02-29T00:00:00+00:00"
+        );
+        assert_eq!(
+            Timestamp::new(0)
+                .with_offset(-(9 * 3600 + 30 * 60))
+                .to_string(),
+            "1969-12-31T14:30:00-09:30"
+        );
+        assert_eq!(
+            Timestamp::new(253402300800).to_string(),
+            "+10000-01-01T00:00:00Z"
+        );
+        let mut s = String::new();
+        write_rfc3339(&mut s, 1735783445, Some(3600)).unwrap();
+        assert_eq!(s, "2025-01-02T03:04:05+01:00");
+    }
+
+    #[test]
+    fn bounds() {
+        let mut s = String::new();
+        assert!(write_rfc3339(&mut s, i64::MAX, Some(3600)).is_err());
+        assert!(write_rfc3339(&mut s, i64::MIN, Some(-3600)).is_err());
+        s.clear();
+        write_rfc3339(&mut s, i64::MAX, None).unwrap();
+        assert!(s.starts_with("+292277026596-"));
+        s.clear();
+        write_rfc3339(&mut s, i64::MIN, Some(3600)).unwrap();
+        assert!(s.starts_with("-292277022657-"));
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::format::{FixedBufStr, WriteResult};
+use std::char::{decode_utf16, DecodeUtf16Error, REPLACEMENT_CHARACTER};
+use std::fmt::{Display, Formatter, Write};
+use std::str::Utf8Error;
+
+/// An iterator over the valid UTF-8 pieces of a WTF-8 string, yielding `"\u{FFFD}"` in place of
+/// each encoded surrogate or invalid sequence.
+struct Wtf8Chunks<'a>(&'a [u8]);
+
+impl<'a> Iterator for Wtf8Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let e = match std::str::from_utf8(self.0) {
+            Ok(s) => {
+                self.0 = &[];
+                return Some(s);
+            }
+            Err(e) => e,
+        };
+        let (valid, rest) = self.0.split_at(e.valid_up_to());
+        if !valid.is_empty() {
+            self.0 = rest;
+            // SAFETY: from_utf8 validated the bytes up to valid_up_to.
+            return Some(unsafe { std::str::from_utf8_unchecked(valid) });
+        }
+        // A surrogate is encoded as ED A0..BF 80..BF and replaced as a whole.
+        let len = match rest {
+            [0xED, 0xA0..=0xBF, 0x80..=0xBF, ..] => 3,
+            _ => e.error_len().unwrap_or(rest.len()),
+        };
+        self.0 = &rest[len..];
+        Some("\u{FFFD}")
+    }
+}
+
+impl<const N: usize> FixedBufStr<N> {
+    fn append_chars(&mut self, chars: impl Iterator<Item = char>) -> WriteResult {
+        let mut res = WriteResult::default();
+        let mut buf = [0; 4];
+        for c in chars {
+            if res.remaining == 0 {
+                let written = self.write_all_or_nothing(c.encode_utf8(&mut buf));
+                res.written += written.written;
+                res.remaining += written.remaining;
+            } else {
+                res.remaining += c.len_utf8();
+            }
+        }
+        res
+    }
+
+    /// Appends a UTF-16 string (ex: from a Windows API), replacing unpaired surrogates with
+    /// U+FFFD, without allocating an intermediate [String].
+    ///
+    /// The string is truncated on a character boundary if it does not fit.
+    pub fn append_utf16_lossy(&mut self, value: &[u16]) -> WriteResult {
+        self.append_chars(
+            decode_utf16(value.iter().copied()).map(|v| v.unwrap_or(REPLACEMENT_CHARACTER)),
+        )
+    }
+
+    /// Appends a UTF-16 string (ex: from a Windows API) without allocating an intermediate
+    /// [String].
+    ///
+    /// The string is truncated on a character boundary if it does not fit.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first decoding error if the string contains an unpaired surrogate, in which
+    /// case nothing is written.
+    pub fn append_utf16(&mut self, value: &[u16]) -> Result<WriteResult, DecodeUtf16Error> {
+        if let Some(Err(e)) = decode_utf16(value.iter().copied()).find(|v| v.is_err()) {
+            return Err(e);
+        }
+        Ok(self.append_chars(decode_utf16(value.iter().copied()).filter_map(|v| v.ok())))
+    }
+
+    /// Appends a WTF-8 string (ex: from `OsStr::as_encoded_bytes` on Windows), replacing
+    /// encoded surrogates and invalid sequences with U+FFFD, without allocating an
+    /// intermediate [String].
+    ///
+    /// The string is truncated on a character boundary if it does not fit.
+    pub fn append_wtf8_lossy(&mut self, value: &[u8]) -> WriteResult {
+        self.append_chars(Wtf8Chunks(value).flat_map(str::chars))
+    }
+
+    /// Appends a WTF-8 string (ex: from `OsStr::as_encoded_bytes` on Windows) without
+    /// allocating an intermediate [String].
+    ///
+    /// The string is truncated on a character boundary if it does not fit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not valid UTF-8, such as when it contains an encoded
+    /// surrogate, in which case nothing is written.
+    pub fn append_wtf8(&mut self, value: &[u8]) -> Result<WriteResult, Utf8Error> {
+        Ok(self.append_chars(std::str::from_utf8(value)?.chars()))
+    }
+}
+
+/// A [Display] adapter writing a UTF-16 string, replacing unpaired surrogates with U+FFFD,
+/// without allocating an intermediate [String].
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::Utf16Lossy;
+///
+/// let wide: Vec<u16> = "héllo".encode_utf16().chain([0xD800]).collect();
+/// assert_eq!(Utf16Lossy(&wide).to_string(), "héllo\u{FFFD}");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Utf16Lossy<'a>(pub &'a [u16]);
+
+impl Display for Utf16Lossy<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut buf: FixedBufStr<64> = FixedBufStr::new();
+        for c in decode_utf16(self.0.iter().copied()) {
+            if buf.len() + 4 > 64 {
+                f.write_str(&buf)?;
+                buf = FixedBufStr::new();
+            }
+            buf.write_char(c.unwrap_or(REPLACEMENT_CHARACTER))?;
+        }
+        f.write_str(&buf)
+    }
+}
+
+/// A [Display] adapter writing a WTF-8 string (ex: from `OsStr::as_encoded_bytes` on
+/// Windows), replacing encoded surrogates and invalid sequences with U+FFFD, without
+/// allocating an intermediate [String].
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::format::Wtf8Lossy;
+///
+/// // "a" followed by the unpaired surrogate U+D800.
+/// assert_eq!(Wtf8Lossy(b"a\xED\xA0\x80").to_string(), "a\u{FFFD}");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Wtf8Lossy<'a>(pub &'a [u8]);
+
+impl Display for Wtf8Lossy<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Wtf8Chunks(self.0).try_for_each(|v| f.write_str(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{FixedBufStr, Utf16Lossy, Wtf8Lossy};
+
+    #[test]
+    fn utf16() {
+        let wide: Vec<u16> = "a€😀".encode_utf16().collect();
+        let mut buf: FixedBufStr<6> = FixedBufStr::new();
+        let res = buf.append_utf16(&wide).unwrap();
+        assert_eq!((buf.str(), res.written, res.remaining), ("a€", 4, 4));
+        let mut buf: FixedBufStr<16> = FixedBufStr::new();
+        assert!(buf.append_utf16(&[0x61, 0xDC00]).is_err());
+        assert!(buf.is_empty());
+        buf.append_utf16_lossy(&[0x61, 0xDC00]);
+        assert_eq!(buf.str(), "a\u{FFFD}");
+        let long: Vec<u16> = "é".repeat(100).encode_utf16().collect();
+        assert_eq!(Utf16Lossy(&long).to_string(), "é".repeat(100));
+    }
+
+    #[test]
+    fn wtf8() {
+        let wtf8 = b"a\xED\xA0\x80\xC3\xA9\xFFb";
+        assert_eq!(Wtf8Lossy(wtf8).to_string(), "a\u{FFFD}é\u{FFFD}b");
+        let mut buf: FixedBufStr<16> = FixedBufStr::new();
+        assert!(buf.append_wtf8(wtf8).is_err());
+        assert!(buf.is_empty());
+        buf.append_wtf8_lossy(wtf8);
+        assert_eq!(buf.str(), "a\u{FFFD}é\u{FFFD}b");
+        let mut buf: FixedBufStr<2> = FixedBufStr::new();
+        let res = buf.append_wtf8("aé".as_bytes()).unwrap();
+        assert_eq!((buf.str(), res.written, res.remaining), ("a", 1, 2));
+    }
+}
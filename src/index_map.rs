@@ -27,12 +27,38 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 //! A map with the key stored as part of the value.
+//!
+//! This module supports `no_std` environments with `alloc` when the `std` feature is disabled.
 
-use std::borrow::Borrow;
-use std::collections::HashSet;
-use std::hash::{Hash, Hasher};
-use std::rc::Rc;
-use std::sync::Arc;
+mod handle;
+mod metrics;
+mod multi;
+mod ordered;
+mod snapshot;
+mod weak;
+
+pub use handle::{Handle, HandleIndexMap};
+#[cfg(feature = "index-map-metrics")]
+pub use metrics::Metrics;
+pub use multi::{MultiIndexMap, SecondaryIndex};
+pub use ordered::{OrderedKeyIndexMap, Range};
+pub use snapshot::{CowIndexMap, Snapshot};
+pub use weak::{Shared, WeakIndexMap};
+
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash, Hasher};
+use hashbrown::HashSet;
+use metrics::Counters;
+
+/// The default hasher used by [IndexMap].
+#[cfg(feature = "std")]
+pub type DefaultHasher = std::collections::hash_map::RandomState;
+
+/// The default hasher used by [IndexMap].
+#[cfg(not(feature = "std"))]
+pub type DefaultHasher = hashbrown::DefaultHashBuilder;
 
 /// The main index type to implement for each type to be stored in an IndexMap.
 pub trait Index {
@@ -88,18 +114,94 @@ impl<V: Index<Key = usize>> Borrow<usize> for Item<V> {
     }
 }
 
+/// The error returned by [try_insert](IndexMap::try_insert) when the key is already present.
+#[derive(Debug)]
+pub struct Occupied<'a, V> {
+    /// The element already stored under the key.
+    pub existing: &'a V,
+
+    /// The value which could not be inserted.
+    pub value: V,
+}
+
+impl<V> core::fmt::Display for Occupied<'_, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("an element with the same key already exists")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<V: core::fmt::Debug> std::error::Error for Occupied<'_, V> {}
+
 /// The main IndexMap data-structure type.
 ///
 /// This map type uses a [HashSet] to store the underlying items.
 /// The underlying items are wrapped in a custom struct, hidden from the public API, to workaround
 /// Rust broken coherence and WTF other stupid similar rules.
-#[derive(Default, Clone, Debug)]
-pub struct IndexMap<V>(HashSet<Item<V>>);
+#[derive(Clone, Debug)]
+pub struct IndexMap<V, S = DefaultHasher>(HashSet<Item<V>, S>, Counters);
+
+impl<V, S: Default> Default for IndexMap<V, S> {
+    fn default() -> Self {
+        Self(HashSet::default(), Counters::default())
+    }
+}
 
 impl<V> IndexMap<V> {
     /// Creates a new instance of an [IndexMap].
     pub fn new() -> IndexMap<V> {
-        IndexMap(HashSet::new())
+        IndexMap(
+            HashSet::with_hasher(DefaultHasher::default()),
+            Counters::default(),
+        )
+    }
+
+    /// Creates a new instance of an [IndexMap] with a given capacity.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity`: the capacity of the new [IndexMap].
+    pub fn with_capacity(capacity: usize) -> IndexMap<V> {
+        Self(
+            HashSet::with_capacity_and_hasher(capacity, DefaultHasher::default()),
+            Counters::default(),
+        )
+    }
+}
+
+impl<V, S> IndexMap<V, S> {
+    /// Creates a new instance of an [IndexMap] with a given hasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `hasher`: the hasher to use for hashing keys.
+    pub fn with_hasher(hasher: S) -> IndexMap<V, S> {
+        Self(HashSet::with_hasher(hasher), Counters::default())
+    }
+
+    /// Creates a new instance of an [IndexMap] with a given capacity and hasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity`: the capacity of the new [IndexMap].
+    /// * `hasher`: the hasher to use for hashing keys.
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> IndexMap<V, S> {
+        Self(
+            HashSet::with_capacity_and_hasher(capacity, hasher),
+            Counters::default(),
+        )
+    }
+
+    /// Returns the counters of the operations performed on this [IndexMap].
+    #[cfg(feature = "index-map-metrics")]
+    pub fn metrics(&self) -> Metrics {
+        self.1.snapshot()
+    }
+
+    /// Resets the counters of the operations performed on this [IndexMap] to zero.
+    #[cfg(feature = "index-map-metrics")]
+    pub fn reset_metrics(&self) {
+        self.1.reset()
     }
 
     /// Returns the number of items in this [IndexMap].
@@ -112,31 +214,237 @@ impl<V> IndexMap<V> {
         self.0.is_empty()
     }
 
-    /// Creates a new instance of an [IndexMap] with a given capacity.
-    ///
-    /// # Arguments
+    /// Returns an iterator over all elements contained in the map.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter(self.0.iter())
+    }
+
+    /// Returns an iterator over all elements contained in the map, this is an alias to
+    /// [iter](IndexMap::iter) mirroring the API of HashMap.
     ///
-    /// * `capacity`: the capacity of the new [IndexMap].
-    pub fn with_capacity(capacity: usize) -> IndexMap<V> {
-        Self(HashSet::with_capacity(capacity))
+    /// There is no `values_mut` as mutating an element could change its key.
+    pub fn values(&self) -> Iter<'_, V> {
+        self.iter()
     }
 
-    /// Returns an iterator over all elements contained in the map.
-    pub fn iter(&self) -> impl Iterator<Item = &V> {
-        self.0.iter().map(|v| &v.0)
+    /// Returns the number of items this [IndexMap] can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+}
+
+/// An iterator over the elements of an [IndexMap].
+///
+/// There is no mutable counterpart as mutating an element could change its key.
+pub struct Iter<'a, V>(hashbrown::hash_set::Iter<'a, Item<V>>);
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|v| &v.0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for Iter<'_, V> {}
+
+/// An owning iterator over the elements of an [IndexMap].
+pub struct IntoIter<V>(hashbrown::hash_set::IntoIter<Item<V>>);
+
+impl<V> Iterator for IntoIter<V> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|v| v.0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for IntoIter<V> {}
+
+impl<'a, V, S> IntoIterator for &'a IndexMap<V, S> {
+    type Item = &'a V;
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<V, S> IntoIterator for IndexMap<V, S> {
+    type Item = V;
+    type IntoIter = IntoIter<V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.0.into_iter())
     }
 }
 
-impl<V: Index> IndexMap<V> {
+#[cfg(feature = "index-map-rayon")]
+impl<V: Sync, S: Sync> IndexMap<V, S> {
+    /// Returns a parallel iterator over all elements contained in the map.
+    pub fn par_iter(&self) -> impl rayon::iter::ParallelIterator<Item = &V> {
+        use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+        self.0.par_iter().map(|v| &v.0)
+    }
+}
+
+/// Statistics about the hash table of an [IndexMap].
+#[cfg(feature = "index-map-stats")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Stats {
+    /// The number of items.
+    pub len: usize,
+
+    /// The number of items the map can hold without reallocating.
+    pub capacity: usize,
+
+    /// The ratio between the number of items and the capacity.
+    pub load_factor: f32,
+
+    /// The number of items whose home bucket in the hash table is shared with another item,
+    /// such that their lookups have to probe past it.
+    pub hash_collisions: usize,
+}
+
+impl<V: Index, S> IndexMap<V, S> {
+    /// Returns an iterator over the keys of all elements contained in the map.
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = &V::Key> {
+        self.iter().map(|v| v.index())
+    }
+}
+
+impl<V: Index, S: BuildHasher> IndexMap<V, S> {
+    /// Reserves capacity for at least `additional` more items.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
+    /// Shrinks the capacity of this [IndexMap] as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit()
+    }
+
+    /// Computes statistics about the hash table of this [IndexMap].
+    ///
+    /// This iterates over all items and is intended for monitoring and tuning only.
+    #[cfg(feature = "index-map-stats")]
+    pub fn stats(&self) -> Stats {
+        // hashbrown reports 7/8 of its power of 2 bucket count as capacity, or the bucket count
+        // minus 1 for tables smaller than 8 buckets; the home bucket of an item is given by the
+        // low bits of its hash.
+        let buckets = match self.capacity() {
+            v if v < 8 => v + 1,
+            v => v / 7 * 8,
+        };
+        let mask = (buckets.next_power_of_two() - 1) as u64;
+        let mut homes = HashSet::<u64>::with_capacity(self.len());
+        let hasher = self.0.hasher();
+        let unique = self
+            .iter()
+            .filter(|v| homes.insert(hasher.hash_one(v.index()) & mask))
+            .count();
+        Stats {
+            len: self.len(),
+            capacity: self.capacity(),
+            load_factor: match self.capacity() {
+                0 => 0.0,
+                v => self.len() as f32 / v as f32,
+            },
+            hash_collisions: self.len() - unique,
+        }
+    }
+
     /// Inserts a new item in this [IndexMap].
     ///
+    /// If an element with the same key already exists, it is kept and `value` is dropped; use
+    /// [try_insert](IndexMap::try_insert) or [insert_replace](IndexMap::insert_replace) to
+    /// detect key collisions.
+    ///
     /// # Arguments
     ///
     /// * `value`: the value to be inserted.
     ///
     /// returns: ()
     pub fn insert(&mut self, value: V) {
-        self.0.insert(Item(value));
+        if self.0.insert(Item(value)) {
+            self.1.insert();
+        }
+    }
+
+    /// Inserts a new item in this [IndexMap], failing if an element with the same key already
+    /// exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to be inserted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [Occupied] with the existing element and the rejected value if the key is
+    /// already present; the map is left untouched.
+    pub fn try_insert(&mut self, value: V) -> Result<(), Occupied<'_, V>> {
+        let item = Item(value);
+        if self.0.contains(&item) {
+            return Err(Occupied {
+                existing: &self.0.get(&item).unwrap().0,
+                value: item.0,
+            });
+        }
+        self.0.insert(item);
+        self.1.insert();
+        Ok(())
+    }
+
+    /// Inserts a new item in this [IndexMap], replacing any element with the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to be inserted.
+    ///
+    /// returns: Option<V> the evicted element, if any.
+    pub fn insert_replace(&mut self, value: V) -> Option<V> {
+        self.1.insert();
+        let old = self.0.replace(Item(value));
+        if old.is_some() {
+            self.1.evict();
+        }
+        old.map(|v| v.0)
+    }
+
+    /// Gets an element stored in this [IndexMap] from its key, constructing and inserting it
+    /// only if no element exists with this key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the key of the element to look for.
+    /// * `f`: the function constructing the element, only called if the key is missing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key of the constructed element is not equal to `key`.
+    #[allow(private_bounds)] // Because Rust is a piece of shit!!
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: &V::Key, f: F) -> &V
+    where
+        Item<V>: Borrow<V::Key>,
+    {
+        let mut hit = true;
+        let value = self.0.get_or_insert_with(key, |_| {
+            hit = false;
+            Item(f())
+        });
+        self.1.get(hit);
+        if !hit {
+            self.1.insert();
+        }
+        &value.0
     }
 
     /// Gets an element stored in this [IndexMap] from its key.
@@ -149,11 +457,13 @@ impl<V: Index> IndexMap<V> {
     where
         Item<V>: Borrow<V::Key>,
     {
-        self.0.get(key).map(|v| &v.0)
+        let value = self.0.get(key);
+        self.1.get(value.is_some());
+        value.map(|v| &v.0)
     }
 }
 
-impl<'a, V: Index> std::ops::Index<&'a V::Key> for IndexMap<V>
+impl<'a, V: Index, S: BuildHasher> core::ops::Index<&'a V::Key> for IndexMap<V, S>
 where
     Item<V>: Borrow<V::Key>,
 {
@@ -163,3 +473,146 @@ where
         self.get(index).unwrap()
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::index_map::{Index, IndexMap};
+    use alloc::vec::Vec;
+
+    pub(crate) struct Named(pub &'static str, pub u32);
+
+    impl Index for Named {
+        type Key = str;
+
+        fn index(&self) -> &Self::Key {
+            self.0
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let mut map = IndexMap::new();
+        map.insert(Named("a", 1));
+        map.insert(Named("b", 2));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"].1, 1);
+        assert!(map.get("c").is_none());
+        let mut sum = 0;
+        for v in &map {
+            sum += v.1;
+        }
+        assert_eq!(sum, 3);
+        assert_eq!(map.iter().len(), 2);
+        let mut keys: Vec<_> = map.keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["a", "b"]);
+        assert_eq!(map.values().map(|v| v.1).sum::<u32>(), 3);
+        #[cfg(feature = "index-map-rayon")]
+        {
+            use rayon::iter::ParallelIterator;
+            assert_eq!(map.par_iter().map(|v| v.1).sum::<u32>(), 3);
+        }
+        let mut names: Vec<_> = map.into_iter().map(|v| v.0).collect();
+        names.sort();
+        assert_eq!(names, ["a", "b"]);
+    }
+
+    #[test]
+    fn get_or_insert_with() {
+        let mut map = IndexMap::new();
+        map.insert(Named("a", 1));
+        assert_eq!(map.get_or_insert_with("a", || unreachable!()).1, 1);
+        assert_eq!(map.get_or_insert_with("b", || Named("b", 2)).1, 2);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn custom_hasher() {
+        let mut map = IndexMap::with_hasher(hashbrown::DefaultHashBuilder::default());
+        map.insert(Named("a", 1));
+        assert_eq!(map.get("a").map(|v| v.1), Some(1));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut map = IndexMap::new();
+        map.reserve(100);
+        assert!(map.capacity() >= 100);
+        map.insert(Named("a", 1));
+        map.shrink_to_fit();
+        assert!(map.capacity() < 100);
+        #[cfg(feature = "index-map-stats")]
+        {
+            let stats = map.stats();
+            assert_eq!(stats.len, 1);
+            assert_eq!(stats.hash_collisions, 0);
+            assert!(stats.load_factor > 0.0);
+        }
+    }
+
+    #[cfg(feature = "index-map-stats")]
+    #[test]
+    fn stats() {
+        #[derive(Default)]
+        struct Constant;
+
+        impl core::hash::Hasher for Constant {
+            fn finish(&self) -> u64 {
+                0
+            }
+
+            fn write(&mut self, _: &[u8]) {}
+        }
+
+        let mut map = IndexMap::with_hasher(core::hash::BuildHasherDefault::<Constant>::default());
+        map.insert(Named("a", 1));
+        map.insert(Named("b", 2));
+        map.insert(Named("c", 3));
+        assert_eq!(map.stats().hash_collisions, 2);
+        struct Id(u32);
+
+        impl Index for Id {
+            type Key = u32;
+
+            fn index(&self) -> &Self::Key {
+                &self.0
+            }
+        }
+
+        // With 64 items in 2048 buckets, most items must have their own home bucket.
+        let mut map = IndexMap::with_capacity(1024);
+        for i in 0..64 {
+            map.insert(Id(i));
+        }
+        assert!(map.stats().hash_collisions < 16);
+    }
+
+    #[test]
+    fn collisions() {
+        let mut map = IndexMap::new();
+        assert!(map.try_insert(Named("a", 1)).is_ok());
+        let err = map.try_insert(Named("a", 2)).unwrap_err();
+        assert_eq!((err.existing.1, err.value.1), (1, 2));
+        assert_eq!(map.insert_replace(Named("a", 3)).map(|v| v.1), Some(1));
+        assert!(map.insert_replace(Named("b", 4)).is_none());
+        assert_eq!(map["a"].1, 3);
+    }
+
+    #[cfg(feature = "index-map-metrics")]
+    #[test]
+    fn metrics() {
+        let mut map = IndexMap::new();
+        map.insert(Named("a", 1));
+        map.insert(Named("a", 2));
+        map.insert_replace(Named("a", 3));
+        assert!(map.get("a").is_some());
+        assert!(map.get("b").is_none());
+        map.get_or_insert_with("b", || Named("b", 4));
+        let metrics = map.metrics();
+        assert_eq!((metrics.gets, metrics.hits, metrics.misses), (3, 1, 2));
+        assert_eq!((metrics.inserts, metrics.evictions), (3, 1));
+        assert_eq!(metrics.hit_rate(), 1.0 / 3.0);
+        map.reset_metrics();
+        assert_eq!(map.metrics(), Default::default());
+    }
+}
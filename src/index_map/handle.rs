@@ -0,0 +1,252 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::index_map::{DefaultHasher, Index};
+use alloc::vec::Vec;
+use core::hash::BuildHasher;
+use hashbrown::HashTable;
+
+/// A lightweight handle to an element of a [HandleIndexMap], made of a slot index and a
+/// generation.
+///
+/// A handle is invalidated when its element is removed or replaced, even if the slot is later
+/// reused by another element.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+struct Slot<V> {
+    generation: u32,
+    value: Option<V>,
+}
+
+/// A map which stores elements by their key, like [IndexMap](crate::index_map::IndexMap), and
+/// also hands out [Handle]s giving O(1) access to elements without hashing.
+///
+/// Elements are stored in a slot array which is never reordered, such that handles stay valid
+/// across rehashes.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::index_map::{HandleIndexMap, Index};
+///
+/// struct Entity(&'static str);
+///
+/// impl Index for Entity {
+///     type Key = str;
+///
+///     fn index(&self) -> &str {
+///         self.0
+///     }
+/// }
+///
+/// let mut map = HandleIndexMap::new();
+/// let player = map.insert(Entity("player"));
+/// assert_eq!(map.get(player).unwrap().0, "player");
+/// assert_eq!(map.handle_of("player"), Some(player));
+/// map.remove(player);
+/// assert!(map.get(player).is_none());
+/// ```
+pub struct HandleIndexMap<V, S = DefaultHasher> {
+    table: HashTable<(u64, u32)>,
+    slots: Vec<Slot<V>>,
+    free: Vec<u32>,
+    hasher: S,
+}
+
+impl<V, S: Default> Default for HandleIndexMap<V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<V> HandleIndexMap<V> {
+    /// Creates a new instance of a [HandleIndexMap].
+    pub fn new() -> HandleIndexMap<V> {
+        Self::with_hasher(DefaultHasher::default())
+    }
+}
+
+impl<V, S> HandleIndexMap<V, S> {
+    /// Creates a new instance of a [HandleIndexMap] with a given hasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `hasher`: the hasher to use for hashing keys.
+    pub fn with_hasher(hasher: S) -> HandleIndexMap<V, S> {
+        HandleIndexMap {
+            table: HashTable::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            hasher,
+        }
+    }
+
+    /// Returns the number of elements in this [HandleIndexMap].
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns true when this [HandleIndexMap] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Gets an element from its handle.
+    ///
+    /// returns: Option<&V> None if the handle was invalidated.
+    pub fn get(&self, handle: Handle) -> Option<&V> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    /// Returns an iterator over all elements with their handle, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &V)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let handle = Handle {
+                index: index as u32,
+                generation: slot.generation,
+            };
+            slot.value.as_ref().map(|v| (handle, v))
+        })
+    }
+}
+
+impl<V: Index, S: BuildHasher> HandleIndexMap<V, S> {
+    fn find(&self, key: &V::Key) -> Option<u32> {
+        let hash = self.hasher.hash_one(key);
+        let slots = &self.slots;
+        self.table
+            .find(hash, |(h, i)| {
+                *h == hash
+                    && slots[*i as usize]
+                        .value
+                        .as_ref()
+                        .is_some_and(|v| v.index() == key)
+            })
+            .map(|(_, i)| *i)
+    }
+
+    fn release(&mut self, index: u32) -> Option<V> {
+        let slot = &mut self.slots[index as usize];
+        let value = slot.value.take()?;
+        let hash = self.hasher.hash_one(value.index());
+        if let Ok(entry) = self.table.find_entry(hash, |(_, i)| *i == index) {
+            entry.remove();
+        }
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(index);
+        Some(value)
+    }
+
+    /// Inserts a new element in this [HandleIndexMap].
+    ///
+    /// An existing element with the same key is replaced and its handle is invalidated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map already stores [u32::MAX] slots.
+    pub fn insert(&mut self, value: V) -> Handle {
+        if let Some(index) = self.find(value.index()) {
+            self.release(index);
+        }
+        let hash = self.hasher.hash_one(value.index());
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index as usize].value = Some(value);
+                index
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("too many slots");
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                index
+            }
+        };
+        self.table.insert_unique(hash, (hash, index), |(h, _)| *h);
+        Handle {
+            index,
+            generation: self.slots[index as usize].generation,
+        }
+    }
+
+    /// Gets an element from its key.
+    pub fn get_by_key(&self, key: &V::Key) -> Option<&V> {
+        self.find(key)
+            .and_then(|i| self.slots[i as usize].value.as_ref())
+    }
+
+    /// Returns the handle of the element stored with a given key.
+    pub fn handle_of(&self, key: &V::Key) -> Option<Handle> {
+        self.find(key).map(|index| Handle {
+            index,
+            generation: self.slots[index as usize].generation,
+        })
+    }
+
+    /// Removes an element from its handle, invalidating the handle.
+    ///
+    /// returns: Option<V> None if the handle was already invalidated.
+    pub fn remove(&mut self, handle: Handle) -> Option<V> {
+        self.get(handle)?;
+        self.release(handle.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index_map::tests::Named;
+    use crate::index_map::HandleIndexMap;
+
+    #[test]
+    fn handles() {
+        let mut map = HandleIndexMap::new();
+        let a = map.insert(Named("a", 1));
+        let b = map.insert(Named("b", 2));
+        assert_eq!(map.get(b).unwrap().1, 2);
+        assert_eq!(map.get_by_key("a").unwrap().1, 1);
+        let a2 = map.insert(Named("a", 3));
+        assert!(map.get(a).is_none());
+        assert_eq!(map.get(a2).unwrap().1, 3);
+        assert_eq!(map.remove(b).unwrap().1, 2);
+        assert!(map.remove(b).is_none());
+        let c = map.insert(Named("c", 4));
+        assert!(map.get(b).is_none());
+        assert_eq!(map.get(c).unwrap().1, 4);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.iter().count(), 2);
+        assert!(map.get_by_key("b").is_none());
+    }
+}
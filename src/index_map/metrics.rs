@@ -0,0 +1,138 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+#[cfg(feature = "index-map-metrics")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counters of the operations performed on an [IndexMap](crate::index_map::IndexMap).
+#[cfg(feature = "index-map-metrics")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// The number of lookups by key, including lookups performed by `get_or_insert_with`.
+    pub gets: usize,
+
+    /// The number of lookups which found an element.
+    pub hits: usize,
+
+    /// The number of lookups which did not find any element.
+    pub misses: usize,
+
+    /// The number of elements inserted, including replacements.
+    pub inserts: usize,
+
+    /// The number of elements replaced by `insert_replace`.
+    pub evictions: usize,
+}
+
+#[cfg(feature = "index-map-metrics")]
+impl Metrics {
+    /// Returns the ratio of lookups which found an element, or 0 if no lookup was performed.
+    pub fn hit_rate(&self) -> f32 {
+        match self.gets {
+            0 => 0.0,
+            v => self.hits as f32 / v as f32,
+        }
+    }
+}
+
+/// The counters stored in each map; this is a zero-sized type when metrics are disabled.
+#[derive(Debug, Default)]
+pub(super) struct Counters {
+    #[cfg(feature = "index-map-metrics")]
+    gets: AtomicUsize,
+    #[cfg(feature = "index-map-metrics")]
+    hits: AtomicUsize,
+    #[cfg(feature = "index-map-metrics")]
+    inserts: AtomicUsize,
+    #[cfg(feature = "index-map-metrics")]
+    evictions: AtomicUsize,
+}
+
+impl Clone for Counters {
+    fn clone(&self) -> Self {
+        #[cfg(feature = "index-map-metrics")]
+        {
+            let metrics = self.snapshot();
+            Counters {
+                gets: AtomicUsize::new(metrics.gets),
+                hits: AtomicUsize::new(metrics.hits),
+                inserts: AtomicUsize::new(metrics.inserts),
+                evictions: AtomicUsize::new(metrics.evictions),
+            }
+        }
+        #[cfg(not(feature = "index-map-metrics"))]
+        Counters {}
+    }
+}
+
+impl Counters {
+    #[inline]
+    pub fn get(&self, _hit: bool) {
+        #[cfg(feature = "index-map-metrics")]
+        {
+            self.gets.fetch_add(1, Ordering::Relaxed);
+            if _hit {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn insert(&self) {
+        #[cfg(feature = "index-map-metrics")]
+        self.inserts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn evict(&self) {
+        #[cfg(feature = "index-map-metrics")]
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "index-map-metrics")]
+    pub fn snapshot(&self) -> Metrics {
+        // A get may complete between the 2 loads, such that hits may exceed gets.
+        let gets = self.gets.load(Ordering::Relaxed);
+        let hits = self.hits.load(Ordering::Relaxed);
+        Metrics {
+            gets,
+            hits,
+            misses: gets.saturating_sub(hits),
+            inserts: self.inserts.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    #[cfg(feature = "index-map-metrics")]
+    pub fn reset(&self) {
+        self.gets.store(0, Ordering::Relaxed);
+        self.hits.store(0, Ordering::Relaxed);
+        self.inserts.store(0, Ordering::Relaxed);
+        self.evictions.store(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,425 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::index_map::{DefaultHasher, Index};
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+use hashbrown::HashTable;
+
+/// A secondary index to implement for each type to be stored in a [MultiIndexMap].
+///
+/// A type may implement several secondary indices, numbered from 0 by `I`.
+pub trait SecondaryIndex<const I: usize = 0>: Index {
+    /// The type of the secondary key.
+    type SecondaryKey: ?Sized + Hash + PartialEq + Eq;
+
+    /// The index function which returns a reference to the secondary key stored in the object.
+    fn secondary_index(&self) -> &Self::SecondaryKey;
+}
+
+// The secondary indices 0 to N - 1 of a type, addressed at runtime.
+trait SecondaryIndices<const N: usize>: Index {
+    fn hash_secondary<S: BuildHasher>(&self, i: usize, hasher: &S) -> u64;
+    fn eq_secondary(&self, i: usize, other: &Self) -> bool;
+}
+
+macro_rules! impl_secondary_indices {
+    ($($n: literal => ($($i: literal),*))*) => {
+        $(
+            impl<V: Index $(+ SecondaryIndex<$i>)*> SecondaryIndices<$n> for V {
+                fn hash_secondary<S: BuildHasher>(&self, i: usize, hasher: &S) -> u64 {
+                    match i {
+                        $($i => hasher.hash_one(SecondaryIndex::<$i>::secondary_index(self)),)*
+                        _ => unreachable!(),
+                    }
+                }
+
+                fn eq_secondary(&self, i: usize, other: &Self) -> bool {
+                    match i {
+                        $($i => SecondaryIndex::<$i>::secondary_index(self)
+                            == SecondaryIndex::<$i>::secondary_index(other),)*
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_secondary_indices! {
+    1 => (0)
+    2 => (0, 1)
+    3 => (0, 1, 2)
+    4 => (0, 1, 2, 3)
+}
+
+/// A map with a primary key and `N` secondary keys stored as part of the value.
+///
+/// The value type must implement [SecondaryIndex] for each index from 0 to `N - 1`; up to 4
+/// secondary indices are supported.
+///
+/// All indices are kept consistent on insert and remove: inserting a value evicts any existing
+/// value sharing either its primary key or any of its secondary keys.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::index_map::{DefaultHasher, Index, MultiIndexMap, SecondaryIndex};
+///
+/// struct User {
+///     name: &'static str,
+///     id: u32,
+///     email: &'static str,
+/// }
+///
+/// impl Index for User {
+///     type Key = str;
+///
+///     fn index(&self) -> &Self::Key {
+///         self.name
+///     }
+/// }
+///
+/// impl SecondaryIndex<0> for User {
+///     type SecondaryKey = u32;
+///
+///     fn secondary_index(&self) -> &Self::SecondaryKey {
+///         &self.id
+///     }
+/// }
+///
+/// impl SecondaryIndex<1> for User {
+///     type SecondaryKey = str;
+///
+///     fn secondary_index(&self) -> &Self::SecondaryKey {
+///         self.email
+///     }
+/// }
+///
+/// let mut map = MultiIndexMap::<User, DefaultHasher, 2>::default();
+/// map.insert(User { name: "root", id: 0, email: "root@localhost" });
+/// assert_eq!(map.get_by::<0>(&0).unwrap().name, "root");
+/// assert_eq!(map.get_by::<1>("root@localhost").unwrap().id, 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiIndexMap<V, S = DefaultHasher, const N: usize = 1> {
+    values: Vec<Option<V>>,
+    free_list: Vec<usize>,
+    primary: HashTable<usize>,
+    secondary: [HashTable<usize>; N],
+    hasher: S,
+}
+
+impl<V, S: Default, const N: usize> Default for MultiIndexMap<V, S, N> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<V> MultiIndexMap<V> {
+    /// Creates a new instance of a [MultiIndexMap] with a single secondary index.
+    pub fn new() -> MultiIndexMap<V> {
+        Self::with_hasher(DefaultHasher::default())
+    }
+}
+
+impl<V, S, const N: usize> MultiIndexMap<V, S, N> {
+    /// Creates a new instance of a [MultiIndexMap] with a given hasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `hasher`: the hasher to use for hashing keys.
+    pub fn with_hasher(hasher: S) -> MultiIndexMap<V, S, N> {
+        MultiIndexMap {
+            values: Vec::new(),
+            free_list: Vec::new(),
+            primary: HashTable::new(),
+            secondary: core::array::from_fn(|_| HashTable::new()),
+            hasher,
+        }
+    }
+
+    /// Returns the number of items in this [MultiIndexMap].
+    pub fn len(&self) -> usize {
+        self.primary.len()
+    }
+
+    /// Returns true when this [MultiIndexMap] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.primary.is_empty()
+    }
+
+    /// Returns an iterator over all elements contained in the map.
+    pub fn iter(&self) -> impl Iterator<Item = &V> {
+        self.values.iter().filter_map(|v| v.as_ref())
+    }
+}
+
+#[allow(private_bounds)] // Because Rust is a piece of shit!!
+impl<V: SecondaryIndices<N>, S: BuildHasher, const N: usize> MultiIndexMap<V, S, N> {
+    fn find(&self, key: &V::Key) -> Option<usize> {
+        let values = &self.values;
+        self.primary
+            .find(self.hasher.hash_one(key), |i| {
+                values[*i].as_ref().map(|v| v.index()) == Some(key)
+            })
+            .copied()
+    }
+
+    fn find_secondary<const I: usize>(
+        &self,
+        key: &<V as SecondaryIndex<I>>::SecondaryKey,
+    ) -> Option<usize>
+    where
+        V: SecondaryIndex<I>,
+    {
+        let values = &self.values;
+        self.secondary[I]
+            .find(self.hasher.hash_one(key), |i| {
+                values[*i]
+                    .as_ref()
+                    .map(|v| SecondaryIndex::<I>::secondary_index(v))
+                    == Some(key)
+            })
+            .copied()
+    }
+
+    fn remove_slot(&mut self, slot: usize) -> Option<V> {
+        let value = self.values[slot].take()?;
+        let hash = self.hasher.hash_one(value.index());
+        if let Ok(entry) = self.primary.find_entry(hash, |i| *i == slot) {
+            entry.remove();
+        }
+        for (index, table) in self.secondary.iter_mut().enumerate() {
+            let hash = value.hash_secondary(index, &self.hasher);
+            if let Ok(entry) = table.find_entry(hash, |i| *i == slot) {
+                entry.remove();
+            }
+        }
+        self.free_list.push(slot);
+        Some(value)
+    }
+
+    /// Inserts a new item in this [MultiIndexMap].
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to be inserted.
+    ///
+    /// returns: Vec<V> the values which were evicted because they shared the primary key or a
+    /// secondary key of the new value.
+    pub fn insert(&mut self, value: V) -> Vec<V> {
+        let mut evicted = Vec::new();
+        if let Some(slot) = self.find(value.index()) {
+            evicted.extend(self.remove_slot(slot));
+        }
+        for index in 0..N {
+            let hash = value.hash_secondary(index, &self.hasher);
+            let values = &self.values;
+            let slot = self.secondary[index]
+                .find(hash, |i| {
+                    values[*i]
+                        .as_ref()
+                        .is_some_and(|v| v.eq_secondary(index, &value))
+                })
+                .copied();
+            if let Some(slot) = slot {
+                evicted.extend(self.remove_slot(slot));
+            }
+        }
+        let primary_hash = self.hasher.hash_one(value.index());
+        let secondary_hashes: [u64; N] =
+            core::array::from_fn(|index| value.hash_secondary(index, &self.hasher));
+        let slot = match self.free_list.pop() {
+            Some(slot) => {
+                self.values[slot] = Some(value);
+                slot
+            }
+            None => {
+                self.values.push(Some(value));
+                self.values.len() - 1
+            }
+        };
+        let (values, hasher) = (&self.values, &self.hasher);
+        self.primary.insert_unique(primary_hash, slot, |i| {
+            hasher.hash_one(values[*i].as_ref().unwrap().index())
+        });
+        for (index, table) in self.secondary.iter_mut().enumerate() {
+            table.insert_unique(secondary_hashes[index], slot, |i| {
+                values[*i].as_ref().unwrap().hash_secondary(index, hasher)
+            });
+        }
+        evicted
+    }
+
+    /// Gets an element stored in this [MultiIndexMap] from its primary key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the primary key of the element to look for.
+    pub fn get(&self, key: &V::Key) -> Option<&V> {
+        self.find(key).and_then(|i| self.values[i].as_ref())
+    }
+
+    /// Gets an element stored in this [MultiIndexMap] from its secondary key number `I`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the secondary key of the element to look for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `I` is not less than the number of secondary indices of this map.
+    pub fn get_by<const I: usize>(&self, key: &<V as SecondaryIndex<I>>::SecondaryKey) -> Option<&V>
+    where
+        V: SecondaryIndex<I>,
+    {
+        self.find_secondary::<I>(key)
+            .and_then(|i| self.values[i].as_ref())
+    }
+
+    /// Gets an element stored in this [MultiIndexMap] from its first secondary key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the secondary key of the element to look for.
+    pub fn get_by_secondary(&self, key: &<V as SecondaryIndex>::SecondaryKey) -> Option<&V>
+    where
+        V: SecondaryIndex,
+    {
+        self.get_by::<0>(key)
+    }
+
+    /// Removes an element from this [MultiIndexMap] from its primary key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the primary key of the element to remove.
+    pub fn remove(&mut self, key: &V::Key) -> Option<V> {
+        self.find(key).and_then(|i| self.remove_slot(i))
+    }
+
+    /// Removes an element from this [MultiIndexMap] from its secondary key number `I`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the secondary key of the element to remove.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `I` is not less than the number of secondary indices of this map.
+    pub fn remove_by<const I: usize>(
+        &mut self,
+        key: &<V as SecondaryIndex<I>>::SecondaryKey,
+    ) -> Option<V>
+    where
+        V: SecondaryIndex<I>,
+    {
+        self.find_secondary::<I>(key)
+            .and_then(|i| self.remove_slot(i))
+    }
+
+    /// Removes an element from this [MultiIndexMap] from its first secondary key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the secondary key of the element to remove.
+    pub fn remove_by_secondary(&mut self, key: &<V as SecondaryIndex>::SecondaryKey) -> Option<V>
+    where
+        V: SecondaryIndex,
+    {
+        self.remove_by::<0>(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index_map::{DefaultHasher, Index, MultiIndexMap, SecondaryIndex};
+
+    #[derive(Debug, PartialEq)]
+    struct Object(&'static str, u32, char);
+
+    impl Index for Object {
+        type Key = str;
+
+        fn index(&self) -> &Self::Key {
+            self.0
+        }
+    }
+
+    impl SecondaryIndex for Object {
+        type SecondaryKey = u32;
+
+        fn secondary_index(&self) -> &Self::SecondaryKey {
+            &self.1
+        }
+    }
+
+    impl SecondaryIndex<1> for Object {
+        type SecondaryKey = char;
+
+        fn secondary_index(&self) -> &Self::SecondaryKey {
+            &self.2
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let mut map = MultiIndexMap::new();
+        assert!(map.insert(Object("a", 1, 'a')).is_empty());
+        assert!(map.insert(Object("b", 2, 'b')).is_empty());
+        assert_eq!(map.get("a"), Some(&Object("a", 1, 'a')));
+        assert_eq!(map.get_by_secondary(&2), Some(&Object("b", 2, 'b')));
+        assert_eq!(map.insert(Object("c", 1, 'c')), [Object("a", 1, 'a')]);
+        assert!(map.get("a").is_none());
+        assert_eq!(map.remove("c"), Some(Object("c", 1, 'c')));
+        assert!(map.get_by_secondary(&1).is_none());
+        assert_eq!(map.insert(Object("b", 3, 'b')), [Object("b", 2, 'b')]);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.remove_by_secondary(&3), Some(Object("b", 3, 'b')));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn several() {
+        let mut map = MultiIndexMap::<Object, DefaultHasher, 2>::default();
+        assert!(map.insert(Object("a", 1, 'a')).is_empty());
+        assert!(map.insert(Object("b", 2, 'b')).is_empty());
+        assert_eq!(map.get_by::<1>(&'b'), Some(&Object("b", 2, 'b')));
+        assert_eq!(
+            map.insert(Object("c", 1, 'b')),
+            [Object("a", 1, 'a'), Object("b", 2, 'b')]
+        );
+        assert_eq!(map.len(), 1);
+        assert!(map.get_by::<0>(&2).is_none());
+        assert_eq!(map.remove_by::<1>(&'b'), Some(Object("c", 1, 'b')));
+        assert!(map.get("c").is_none());
+        assert!(map.get_by::<0>(&1).is_none());
+        assert!(map.is_empty());
+    }
+}
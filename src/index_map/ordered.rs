@@ -0,0 +1,271 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::index_map::{Index, Item};
+use alloc::collections::btree_set::{self, BTreeSet};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::ops::{Bound, RangeBounds};
+
+impl<V: Index> PartialOrd for Item<V>
+where
+    V::Key: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<V: Index> Ord for Item<V>
+where
+    V::Key: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.index().cmp(other.0.index())
+    }
+}
+
+/// A map with the key stored as part of the value, which keeps its elements sorted by key to
+/// support range and prefix queries.
+///
+/// This map type uses a [BTreeSet] to store the underlying items.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::index_map::{Index, OrderedKeyIndexMap};
+///
+/// struct Command(&'static str);
+///
+/// impl Index for Command {
+///     type Key = str;
+///
+///     fn index(&self) -> &str {
+///         self.0
+///     }
+/// }
+///
+/// let mut registry = OrderedKeyIndexMap::new();
+/// registry.insert(Command("mesh.load"));
+/// registry.insert(Command("texture.load"));
+/// registry.insert(Command("mesh.bake"));
+/// let names: Vec<_> = registry.prefix("mesh.").map(|v| v.0).collect();
+/// assert_eq!(names, ["mesh.bake", "mesh.load"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct OrderedKeyIndexMap<V>(BTreeSet<Item<V>>);
+
+impl<V> Default for OrderedKeyIndexMap<V> {
+    fn default() -> Self {
+        Self(BTreeSet::new())
+    }
+}
+
+impl<V> OrderedKeyIndexMap<V> {
+    /// Creates a new instance of an [OrderedKeyIndexMap].
+    pub fn new() -> OrderedKeyIndexMap<V> {
+        Self::default()
+    }
+
+    /// Returns the number of items in this [OrderedKeyIndexMap].
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true when this [OrderedKeyIndexMap] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over all elements contained in the map, in ascending key order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.0.iter().map(|v| &v.0)
+    }
+}
+
+impl<V: Index> OrderedKeyIndexMap<V>
+where
+    V::Key: Ord,
+{
+    /// Inserts a new item in this [OrderedKeyIndexMap].
+    ///
+    /// If an element with the same key already exists, it is kept and `value` is dropped, as
+    /// done by [IndexMap::insert](crate::index_map::IndexMap::insert).
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to be inserted.
+    ///
+    /// returns: ()
+    pub fn insert(&mut self, value: V) {
+        self.0.insert(Item(value));
+    }
+
+    /// Inserts a new item in this [OrderedKeyIndexMap], replacing any element with the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to be inserted.
+    ///
+    /// returns: Option<V> the evicted element, if any.
+    pub fn insert_replace(&mut self, value: V) -> Option<V> {
+        self.0.replace(Item(value)).map(|v| v.0)
+    }
+
+    /// Gets an element stored in this [OrderedKeyIndexMap] from its key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the key of the element to look for.
+    ///
+    /// returns: Option<&V>
+    #[allow(private_bounds)] // Because Rust is a piece of shit!!
+    pub fn get(&self, key: &V::Key) -> Option<&V>
+    where
+        Item<V>: Borrow<V::Key>,
+    {
+        self.0.get(key).map(|v| &v.0)
+    }
+
+    /// Removes an element from this [OrderedKeyIndexMap].
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the key of the element to remove.
+    ///
+    /// returns: Option<V> the removed element, if any.
+    #[allow(private_bounds)] // Because Rust is a piece of shit!!
+    pub fn remove(&mut self, key: &V::Key) -> Option<V>
+    where
+        Item<V>: Borrow<V::Key>,
+    {
+        self.0.take(key).map(|v| v.0)
+    }
+
+    /// Returns an iterator over the elements whose key is within a range, in ascending key
+    /// order.
+    ///
+    /// # Arguments
+    ///
+    /// * `range`: the range of keys (ex: `10..`). Unsized keys such as [str] need a pair of
+    ///   [Bound] instead (ex: `(Bound::Included("a"), Bound::Excluded("c"))`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if both are equal and
+    /// excluded, as done by [BTreeSet::range].
+    #[allow(private_bounds)] // Because Rust is a piece of shit!!
+    pub fn range<R: RangeBounds<V::Key>>(&self, range: R) -> Range<'_, V>
+    where
+        Item<V>: Borrow<V::Key>,
+    {
+        Range(self.0.range::<V::Key, _>(range))
+    }
+}
+
+impl<V: Index<Key = str>> OrderedKeyIndexMap<V> {
+    /// Returns an iterator over the elements whose key starts with `prefix`, in ascending key
+    /// order.
+    ///
+    /// Only the matching elements are visited.
+    pub fn prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a V> + 'a {
+        self.0
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .map(|v| &v.0)
+            .take_while(move |v| v.index().starts_with(prefix))
+    }
+}
+
+/// An iterator over a range of elements of an [OrderedKeyIndexMap].
+pub struct Range<'a, V>(btree_set::Range<'a, Item<V>>);
+
+impl<'a, V> Iterator for Range<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|v| &v.0)
+    }
+}
+
+impl<V> DoubleEndedIterator for Range<'_, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|v| &v.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index_map::tests::Named;
+    use crate::index_map::{Index, OrderedKeyIndexMap};
+    use alloc::vec::Vec;
+    use core::ops::Bound;
+
+    struct Id(usize, u32);
+
+    impl Index for Id {
+        type Key = usize;
+
+        fn index(&self) -> &Self::Key {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn prefix() {
+        let mut map = OrderedKeyIndexMap::new();
+        for name in ["mesh", "mesh.load", "meshes", "mesh.bake", "a", "z"] {
+            map.insert(Named(name, 0));
+        }
+        let found: Vec<_> = map.prefix("mesh.").map(|v| v.0).collect();
+        assert_eq!(found, ["mesh.bake", "mesh.load"]);
+        let found: Vec<_> = map
+            .range::<(Bound<&str>, _)>((Bound::Included("b"), Bound::Excluded("mesh.c")))
+            .map(|v| v.0)
+            .collect();
+        assert_eq!(found, ["mesh", "mesh.bake"]);
+        assert_eq!(map.prefix("x").count(), 0);
+        assert_eq!(map.iter().next().map(|v| v.0), Some("a"));
+        assert!(map.remove("a").is_some());
+        assert!(map.get("a").is_none());
+    }
+
+    #[test]
+    fn range() {
+        let mut map = OrderedKeyIndexMap::new();
+        for i in 0..10 {
+            map.insert(Id(i, i as u32));
+        }
+        map.insert(Id(3, 42));
+        assert_eq!(map.get(&3).map(|v| v.1), Some(3));
+        assert_eq!(map.insert_replace(Id(3, 42)).map(|v| v.1), Some(3));
+        let found: Vec<_> = map.range(2..5).map(|v| v.1).collect();
+        assert_eq!(found, [2, 42, 4]);
+        assert_eq!(map.range(8..).next_back().map(|v| v.0), Some(9));
+        assert_eq!(map.len(), 10);
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::index_map::{DefaultHasher, Index, IndexMap};
+use alloc::sync::Arc;
+use core::hash::BuildHasher;
+use core::ops::Deref;
+
+/// An immutable, cheaply cloneable view of an [IndexMap], obtained from [CowIndexMap::snapshot].
+///
+/// A snapshot is never affected by later mutations of the [CowIndexMap] it was taken from.
+#[derive(Debug)]
+pub struct Snapshot<V, S = DefaultHasher>(Arc<IndexMap<V, S>>);
+
+impl<V, S> Clone for Snapshot<V, S> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<V, S> Deref for Snapshot<V, S> {
+    type Target = IndexMap<V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// An [IndexMap] with copy-on-write semantics, allowing to take consistent snapshots for readers
+/// in other threads while the writer continues mutating the map.
+///
+/// Taking a snapshot is O(1); the first mutation after a snapshot was taken clones the map if
+/// the snapshot is still alive. Storing values behind an [Arc] keeps that clone cheap.
+#[derive(Debug)]
+pub struct CowIndexMap<V, S = DefaultHasher>(Arc<IndexMap<V, S>>);
+
+impl<V, S: Default> Default for CowIndexMap<V, S> {
+    fn default() -> Self {
+        Self(Arc::new(IndexMap::default()))
+    }
+}
+
+impl<V> CowIndexMap<V> {
+    /// Creates a new empty [CowIndexMap].
+    pub fn new() -> Self {
+        Self(Arc::new(IndexMap::new()))
+    }
+}
+
+impl<V, S> From<IndexMap<V, S>> for CowIndexMap<V, S> {
+    fn from(value: IndexMap<V, S>) -> Self {
+        Self(Arc::new(value))
+    }
+}
+
+impl<V, S> Deref for CowIndexMap<V, S> {
+    type Target = IndexMap<V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V, S> CowIndexMap<V, S> {
+    /// Returns an immutable view of the current content of this map.
+    pub fn snapshot(&self) -> Snapshot<V, S> {
+        Snapshot(self.0.clone())
+    }
+}
+
+impl<V: Clone, S: Clone> CowIndexMap<V, S> {
+    /// Returns a mutable reference to the underlying map, cloning it first if a [Snapshot] of it
+    /// is still alive.
+    pub fn make_mut(&mut self) -> &mut IndexMap<V, S> {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
+impl<V: Index + Clone, S: BuildHasher + Clone> CowIndexMap<V, S> {
+    /// Inserts a new item in this map, see [IndexMap::insert].
+    pub fn insert(&mut self, value: V) {
+        self.make_mut().insert(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index_map::tests::Named;
+    use crate::index_map::CowIndexMap;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn snapshot() {
+        let mut map = CowIndexMap::new();
+        map.insert(Arc::new(Named("a", 0)));
+        let snapshot = map.snapshot();
+        map.insert(Arc::new(Named("b", 0)));
+        assert_eq!(snapshot.len(), 1);
+        assert!(snapshot.get("b").is_none());
+        assert_eq!(map.len(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn send() {
+        let mut map = CowIndexMap::new();
+        map.insert(Arc::new(Named("a", 0)));
+        let snapshot = map.snapshot();
+        let handle = std::thread::spawn(move || snapshot.get("a").is_some());
+        assert!(handle.join().unwrap());
+    }
+}
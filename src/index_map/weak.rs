@@ -0,0 +1,267 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::index_map::{DefaultHasher, Index};
+use alloc::rc::{self, Rc};
+use alloc::sync::{self, Arc};
+use core::hash::BuildHasher;
+use core::ops::Deref;
+use hashbrown::HashTable;
+
+/// A reference counted pointer which can be stored as a weak reference in a [WeakIndexMap].
+pub trait Shared: Deref + Sized {
+    /// The type of the weak reference.
+    type Weak;
+
+    /// Creates a new weak reference to this pointer.
+    fn downgrade(this: &Self) -> Self::Weak;
+
+    /// Attempts to upgrade a weak reference, returns None if the value was dropped.
+    fn upgrade(weak: &Self::Weak) -> Option<Self>;
+
+    /// Returns true if the value behind a weak reference was dropped.
+    fn is_dead(weak: &Self::Weak) -> bool;
+}
+
+impl<T> Shared for Rc<T> {
+    type Weak = rc::Weak<T>;
+
+    fn downgrade(this: &Self) -> Self::Weak {
+        Rc::downgrade(this)
+    }
+
+    fn upgrade(weak: &Self::Weak) -> Option<Self> {
+        weak.upgrade()
+    }
+
+    fn is_dead(weak: &Self::Weak) -> bool {
+        weak.strong_count() == 0
+    }
+}
+
+impl<T> Shared for Arc<T> {
+    type Weak = sync::Weak<T>;
+
+    fn downgrade(this: &Self) -> Self::Weak {
+        Arc::downgrade(this)
+    }
+
+    fn upgrade(weak: &Self::Weak) -> Option<Self> {
+        weak.upgrade()
+    }
+
+    fn is_dead(weak: &Self::Weak) -> bool {
+        weak.strong_count() == 0
+    }
+}
+
+/// A map storing weak references to values which are kept alive elsewhere, with the key stored
+/// as part of the value.
+///
+/// Entries whose value was dropped are pruned lazily: on insert of a value with the same key
+/// hash, before the map would grow and on explicit [purge](WeakIndexMap::purge).
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::index_map::{Index, WeakIndexMap};
+/// use std::rc::Rc;
+///
+/// struct Asset(&'static str);
+///
+/// impl Index for Asset {
+///     type Key = str;
+///
+///     fn index(&self) -> &str {
+///         self.0
+///     }
+/// }
+///
+/// let mut cache = WeakIndexMap::new();
+/// let asset = Rc::new(Asset("logo.png"));
+/// cache.insert(&asset);
+/// assert!(cache.get("logo.png").is_some());
+/// drop(asset);
+/// assert!(cache.get("logo.png").is_none());
+/// ```
+pub struct WeakIndexMap<P: Shared, S = DefaultHasher> {
+    table: HashTable<(u64, P::Weak)>,
+    hasher: S,
+}
+
+impl<P: Shared, S: Default> Default for WeakIndexMap<P, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<P: Shared> WeakIndexMap<P> {
+    /// Creates a new instance of a [WeakIndexMap].
+    pub fn new() -> WeakIndexMap<P> {
+        Self::with_hasher(DefaultHasher::default())
+    }
+}
+
+impl<P: Shared, S> WeakIndexMap<P, S> {
+    /// Creates a new instance of a [WeakIndexMap] with a given hasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `hasher`: the hasher to use for hashing keys.
+    pub fn with_hasher(hasher: S) -> WeakIndexMap<P, S> {
+        WeakIndexMap {
+            table: HashTable::new(),
+            hasher,
+        }
+    }
+
+    /// Returns the number of entries in this [WeakIndexMap], including entries whose value was
+    /// dropped but which were not yet pruned.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns true when this [WeakIndexMap] has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Returns an iterator over all values still alive in the map.
+    pub fn iter(&self) -> impl Iterator<Item = P> + '_ {
+        self.table.iter().filter_map(|(_, w)| P::upgrade(w))
+    }
+
+    /// Removes all entries whose value was dropped.
+    pub fn purge(&mut self) {
+        self.table.retain(|(_, w)| !P::is_dead(w));
+    }
+}
+
+impl<P: Shared, S: BuildHasher> WeakIndexMap<P, S>
+where
+    P::Target: Index,
+{
+    /// Inserts a new weak reference to a value in this [WeakIndexMap].
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to reference.
+    ///
+    /// returns: Option<P> the previous value stored with the same key if it is still alive.
+    pub fn insert(&mut self, value: &P) -> Option<P> {
+        let key = value.index();
+        let hash = self.hasher.hash_one(key);
+        let mut old = None;
+        while let Ok(entry) = self.table.find_entry(hash, |(h, w)| {
+            *h == hash && P::upgrade(w).is_none_or(|v| v.index() == key)
+        }) {
+            let ((_, w), _) = entry.remove();
+            old = P::upgrade(&w).or(old);
+        }
+        if self.table.len() == self.table.capacity() {
+            self.purge();
+        }
+        self.table
+            .insert_unique(hash, (hash, P::downgrade(value)), |(h, _)| *h);
+        old
+    }
+
+    /// Gets a value stored in this [WeakIndexMap] from its key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the key of the value to look for.
+    ///
+    /// returns: Option<P> None if no value exists with this key or if it was dropped.
+    pub fn get(&self, key: &<P::Target as Index>::Key) -> Option<P> {
+        let hash = self.hasher.hash_one(key);
+        self.table
+            .find(hash, |(h, w)| {
+                *h == hash && P::upgrade(w).is_some_and(|v| v.index() == key)
+            })
+            .and_then(|(_, w)| P::upgrade(w))
+    }
+
+    /// Removes a value from this [WeakIndexMap].
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the key of the value to remove.
+    ///
+    /// returns: Option<P> the removed value if it was still alive.
+    pub fn remove(&mut self, key: &<P::Target as Index>::Key) -> Option<P> {
+        let hash = self.hasher.hash_one(key);
+        let entry = self
+            .table
+            .find_entry(hash, |(h, w)| {
+                *h == hash && P::upgrade(w).is_some_and(|v| v.index() == key)
+            })
+            .ok()?;
+        let ((_, w), _) = entry.remove();
+        P::upgrade(&w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index_map::tests::Named;
+    use crate::index_map::WeakIndexMap;
+    use alloc::rc::Rc;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn prune() {
+        let mut map = WeakIndexMap::new();
+        let a = Rc::new(Named("a", 1));
+        let b = Rc::new(Named("b", 2));
+        map.insert(&a);
+        map.insert(&b);
+        assert_eq!(map.get("a").map(|v| v.1), Some(1));
+        drop(b);
+        assert_eq!(map.iter().count(), 1);
+        assert_eq!(map.len(), 2);
+        map.purge();
+        assert_eq!(map.len(), 1);
+        let a2 = Rc::new(Named("a", 3));
+        assert_eq!(map.insert(&a2).map(|v| v.1), Some(1));
+        assert_eq!(map.get("a").map(|v| v.1), Some(3));
+        assert_eq!(map.remove("a").map(|v| v.1), Some(3));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn arc() {
+        let mut map = WeakIndexMap::new();
+        let a = Arc::new(Named("a", 1));
+        map.insert(&a);
+        drop(a);
+        let a = Arc::new(Named("a", 2));
+        assert!(map.insert(&a).is_none());
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -27,10 +27,17 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
 //! Generic utilities not tied to any particular platform for use with other BP3D software.
 
+#[cfg(feature = "index-map")]
+extern crate alloc;
+
+#[cfg(any(feature = "format", feature = "tzif"))]
+mod civil;
+
 #[cfg(feature = "env")]
 pub mod env;
 
@@ -54,3 +61,12 @@ pub mod extension;
 
 #[cfg(feature = "index-map")]
 pub mod index_map;
+
+#[cfg(feature = "collections")]
+pub mod collections;
+
+#[cfg(feature = "string")]
+pub mod string;
+
+#[cfg(feature = "bytes")]
+pub mod bytes;
@@ -31,22 +31,450 @@
 //! This used to be in bp3d-os::fs but was moved here as it does not depend on any platform
 //! specific function.
 
+mod joiner;
+mod trie;
+
+pub use joiner::{join_all, PathJoiner};
+pub use trie::PathTrie;
+
 use crate::extension;
 use std::borrow::Cow;
-use std::ffi::OsStr;
-use std::path::Path;
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Display, Formatter, Write};
+use std::path::{Component, Path, PathBuf};
 
 extension! {
     /// Extension trait for [Path](Path) for common functionality in BP3D software.
     pub extension PathExt: Path {
         /// Ensures the given extension is present on a [Path](Path). Reallocates a new
         /// [PathBuf](std::path::PathBuf) if no extension is present or that the extension is incorrect.
-        fn ensure_extension<S: AsRef<OsStr>>(&self, extension: S) -> Cow<Path>;
+        fn ensure_extension<S: AsRef<OsStr>>(&self, extension: S) -> Cow<'_, Path>;
+
+        /// Checks if this [Path](Path) matches a glob-like pattern.
+        ///
+        /// Patterns are made of `/` separated components where `*` matches any sequence of
+        /// characters within a component, `?` matches a single character and `**` matches any
+        /// number of components (including none). Matching is purely lexical and only considers
+        /// normal components: root, prefix and `.` components are ignored.
+        ///
+        /// # Arguments
+        ///
+        /// * `pattern`: the pattern to match against (ex: `src/**/*.rs`).
+        ///
+        /// returns: bool
+        fn matches_pattern(&self, pattern: &str) -> bool;
+
+        /// Returns an object to display this [Path](Path) relative to a base directory when
+        /// possible or as-is otherwise. This never allocates.
+        ///
+        /// # Arguments
+        ///
+        /// * `base`: the base directory (ex: the workspace root).
+        ///
+        /// returns: RelativeDisplay
+        fn display_relative_to(&self, base: &Path) -> RelativeDisplay<'_>;
+
+        /// Derives a temporary sibling path by inserting `.tmp-XXXX` before the extension
+        /// (ex: `file.ext` -> `file.tmp-00ab.ext`). This is purely lexical.
+        ///
+        /// # Arguments
+        ///
+        /// * `id`: the identifier of the temporary file, formatted as hexadecimal
+        ///   (ex: a random number or a process id).
+        ///
+        /// returns: PathBuf
+        fn temp_sibling(&self, id: u32) -> PathBuf;
+
+        /// Derives a numbered sibling path (ex: `file.ext` -> `file (1).ext`). This is purely
+        /// lexical.
+        ///
+        /// # Arguments
+        ///
+        /// * `n`: the number to insert.
+        ///
+        /// returns: PathBuf
+        fn numbered_sibling(&self, n: u32) -> PathBuf;
+
+        /// Finds the first available path among this [Path](Path) and its numbered siblings
+        /// (see [numbered_sibling](PathExt::numbered_sibling)).
+        ///
+        /// # Arguments
+        ///
+        /// * `exists`: the function called to check if a path already exists.
+        ///
+        /// returns: Cow<Path> this [Path](Path) if it is available, a numbered sibling otherwise.
+        fn next_available<F: FnMut(&Path) -> bool>(&self, exists: F) -> Cow<'_, Path>;
+
+        /// Computes a content-addressed cache path under this [Path](Path) used as the cache root,
+        /// fanning out over sub-directories named after 2 character slices of the hash
+        /// (ex: `root/ab/cd/abcdef.ext` with a depth of 2).
+        ///
+        /// # Arguments
+        ///
+        /// * `hash`: the content hash (ex: a hexadecimal digest).
+        /// * `depth`: the number of sub-directory levels, clamped to half the hash length.
+        /// * `extension`: an optional extension for the cache file.
+        ///
+        /// returns: PathBuf
+        fn cache_path(&self, hash: &str, depth: usize, extension: Option<&str>) -> PathBuf;
+
+        /// Extracts the content hash from a path built with [cache_path](PathExt::cache_path).
+        ///
+        /// Returns None if this [Path](Path) is not a valid cache path under `root` with the
+        /// given depth.
+        ///
+        /// # Arguments
+        ///
+        /// * `root`: the cache root.
+        /// * `depth`: the number of sub-directory levels.
+        fn parse_cache_path(&self, root: &Path, depth: usize) -> Option<&str>;
+
+        /// Converts this [Path](Path) to a `file://` URL, percent-encoding all bytes which are
+        /// not unreserved URL characters. Windows paths are converted to forward slashes with
+        /// the drive letter kept as the first segment (ex: `file:///C:/My%20Files`).
+        ///
+        /// Returns None if this [Path](Path) is not absolute.
+        ///
+        /// See [from_file_url](from_file_url) for the reverse conversion.
+        fn to_file_url(&self) -> Option<String>;
+
+        /// Checks if the file name of this [Path](Path) follows the hidden file convention of a
+        /// leading dot (ex: `.git`). This is purely lexical: platform attributes such as the
+        /// Windows hidden flag are not checked.
+        fn is_hidden(&self) -> bool;
+
+        /// Splits this [Path](Path) into its parent directory, file stem and extension in one
+        /// call (ex: `dir/file.tar.gz` -> `(dir, file.tar, gz)`).
+        ///
+        /// See [join3](join3) to recombine the parts.
+        ///
+        /// returns: (Option<&Path>, Option<&OsStr>, Option<&OsStr>)
+        fn split3(&self) -> (Option<&Path>, Option<&OsStr>, Option<&OsStr>);
+
+        /// Returns an iterator over the ancestors of this [Path](Path), after lexical
+        /// normalization, starting with the parent directory (ex: `a/b/../c/file` yields `a/c`,
+        /// `a` and an empty path).
+        ///
+        /// `.` components are removed and `..` components cancel the preceding component; a
+        /// leading `..` is kept for relative paths and dropped for absolute paths. Symbolic
+        /// links are not resolved.
+        fn normalized_ancestors(&self) -> NormalizedAncestors;
+
+        /// Shortens this [Path](Path) for display to at most `max_chars` characters, by
+        /// replacing leading components with `…` (ex: `…/src/module/file.rs`).
+        ///
+        /// Components are never cut, except for the file name when it does not fit on its own,
+        /// in which case its start is replaced with `…`. Non UTF-8 components are displayed
+        /// lossily.
+        fn shorten(&self, max_chars: usize) -> Cow<'_, str>;
+
+        /// Replaces the home directory prefix of this [Path](Path) with `~`
+        /// (ex: `/home/user/src` -> `~/src`).
+        ///
+        /// Returns this path unchanged if it is not located in `home`.
+        fn home_relative(&self, home: &Path) -> Cow<'_, Path>;
     }
 }
 
+/// Rebuilds a path from its parent directory, file stem and extension with a single
+/// allocation; this is the reverse of [split3](PathExt::split3).
+///
+/// # Arguments
+///
+/// * `dir`: the parent directory.
+/// * `stem`: the file stem.
+/// * `extension`: the extension, without the leading dot.
+///
+/// returns: PathBuf
+pub fn join3<D: AsRef<Path>, S: AsRef<OsStr>, E: AsRef<OsStr>>(
+    dir: Option<D>,
+    stem: Option<S>,
+    extension: Option<E>,
+) -> PathBuf {
+    let dir = dir.as_ref().map(|v| v.as_ref().as_os_str());
+    let stem = stem.as_ref().map(|v| v.as_ref());
+    let extension = extension.as_ref().map(|v| v.as_ref());
+    let len = dir.map_or(0, |v| v.len() + 1)
+        + stem.map_or(0, |v| v.len())
+        + extension.map_or(0, |v| v.len() + 1);
+    let mut path = PathBuf::with_capacity(len);
+    if let Some(dir) = dir {
+        path.push(dir);
+    }
+    path.push(stem.unwrap_or_default());
+    if let Some(extension) = extension {
+        let name = path.as_mut_os_string();
+        name.push(".");
+        name.push(extension);
+    }
+    path
+}
+
+/// Ensures the given extension is present on a [PathBuf](PathBuf), modifying it in place to
+/// reuse its allocation. Nothing is changed if the extension is already correct.
+///
+/// This only needs a mutable reference to each path, so it can be applied in parallel over a
+/// slice of paths (ex: using `par_iter_mut` from rayon).
+///
+/// # Arguments
+///
+/// * `path`: the path to modify.
+/// * `extension`: the extension to ensure.
+pub fn ensure_extension_in_place<S: AsRef<OsStr>>(path: &mut PathBuf, extension: S) {
+    if path.extension() != Some(extension.as_ref()) {
+        path.set_extension(extension);
+    }
+}
+
+/// Applies [ensure_extension_in_place] to each path of an iterator.
+///
+/// # Arguments
+///
+/// * `paths`: the paths to process.
+/// * `extension`: the extension to ensure.
+///
+/// returns: impl Iterator<Item = PathBuf> an iterator which reuses the allocation of each path.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::path::ensure_extensions;
+/// use std::path::PathBuf;
+///
+/// let paths = vec![PathBuf::from("a.txt"), PathBuf::from("b")];
+/// let paths: Vec<PathBuf> = ensure_extensions(paths, "png").collect();
+/// assert_eq!(paths, [PathBuf::from("a.png"), PathBuf::from("b.png")]);
+/// ```
+pub fn ensure_extensions<I: IntoIterator<Item = PathBuf>, S: AsRef<OsStr>>(
+    paths: I,
+    extension: S,
+) -> impl Iterator<Item = PathBuf> {
+    paths.into_iter().map(move |mut path| {
+        ensure_extension_in_place(&mut path, &extension);
+        path
+    })
+}
+
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Sanitizes a string to be usable as a file name on both Windows and Unix.
+///
+/// Path separators, characters reserved on Windows (`<>:"|?*`) and control characters are
+/// replaced by the placeholder, as are trailing dots and spaces. Windows reserved device names
+/// (ex: `CON`, `lpt1.txt`) are prefixed with the placeholder and an empty name, `.` or `..`
+/// become a single placeholder.
+///
+/// # Arguments
+///
+/// * `name`: the name to sanitize (ex: a user provided title).
+/// * `placeholder`: the replacement character, which should itself be valid (ex: `_`).
+///
+/// returns: Cow<str> the name as-is if it is already valid, a sanitized copy otherwise.
+pub fn sanitize_file_name(name: &str, placeholder: char) -> Cow<'_, str> {
+    if name.is_empty() || name == "." || name == ".." {
+        return placeholder.to_string().into();
+    }
+    let trailing = name.len() - name.trim_end_matches(['.', ' ']).len();
+    let mut out = String::with_capacity(name.len() + 1);
+    let stem = name.split('.').next().unwrap_or_default();
+    if WINDOWS_RESERVED
+        .iter()
+        .any(|v| v.eq_ignore_ascii_case(stem.trim_end()))
+    {
+        out.push(placeholder);
+    }
+    for c in name[..name.len() - trailing].chars() {
+        match c {
+            '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*' => out.push(placeholder),
+            c if c.is_control() => out.push(placeholder),
+            c => out.push(c),
+        }
+    }
+    out.extend(std::iter::repeat_n(placeholder, trailing));
+    if out == name {
+        name.into()
+    } else {
+        out.into()
+    }
+}
+
+fn is_url_safe(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~' | b'/' | b':')
+}
+
+fn from_hex(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|v| v as u8)
+}
+
+/// Converts a `file://` URL to a [PathBuf](PathBuf), decoding percent-encoded bytes.
+///
+/// Only URLs with an empty or `localhost` host are supported. On Windows, the leading `/` before
+/// a drive letter is removed and forward slashes are converted to backslashes.
+///
+/// # Arguments
+///
+/// * `url`: the URL to convert (ex: `file:///usr/share/My%20Assets`).
+///
+/// returns: Option<PathBuf> None if the URL is not a supported file URL or is not correctly
+/// encoded.
+pub fn from_file_url(url: &str) -> Option<PathBuf> {
+    let rest = url
+        .get(..7)
+        .filter(|v| v.eq_ignore_ascii_case("file://"))
+        .map(|_| &url[7..])?;
+    let path = match rest.strip_prefix("localhost") {
+        Some(v) => v,
+        None => rest,
+    };
+    if !path.starts_with('/') {
+        return None;
+    }
+    let path = path.split(['?', '#']).next()?;
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hi = from_hex(iter.next()?)?;
+            let lo = from_hex(iter.next()?)?;
+            bytes.push(hi << 4 | lo);
+        } else {
+            bytes.push(b);
+        }
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Some(PathBuf::from(OsString::from_vec(bytes)))
+    }
+    #[cfg(not(unix))]
+    {
+        let path = String::from_utf8(bytes).ok()?;
+        let path = match path.as_bytes() {
+            [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+            _ => &path,
+        };
+        Some(PathBuf::from(path.replace('/', "\\")))
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    let mut depth = 0;
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir if depth > 0 => {
+                normalized.pop();
+                depth -= 1;
+            }
+            Component::ParentDir if normalized.has_root() => (),
+            Component::Normal(_) => {
+                normalized.push(component);
+                depth += 1;
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn sibling_with_stem_suffix(path: &Path, suffix: std::fmt::Arguments) -> PathBuf {
+    let mut name = OsString::new();
+    if let Some(stem) = path.file_stem() {
+        name.push(stem);
+    }
+    name.push(suffix.to_string());
+    if let Some(ext) = path.extension() {
+        name.push(".");
+        name.push(ext);
+    }
+    path.with_file_name(name)
+}
+
+/// An iterator over the ancestors of a lexically normalized [Path](Path).
+///
+/// See [normalized_ancestors](PathExt::normalized_ancestors).
+#[derive(Clone, Debug)]
+pub struct NormalizedAncestors(PathBuf);
+
+impl Iterator for NormalizedAncestors {
+    type Item = PathBuf;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.0.pop() {
+            true => Some(self.0.clone()),
+            false => None,
+        }
+    }
+}
+
+/// A helper to display a [Path](Path) relative to a base directory.
+///
+/// See [display_relative_to](PathExt::display_relative_to).
+#[derive(Copy, Clone, Debug)]
+pub struct RelativeDisplay<'a>(&'a Path);
+
+impl Display for RelativeDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0.as_os_str().is_empty() {
+            f.write_str(".")
+        } else {
+            self.0.display().fmt(f)
+        }
+    }
+}
+
+// Matches a sequence against a pattern where star elements match any run of elements, in
+// linear space and O(n * m) time by only backtracking to the last star.
+fn glob<P, N>(
+    pattern: &[P],
+    name: &[N],
+    is_star: impl Fn(&P) -> bool,
+    matches: impl Fn(&P, &N) -> bool,
+) -> bool {
+    let (mut p, mut n) = (0, 0);
+    let mut star = None;
+    while n < name.len() {
+        if p < pattern.len() && is_star(&pattern[p]) {
+            star = Some((p, n));
+            p += 1;
+        } else if p < pattern.len() && matches(&pattern[p], &name[n]) {
+            p += 1;
+            n += 1;
+        } else if let Some((sp, sn)) = star {
+            // Let the last star absorb one more element and retry after it.
+            star = Some((sp, sn + 1));
+            p = sp + 1;
+            n = sn + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(is_star)
+}
+
+fn matches_component(pattern: &[char], name: &[char]) -> bool {
+    glob(pattern, name, |c| *c == '*', |c, v| *c == '?' || c == v)
+}
+
+fn matches_components(pattern: &[&str], path: &[Cow<str>]) -> bool {
+    glob(
+        pattern,
+        path,
+        |component| *component == "**",
+        |component, name| {
+            let pattern: Vec<char> = component.chars().collect();
+            let name: Vec<char> = name.chars().collect();
+            matches_component(&pattern, &name)
+        },
+    )
+}
+
 impl PathExt for Path {
-    fn ensure_extension<S: AsRef<OsStr>>(&self, extension: S) -> Cow<Path> {
+    fn ensure_extension<S: AsRef<OsStr>>(&self, extension: S) -> Cow<'_, Path> {
         if let Some(ext) = self.extension() {
             if ext == extension.as_ref() {
                 self.into()
@@ -59,11 +487,168 @@ impl PathExt for Path {
             self.with_extension(extension).into()
         }
     }
+
+    fn matches_pattern(&self, pattern: &str) -> bool {
+        let pattern: Vec<&str> = pattern
+            .split('/')
+            .filter(|v| !v.is_empty() && *v != ".")
+            .collect();
+        let path: Vec<Cow<str>> = self
+            .components()
+            .filter_map(|v| match v {
+                Component::Normal(v) => Some(v.to_string_lossy()),
+                Component::ParentDir => Some("..".into()),
+                _ => None,
+            })
+            .collect();
+        matches_components(&pattern, &path)
+    }
+
+    fn display_relative_to(&self, base: &Path) -> RelativeDisplay<'_> {
+        RelativeDisplay(self.strip_prefix(base).unwrap_or(self))
+    }
+
+    fn temp_sibling(&self, id: u32) -> PathBuf {
+        sibling_with_stem_suffix(self, format_args!(".tmp-{:04x}", id))
+    }
+
+    fn numbered_sibling(&self, n: u32) -> PathBuf {
+        sibling_with_stem_suffix(self, format_args!(" ({})", n))
+    }
+
+    fn cache_path(&self, hash: &str, depth: usize, extension: Option<&str>) -> PathBuf {
+        let depth = depth.min(hash.len() / 2);
+        let mut path = self.to_path_buf();
+        for level in 0..depth {
+            match hash.get(level * 2..level * 2 + 2) {
+                Some(v) => path.push(v),
+                None => break,
+            }
+        }
+        path.push(hash);
+        if let Some(extension) = extension {
+            path.set_extension(extension);
+        }
+        path
+    }
+
+    fn parse_cache_path(&self, root: &Path, depth: usize) -> Option<&str> {
+        let mut components = self.strip_prefix(root).ok()?.components();
+        let hash = Path::new(components.next_back()?.as_os_str())
+            .file_stem()?
+            .to_str()?;
+        if depth > hash.len() / 2 {
+            return None;
+        }
+        for level in 0..depth {
+            if components.next()?.as_os_str().to_str()? != hash.get(level * 2..level * 2 + 2)? {
+                return None;
+            }
+        }
+        match components.next() {
+            Some(_) => None,
+            None => Some(hash),
+        }
+    }
+
+    fn to_file_url(&self) -> Option<String> {
+        if !self.is_absolute() {
+            return None;
+        }
+        let mut url = String::from("file://");
+        let bytes = self.as_os_str().as_encoded_bytes();
+        if !bytes.starts_with(b"/") {
+            url.push('/');
+        }
+        for &b in bytes {
+            match b {
+                b'\\' if cfg!(windows) => url.push('/'),
+                b if is_url_safe(b) => url.push(b as char),
+                b => {
+                    let _ = write!(url, "%{:02X}", b);
+                }
+            }
+        }
+        Some(url)
+    }
+
+    fn split3(&self) -> (Option<&Path>, Option<&OsStr>, Option<&OsStr>) {
+        let dir = self.parent().filter(|v| !v.as_os_str().is_empty());
+        (dir, self.file_stem(), self.extension())
+    }
+
+    fn normalized_ancestors(&self) -> NormalizedAncestors {
+        NormalizedAncestors(normalize(self))
+    }
+
+    fn shorten(&self, max_chars: usize) -> Cow<'_, str> {
+        let full = self.to_string_lossy();
+        if full.chars().count() <= max_chars {
+            return full;
+        }
+        let mut components = self.components().rev();
+        let name = match components.next() {
+            Some(v) => v.as_os_str().to_string_lossy(),
+            None => return full,
+        };
+        let name_len = name.chars().count();
+        if name_len + 2 > max_chars {
+            let skip = (name_len + 1).saturating_sub(max_chars);
+            let mut short = String::from("…");
+            short.extend(name.chars().skip(skip));
+            return short.into();
+        }
+        let mut tail = vec![name];
+        let mut len = name_len + 2;
+        for component in components {
+            let component = component.as_os_str().to_string_lossy();
+            let component_len = component.chars().count() + 1;
+            if len + component_len > max_chars {
+                break;
+            }
+            len += component_len;
+            tail.push(component);
+        }
+        let mut short = String::from("…");
+        for component in tail.iter().rev() {
+            short.push(std::path::MAIN_SEPARATOR);
+            short.push_str(component);
+        }
+        short.into()
+    }
+
+    fn home_relative(&self, home: &Path) -> Cow<'_, Path> {
+        match self.strip_prefix(home) {
+            Ok(rest) => Path::new("~").join(rest).into(),
+            Err(_) => self.into(),
+        }
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.file_name()
+            .is_some_and(|v| v.as_encoded_bytes().starts_with(b"."))
+    }
+
+    fn next_available<F: FnMut(&Path) -> bool>(&self, mut exists: F) -> Cow<'_, Path> {
+        if !exists(self) {
+            return self.into();
+        }
+        let mut n = 1;
+        loop {
+            let path = self.numbered_sibling(n);
+            if !exists(&path) {
+                return path.into();
+            }
+            n += 1;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::path::PathExt;
+    use crate::path::{
+        ensure_extension_in_place, from_file_url, join3, sanitize_file_name, PathExt,
+    };
     use std::borrow::Cow;
     use std::path::Path;
 
@@ -81,5 +666,170 @@ mod tests {
         assert_eq!(&wrong_ext_corrected, Path::new("myfile.bpx"));
         assert_eq!(&no_ext_corrected, Path::new("myfile.bpx"));
         assert_eq!(&correct_ext_corrected, Path::new("myfile.bpx"));
+        let mut path = Path::new("dir/file.tar.gz").to_path_buf();
+        ensure_extension_in_place(&mut path, "gz");
+        assert_eq!(path, Path::new("dir/file.tar.gz"));
+        ensure_extension_in_place(&mut path, "zst");
+        assert_eq!(path, Path::new("dir/file.tar.zst"));
+    }
+
+    #[test]
+    fn matches_pattern() {
+        let path = Path::new("src/format/scratch.rs");
+        assert!(path.matches_pattern("src/**/*.rs"));
+        assert!(path.matches_pattern("**/*.rs"));
+        assert!(path.matches_pattern("src/*/scr?tch.rs"));
+        assert!(path.matches_pattern("src/format/**/scratch.rs"));
+        assert!(!path.matches_pattern("src/*.rs"));
+        assert!(!path.matches_pattern("src/**/*.txt"));
+        assert!(Path::new("./a.rs").matches_pattern("*.rs"));
+        assert!(Path::new("abcbd").matches_pattern("a*b?"));
+        assert!(!Path::new("abcbd").matches_pattern("a*c"));
+        assert!(Path::new("a/b/c/b/d").matches_pattern("a/**/b/*"));
+        // This would take exponential time with a backtracking matcher.
+        let name = "a".repeat(64);
+        assert!(!Path::new(&name).matches_pattern(&format!("{}b", "*a".repeat(16))));
+    }
+
+    #[test]
+    fn display_relative_to() {
+        let base = Path::new("/home/user/project");
+        let path = Path::new("/home/user/project/src/lib.rs");
+        assert_eq!(path.display_relative_to(base).to_string(), "src/lib.rs");
+        assert_eq!(base.display_relative_to(base).to_string(), ".");
+        let other = Path::new("/etc/localtime");
+        assert_eq!(
+            other.display_relative_to(base).to_string(),
+            "/etc/localtime"
+        );
+        let relative = Path::new("src/lib.rs");
+        assert_eq!(relative.display_relative_to(base).to_string(), "src/lib.rs");
+    }
+
+    #[test]
+    fn siblings() {
+        let path = Path::new("dir/archive.tar.gz");
+        assert_eq!(
+            path.temp_sibling(0xab),
+            Path::new("dir/archive.tar.tmp-00ab.gz")
+        );
+        assert_eq!(
+            path.numbered_sibling(2),
+            Path::new("dir/archive.tar (2).gz")
+        );
+        assert_eq!(Path::new("file").numbered_sibling(1), Path::new("file (1)"));
+        let taken = [Path::new("a.txt"), Path::new("a (1).txt")];
+        let available = Path::new("a.txt").next_available(|p| taken.contains(&p));
+        assert_eq!(available, Path::new("a (2).txt"));
+        let available = Path::new("b.txt").next_available(|p| taken.contains(&p));
+        assert!(matches!(available, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn cache_path() {
+        let root = Path::new("cache");
+        let path = root.cache_path("abcdef12", 2, Some("bin"));
+        assert_eq!(path, Path::new("cache/ab/cd/abcdef12.bin"));
+        assert_eq!(path.parse_cache_path(root, 2), Some("abcdef12"));
+        assert_eq!(path.parse_cache_path(root, 1), None);
+        assert_eq!(root.cache_path("ab", 4, None), Path::new("cache/ab/ab"));
+        let wrong = Path::new("cache/ab/ce/abcdef12.bin");
+        assert_eq!(wrong.parse_cache_path(root, 2), None);
+    }
+
+    #[test]
+    fn file_url() {
+        assert_eq!(Path::new("relative").to_file_url(), None);
+        assert_eq!(from_file_url("http://host/a"), None);
+        assert_eq!(from_file_url("file://server/a"), None);
+        assert_eq!(from_file_url("file:///a%2"), None);
+        #[cfg(unix)]
+        {
+            let path = Path::new("/assets/my file#1.png");
+            let url = path.to_file_url().unwrap();
+            assert_eq!(url, "file:///assets/my%20file%231.png");
+            assert_eq!(from_file_url(&url).unwrap(), path);
+            let path = from_file_url("FILE://localhost/a/%C3%A9?x=1").unwrap();
+            assert_eq!(path, Path::new("/a/é"));
+        }
+        #[cfg(windows)]
+        {
+            let path = Path::new("C:\\My Files\\a.txt");
+            let url = path.to_file_url().unwrap();
+            assert_eq!(url, "file:///C:/My%20Files/a.txt");
+            assert_eq!(from_file_url(&url).unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn hidden() {
+        assert!(Path::new("a/.git").is_hidden());
+        assert!(!Path::new("a/git").is_hidden());
+        assert!(!Path::new("..").is_hidden());
+        assert!(!Path::new(".").is_hidden());
+    }
+
+    #[test]
+    fn sanitize() {
+        assert!(matches!(
+            sanitize_file_name("report.txt", '_'),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(sanitize_file_name("a/b:c?.txt", '_'), "a_b_c_.txt");
+        assert_eq!(sanitize_file_name("name. ", '_'), "name__");
+        assert_eq!(sanitize_file_name("con.txt", '_'), "_con.txt");
+        assert_eq!(sanitize_file_name("..", '_'), "_");
+        assert_eq!(sanitize_file_name("tab\tx", '-'), "tab-x");
+    }
+
+    #[test]
+    fn split3() {
+        let path = Path::new("dir/file.tar.gz");
+        let (dir, stem, ext) = path.split3();
+        assert_eq!(dir, Some(Path::new("dir")));
+        assert_eq!(stem.unwrap(), "file.tar");
+        assert_eq!(ext.unwrap(), "gz");
+        assert_eq!(join3(dir, Some("other"), ext), Path::new("dir/other.gz"));
+        let (dir, stem, ext) = Path::new("file").split3();
+        assert_eq!((dir, ext), (None, None));
+        assert_eq!(join3(dir, stem, Some("txt")), Path::new("file.txt"));
+        let path = join3(Some("dir"), None::<&str>, Some("env"));
+        assert_eq!(path, Path::new("dir/.env"));
+    }
+
+    #[test]
+    fn normalized_ancestors() {
+        let ancestors: Vec<_> = Path::new("a/b/../c/./file")
+            .normalized_ancestors()
+            .collect();
+        assert_eq!(ancestors, [Path::new("a/c"), Path::new("a"), Path::new("")]);
+        let ancestors: Vec<_> = Path::new("../a/../../b").normalized_ancestors().collect();
+        assert_eq!(
+            ancestors,
+            [Path::new("../.."), Path::new(".."), Path::new("")]
+        );
+        #[cfg(unix)]
+        {
+            let ancestors: Vec<_> = Path::new("/../a/b").normalized_ancestors().collect();
+            assert_eq!(ancestors, [Path::new("/a"), Path::new("/")]);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shorten() {
+        let path = Path::new("/home/user/project/src/module/file.rs");
+        assert_eq!(path.shorten(100), "/home/user/project/src/module/file.rs");
+        assert_eq!(path.shorten(20), "…/src/module/file.rs");
+        assert_eq!(path.shorten(19), "…/module/file.rs");
+        assert_eq!(path.shorten(9), "…/file.rs");
+        assert_eq!(path.shorten(5), "…e.rs");
+        let home = Path::new("/home/user");
+        assert_eq!(
+            path.home_relative(home),
+            Path::new("~/project/src/module/file.rs")
+        );
+        assert_eq!(home.home_relative(home), Path::new("~"));
+        assert_eq!(Path::new("/etc").home_relative(home), Path::new("/etc"));
     }
 }
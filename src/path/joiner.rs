@@ -0,0 +1,186 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+fn capacity<'a>(parts: impl Iterator<Item = &'a OsStr>) -> usize {
+    parts.map(|v| v.len() + 1).sum()
+}
+
+/// Joins path segments into a new [PathBuf](PathBuf) with a single allocation, computing the
+/// required capacity from all segments before pushing them.
+///
+/// Segments are pushed with [PathBuf::push], so an absolute segment replaces everything before
+/// it.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::path::join_all;
+/// use std::path::Path;
+///
+/// assert_eq!(join_all(["a", "b", "c.txt"]), Path::new("a/b/c.txt"));
+/// ```
+pub fn join_all<P: AsRef<Path>, I: IntoIterator<Item = P>>(parts: I) -> PathBuf
+where
+    I::IntoIter: Clone,
+{
+    let parts = parts.into_iter();
+    let len = parts
+        .clone()
+        .map(|v| v.as_ref().as_os_str().len() + 1)
+        .sum();
+    let mut path = PathBuf::with_capacity(len);
+    for part in parts {
+        path.push(part);
+    }
+    path
+}
+
+/// A builder of paths made of borrowed segments, some of which may be conditional, which are
+/// joined with a single allocation.
+///
+/// A [PathJoiner](PathJoiner) can be [cleared](PathJoiner::clear) and reused to build paths in
+/// a loop, and [join_into](PathJoiner::join_into) reuses an existing [PathBuf](PathBuf), such
+/// that no allocation happens once both buffers are large enough.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::path::PathJoiner;
+/// use std::path::Path;
+///
+/// let debug = false;
+/// let path = PathJoiner::new()
+///     .push("target")
+///     .push_if(debug, "debug")
+///     .push_opt(Some("assets"))
+///     .push("logo.png")
+///     .join();
+/// assert_eq!(path, Path::new("target/assets/logo.png"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PathJoiner<'a> {
+    parts: Vec<&'a OsStr>,
+}
+
+impl<'a> PathJoiner<'a> {
+    /// Creates a new empty [PathJoiner](PathJoiner).
+    pub fn new() -> Self {
+        Self { parts: Vec::new() }
+    }
+
+    /// Appends a segment.
+    pub fn push<P: AsRef<Path> + ?Sized>(mut self, part: &'a P) -> Self {
+        self.push_mut(part);
+        self
+    }
+
+    /// Appends a segment only if `condition` is true.
+    pub fn push_if<P: AsRef<Path> + ?Sized>(self, condition: bool, part: &'a P) -> Self {
+        if condition {
+            self.push(part)
+        } else {
+            self
+        }
+    }
+
+    /// Appends a segment only if it is present.
+    pub fn push_opt<P: AsRef<Path> + ?Sized>(self, part: Option<&'a P>) -> Self {
+        match part {
+            Some(part) => self.push(part),
+            None => self,
+        }
+    }
+
+    /// Appends a segment to this builder in place, for use in loops.
+    pub fn push_mut<P: AsRef<Path> + ?Sized>(&mut self, part: &'a P) {
+        self.parts.push(part.as_ref().as_os_str());
+    }
+
+    /// Removes all segments, keeping the allocated storage.
+    pub fn clear(&mut self) {
+        self.parts.clear();
+    }
+
+    /// Returns the number of bytes needed to store the joined path.
+    pub fn capacity(&self) -> usize {
+        capacity(self.parts.iter().copied())
+    }
+
+    /// Joins all segments into a new [PathBuf](PathBuf) with a single allocation.
+    pub fn join(&self) -> PathBuf {
+        let mut path = PathBuf::with_capacity(self.capacity());
+        self.push_all(&mut path);
+        path
+    }
+
+    /// Joins all segments into an existing [PathBuf](PathBuf), replacing its content and only
+    /// growing it if its capacity is too small.
+    pub fn join_into(&self, path: &mut PathBuf) {
+        path.clear();
+        path.reserve(self.capacity());
+        self.push_all(path);
+    }
+
+    fn push_all(&self, path: &mut PathBuf) {
+        for part in &self.parts {
+            path.push(part);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path::{join_all, PathJoiner};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn joiner() {
+        let names = ["a.txt", "b.txt"];
+        let mut joiner = PathJoiner::new();
+        let mut path = PathBuf::new();
+        for name in names {
+            joiner.clear();
+            joiner.push_mut("out");
+            joiner.push_mut(name);
+            joiner.join_into(&mut path);
+            assert_eq!(path, Path::new("out").join(name));
+            assert!(path.capacity() >= joiner.capacity());
+        }
+        let path = PathJoiner::new()
+            .push("a")
+            .push_if(true, "b")
+            .push_opt(None::<&str>)
+            .join();
+        assert_eq!(path, Path::new("a/b"));
+        assert_eq!(join_all(Vec::<&str>::new()), PathBuf::new());
+        assert_eq!(join_all(&["x", "y"]), Path::new("x/y"));
+    }
+}
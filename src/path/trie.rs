@@ -0,0 +1,188 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::path::normalize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+struct Node<V> {
+    value: Option<V>,
+    children: HashMap<OsString, Node<V>>,
+}
+
+impl<V> Default for Node<V> {
+    fn default() -> Self {
+        Self {
+            value: None,
+            children: HashMap::new(),
+        }
+    }
+}
+
+/// A lookup table mapping path prefixes to values, with longest-prefix lookup.
+///
+/// Paths are normalized lexically before being inserted or looked up (see
+/// [normalized_ancestors](crate::path::PathExt::normalized_ancestors)) and matched component
+/// by component, such that `/assets/tex` is not a prefix of `/assets/textures`.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::path::PathTrie;
+/// use std::path::Path;
+///
+/// let mut routes = PathTrie::new();
+/// routes.insert("/assets", "root");
+/// routes.insert("/assets/textures", "textures");
+/// let (value, rest) = routes.query("/assets/./textures/rock.png").unwrap();
+/// assert_eq!((*value, rest.as_path()), ("textures", Path::new("rock.png")));
+/// assert!(routes.query("/other").is_none());
+/// ```
+#[derive(Debug)]
+pub struct PathTrie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Default for PathTrie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> PathTrie<V> {
+    /// Creates a new empty [PathTrie].
+    pub fn new() -> Self {
+        Self {
+            root: Node::default(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of prefixes stored in this table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if this table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Associates a value with a path prefix.
+    ///
+    /// returns: Option<V> the value previously associated with this prefix, if any.
+    pub fn insert<P: AsRef<Path>>(&mut self, prefix: P, value: V) -> Option<V> {
+        let prefix = normalize(prefix.as_ref());
+        let mut node = &mut self.root;
+        for component in prefix.components() {
+            node = node
+                .children
+                .entry(component.as_os_str().into())
+                .or_default();
+        }
+        let old = node.value.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Returns the value associated with exactly this path prefix.
+    pub fn get<P: AsRef<Path>>(&self, prefix: P) -> Option<&V> {
+        let prefix = normalize(prefix.as_ref());
+        let mut node = &self.root;
+        for component in prefix.components() {
+            node = node.children.get(component.as_os_str())?;
+        }
+        node.value.as_ref()
+    }
+
+    /// Removes the value associated with exactly this path prefix.
+    ///
+    /// Empty nodes are kept, such that the table does not shrink.
+    pub fn remove<P: AsRef<Path>>(&mut self, prefix: P) -> Option<V> {
+        let prefix = normalize(prefix.as_ref());
+        let mut node = &mut self.root;
+        for component in prefix.components() {
+            node = node.children.get_mut(component.as_os_str())?;
+        }
+        let old = node.value.take();
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Finds the value associated with the longest prefix of a path.
+    ///
+    /// returns: Option<(&V, PathBuf)> the value with the remainder of the normalized path
+    /// relative to the matched prefix, or None if no prefix matches.
+    pub fn query<P: AsRef<Path>>(&self, path: P) -> Option<(&V, PathBuf)> {
+        let path = normalize(path.as_ref());
+        let mut node = &self.root;
+        let mut best = node.value.as_ref().map(|v| (v, 0));
+        for (depth, component) in path.components().enumerate() {
+            match node.children.get(component.as_os_str()) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = &node.value {
+                best = Some((value, depth + 1));
+            }
+        }
+        best.map(|(value, depth)| (value, path.components().skip(depth).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path::PathTrie;
+    use std::path::Path;
+
+    #[test]
+    fn longest_match() {
+        let mut trie = PathTrie::new();
+        assert!(trie.insert("assets", 1).is_none());
+        assert!(trie.insert("assets/textures/", 2).is_none());
+        assert_eq!(trie.insert("assets/x/../textures", 3), Some(2));
+        assert_eq!(trie.len(), 2);
+        let (value, rest) = trie.query("assets/tex/rock.png").unwrap();
+        assert_eq!((*value, rest.as_path()), (1, Path::new("tex/rock.png")));
+        let (value, rest) = trie.query("assets/textures").unwrap();
+        assert_eq!((*value, rest.as_path()), (3, Path::new("")));
+        assert!(trie.query("other").is_none());
+        assert_eq!(trie.get("assets"), Some(&1));
+        assert_eq!(trie.remove("assets/textures"), Some(3));
+        assert_eq!(trie.query("assets/textures/a").unwrap().0, &1);
+        trie.insert("", 0);
+        assert_eq!(trie.query("other").unwrap().0, &0);
+    }
+}
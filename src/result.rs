@@ -28,6 +28,18 @@
 
 //! Result utilities.
 
+mod batch;
+mod exit;
+mod panic;
+mod retry;
+mod warned;
+
+pub use batch::{BatchReport, Entry, Summary};
+pub use exit::{ErrorCode, ExitPolicy, ExitPolicyExt, ExitResult};
+pub use panic::{catch_panic, PanicError};
+pub use retry::{Always, Backoff, Retry, RetryError, Retryable};
+pub use warned::Warned;
+
 use crate::extension;
 use std::error::Error;
 
@@ -44,6 +56,15 @@ extension! {
         ///
         /// returns: T the value if no errors have occurred.
         fn expect_exit(self, msg: &str, code: i32) -> T;
+
+        /// Converts this result into an [ExitResult] which can be returned from `main`,
+        /// discarding the value.
+        ///
+        /// # Arguments
+        ///
+        /// * `msg`: a failure context message.
+        /// * `code`: the exit code to exit the program with in case of error.
+        fn into_exit(self, msg: &str, code: i32) -> ExitResult;
     }
 }
 
@@ -57,4 +78,11 @@ impl<T, E: Error> ResultExt<T> for Result<T, E> {
             }
         }
     }
+
+    fn into_exit(self, msg: &str, code: i32) -> ExitResult {
+        match self {
+            Ok(_) => ExitResult::success(),
+            Err(e) => ExitResult::failure(format_args!("{}: {}", msg, e), code),
+        }
+    }
 }
@@ -0,0 +1,220 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// The outcome of a single item of a [BatchReport].
+#[derive(Clone, Debug)]
+pub struct Entry<K, E> {
+    /// The key identifying the item (ex: a file name).
+    pub key: K,
+
+    /// The time it took to process the item.
+    pub duration: Duration,
+
+    /// The outcome of the item.
+    pub result: Result<(), E>,
+}
+
+/// A summary of a [BatchReport].
+#[derive(Clone, Debug)]
+pub struct Summary<'a, K> {
+    /// The number of items which succeeded.
+    pub succeeded: usize,
+
+    /// The number of items which failed.
+    pub failed: usize,
+
+    /// The total time spent processing items.
+    pub total_duration: Duration,
+
+    /// The slowest items, slowest first.
+    pub slowest: Vec<(&'a K, Duration)>,
+}
+
+/// A report of per-item outcomes and timings of a batch operation.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::result::BatchReport;
+///
+/// let mut report = BatchReport::new();
+/// for file in ["a.png", "b.png"] {
+///     report.time(file, || if file == "b.png" { Err("corrupt") } else { Ok(()) });
+/// }
+/// assert_eq!(report.summary(3).failed, 1);
+/// println!("{}", report);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BatchReport<K, E> {
+    entries: Vec<Entry<K, E>>,
+}
+
+impl<K, E> Default for BatchReport<K, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, E> BatchReport<K, E> {
+    /// Creates a new empty report.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Records the outcome of an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the key identifying the item.
+    /// * `duration`: the time it took to process the item.
+    /// * `result`: the outcome of the item.
+    pub fn record(&mut self, key: K, duration: Duration, result: Result<(), E>) {
+        self.entries.push(Entry {
+            key,
+            duration,
+            result,
+        });
+    }
+
+    /// Runs and times an item, recording its outcome.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: the key identifying the item.
+    /// * `f`: the function processing the item.
+    ///
+    /// returns: Option<T> the value returned by the function if it succeeded.
+    pub fn time<T, F: FnOnce() -> Result<T, E>>(&mut self, key: K, f: F) -> Option<T> {
+        let start = Instant::now();
+        let result = f();
+        let duration = start.elapsed();
+        match result {
+            Ok(v) => {
+                self.record(key, duration, Ok(()));
+                Some(v)
+            }
+            Err(e) => {
+                self.record(key, duration, Err(e));
+                None
+            }
+        }
+    }
+
+    /// Returns an iterator over all recorded entries in recording order.
+    pub fn iter(&self) -> impl Iterator<Item = &Entry<K, E>> {
+        self.entries.iter()
+    }
+
+    /// Returns an iterator over all failed entries.
+    pub fn failures(&self) -> impl Iterator<Item = (&K, &E)> {
+        self.entries
+            .iter()
+            .filter_map(|v| v.result.as_ref().err().map(|e| (&v.key, e)))
+    }
+
+    /// Returns true if any item failed.
+    pub fn has_failures(&self) -> bool {
+        self.entries.iter().any(|v| v.result.is_err())
+    }
+
+    /// Computes a summary of this report.
+    ///
+    /// # Arguments
+    ///
+    /// * `slowest`: the maximum number of slowest items to include.
+    pub fn summary(&self, slowest: usize) -> Summary<'_, K> {
+        let failed = self.entries.iter().filter(|v| v.result.is_err()).count();
+        let mut items: Vec<(&K, Duration)> =
+            self.entries.iter().map(|v| (&v.key, v.duration)).collect();
+        items.sort_by_key(|v| std::cmp::Reverse(v.1));
+        items.truncate(slowest);
+        Summary {
+            succeeded: self.entries.len() - failed,
+            failed,
+            total_duration: self.entries.iter().map(|v| v.duration).sum(),
+            slowest: items,
+        }
+    }
+}
+
+impl<K: Display, E: Display> Display for BatchReport<K, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let summary = self.summary(3);
+        write!(
+            f,
+            "{} succeeded, {} failed in {:.2?}",
+            summary.succeeded, summary.failed, summary.total_duration
+        )?;
+        if !summary.slowest.is_empty() {
+            f.write_str("\nslowest:")?;
+            for (key, duration) in summary.slowest {
+                write!(f, "\n  {}: {:.2?}", key, duration)?;
+            }
+        }
+        if summary.failed > 0 {
+            f.write_str("\nfailures:")?;
+            for (key, error) in self.failures() {
+                write!(f, "\n  {}: {}", key, error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::result::BatchReport;
+    use std::time::Duration;
+
+    #[test]
+    fn basic() {
+        let mut report = BatchReport::new();
+        report.record("a", Duration::from_millis(5), Ok(()));
+        report.record("b", Duration::from_millis(20), Err("corrupt"));
+        report.record("c", Duration::from_millis(10), Ok(()));
+        let summary = report.summary(2);
+        assert_eq!(summary.succeeded, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.total_duration, Duration::from_millis(35));
+        assert_eq!(
+            summary.slowest,
+            [
+                (&"b", Duration::from_millis(20)),
+                (&"c", Duration::from_millis(10))
+            ]
+        );
+        let text = report.to_string();
+        assert!(text.starts_with("2 succeeded, 1 failed in 35.00ms"));
+        assert!(text.ends_with("failures:\n  b: corrupt"));
+    }
+}
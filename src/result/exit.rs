@@ -0,0 +1,265 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::extension;
+use crate::result::ResultExt;
+use std::error::Error;
+use std::fmt::Display;
+use std::process::{ExitCode, Termination};
+
+/// Classifies an error into a category used to select an exit code from an [ExitPolicy].
+pub trait ErrorCode {
+    /// Returns the category of this error (ex: `io`, `config`, `usage`).
+    fn category(&self) -> &'static str;
+}
+
+/// A table mapping error categories to process exit codes, intended to be shared across a
+/// binary as a `static`.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::result::{ErrorCode, ExitPolicy};
+///
+/// static POLICY: ExitPolicy = ExitPolicy::new(1, &[("usage", 64), ("io", 74)]);
+///
+/// struct UsageError;
+///
+/// impl ErrorCode for UsageError {
+///     fn category(&self) -> &'static str {
+///         "usage"
+///     }
+/// }
+///
+/// assert_eq!(POLICY.code_for(&UsageError), 64);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ExitPolicy {
+    default: i32,
+    codes: &'static [(&'static str, i32)],
+}
+
+impl ExitPolicy {
+    /// Creates a new [ExitPolicy].
+    ///
+    /// # Arguments
+    ///
+    /// * `default`: the exit code for categories not listed in `codes`.
+    /// * `codes`: the table of categories with their exit code.
+    pub const fn new(default: i32, codes: &'static [(&'static str, i32)]) -> ExitPolicy {
+        ExitPolicy { default, codes }
+    }
+
+    /// Returns the exit code for a given category.
+    pub fn code(&self, category: &str) -> i32 {
+        self.codes
+            .iter()
+            .find(|(name, _)| *name == category)
+            .map_or(self.default, |(_, code)| *code)
+    }
+
+    /// Returns the exit code for a given error.
+    pub fn code_for<E: ErrorCode + ?Sized>(&self, error: &E) -> i32 {
+        self.code(error.category())
+    }
+}
+
+/// The outcome of a program which can be returned from `main`, unifying with the semantics of
+/// [expect_exit](crate::result::ResultExt::expect_exit): on failure, the error message is
+/// printed to the standard error and the program exits with the configured code.
+///
+/// Exit codes outside of 0..=255 are reported as 255, such that a failure is never reported as
+/// a success.
+///
+/// # Example
+///
+/// ```no_run
+/// use bp3d_util::result::{ExitResult, ResultExt};
+///
+/// fn main() -> ExitResult {
+///     std::fs::read("config.toml").into_exit("failed to read config", 74)
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExitResult(Option<(String, i32)>);
+
+impl ExitResult {
+    /// Creates a successful [ExitResult].
+    pub fn success() -> ExitResult {
+        ExitResult(None)
+    }
+
+    /// Creates a failed [ExitResult].
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: the message to print to the standard error.
+    /// * `code`: the exit code, reported as 255 if it does not fit in 0..=255.
+    pub fn failure(message: impl Display, code: i32) -> ExitResult {
+        ExitResult(Some((message.to_string(), code)))
+    }
+
+    /// Returns the exit code of this result.
+    pub fn code(&self) -> i32 {
+        self.0.as_ref().map_or(0, |(_, code)| *code)
+    }
+
+    /// Returns the error message of this result, if any.
+    pub fn message(&self) -> Option<&str> {
+        self.0.as_ref().map(|(message, _)| &**message)
+    }
+}
+
+extension! {
+    /// [ResultExt] variants selecting the exit code with an [ExitPolicy] from the category of
+    /// the error.
+    pub extension ExitPolicyExt<T>: Result<T, E> {
+        /// Expects a given result to unwrap without issues, in case the result is an error,
+        /// this function exits the program with the exit code selected by an [ExitPolicy] for
+        /// the category of the error.
+        ///
+        /// # Arguments
+        ///
+        /// * `msg`: a failure context message.
+        /// * `policy`: the policy used to select the exit code.
+        ///
+        /// returns: T the value if no errors have occurred.
+        fn exit_with_policy(self, msg: &str, policy: &ExitPolicy) -> T;
+
+        /// Converts this result into an [ExitResult] which can be returned from `main`,
+        /// discarding the value, with the exit code selected by an [ExitPolicy] for the
+        /// category of the error.
+        ///
+        /// # Arguments
+        ///
+        /// * `msg`: a failure context message.
+        /// * `policy`: the policy used to select the exit code.
+        fn into_exit_with_policy(self, msg: &str, policy: &ExitPolicy) -> ExitResult;
+    }
+}
+
+impl<T, E: Error + ErrorCode> ExitPolicyExt<T> for Result<T, E> {
+    fn exit_with_policy(self, msg: &str, policy: &ExitPolicy) -> T {
+        let code = match &self {
+            Ok(_) => 0,
+            Err(e) => policy.code_for(e),
+        };
+        self.expect_exit(msg, code)
+    }
+
+    fn into_exit_with_policy(self, msg: &str, policy: &ExitPolicy) -> ExitResult {
+        let code = match &self {
+            Ok(_) => 0,
+            Err(e) => policy.code_for(e),
+        };
+        self.into_exit(msg, code)
+    }
+}
+
+// Codes outside of 0..=255 are clamped to 255 rather than truncated, such that a failure can
+// never be reported as a success (ex: 256).
+fn exit_code(code: i32) -> u8 {
+    u8::try_from(code).unwrap_or(u8::MAX)
+}
+
+impl Termination for ExitResult {
+    fn report(self) -> ExitCode {
+        if let Some(message) = self.message() {
+            eprintln!("{}", message);
+        }
+        ExitCode::from(exit_code(self.code()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::result::exit::exit_code;
+    use crate::result::{ExitPolicy, ExitResult, ResultExt};
+
+    #[test]
+    fn codes() {
+        const POLICY: ExitPolicy = ExitPolicy::new(1, &[("io", 74), ("config", 78)]);
+        assert_eq!(POLICY.code("io"), 74);
+        assert_eq!(POLICY.code("config"), 78);
+        assert_eq!(POLICY.code("other"), 1);
+    }
+
+    #[test]
+    fn exit_result() {
+        let res: Result<(), std::fmt::Error> = Err(std::fmt::Error);
+        let res = res.into_exit("failed to format", 70);
+        assert_eq!(res.code(), 70);
+        assert_eq!(
+            res.message(),
+            Some("failed to format: an error occurred when formatting an argument")
+        );
+        assert_eq!(
+            Ok::<_, std::fmt::Error>(1).into_exit("", 70),
+            ExitResult::success()
+        );
+    }
+
+    #[test]
+    fn clamp() {
+        assert_eq!(exit_code(0), 0);
+        assert_eq!(exit_code(74), 74);
+        assert_eq!(exit_code(255), 255);
+        assert_eq!(exit_code(256), 255);
+        assert_eq!(exit_code(512), 255);
+        assert_eq!(exit_code(-1), 255);
+    }
+
+    #[cfg(feature = "simple-error")]
+    #[test]
+    fn simple_error_category() {
+        use crate::result::{ErrorCode, ExitPolicyExt};
+        use crate::simple_error;
+
+        simple_error!(
+            /// Doc.
+            TestError {
+                /// Doc.
+                [User] Usage => "usage",
+                /// Doc.
+                Bug => "bug"
+            }
+        );
+
+        impl ErrorCode for TestError {
+            fn category(&self) -> &'static str {
+                TestError::category(self).as_str()
+            }
+        }
+
+        const POLICY: ExitPolicy = ExitPolicy::new(1, &[("user", 64)]);
+        assert_eq!(POLICY.code_for(&TestError::Usage), 64);
+        assert_eq!(POLICY.code_for(&TestError::Bug), 1);
+        let res = Err::<(), _>(TestError::Usage).into_exit_with_policy("failed", &POLICY);
+        assert_eq!(res.code(), 64);
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::panic::{catch_unwind, UnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+fn install_hook() {
+    HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // The hook is process-wide and may run while thread locals are being destroyed.
+            if DEPTH.try_with(|v| v.get()).unwrap_or(0) == 0 {
+                return previous(info);
+            }
+            let location = info.location().map(|v| v.to_string());
+            let _ = LOCATION.try_with(|v| *v.borrow_mut() = location);
+        }));
+    });
+}
+
+/// An error produced by a panic caught with [catch_panic].
+#[derive(Clone, Debug)]
+pub struct PanicError {
+    /// The panic message, or `Box<dyn Any>` if the payload was not a string.
+    pub message: String,
+
+    /// The location of the panic (ex: `src/main.rs:12:5`) when available.
+    pub location: Option<String>,
+}
+
+impl PanicError {
+    fn from_payload(payload: Box<dyn Any + Send>, location: Option<String>) -> PanicError {
+        let message = match payload.downcast::<String>() {
+            Ok(v) => *v,
+            Err(payload) => match payload.downcast_ref::<&'static str>() {
+                Some(v) => String::from(*v),
+                None => String::from("Box<dyn Any>"),
+            },
+        };
+        PanicError { message, location }
+    }
+}
+
+impl Display for PanicError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "panicked at {}: {}", location, self.message),
+            None => write!(f, "panicked: {}", self.message),
+        }
+    }
+}
+
+impl Error for PanicError {}
+
+/// Runs a function, converting any panic into a [PanicError].
+///
+/// The first call installs a process-wide panic hook which records the location of panics
+/// occurring inside this function and suppresses their default report; panics occurring outside
+/// are forwarded to the previously installed hook. [PanicError] implements [Error] and can
+/// therefore be wrapped in error types generated by `simple_error!`.
+///
+/// # Arguments
+///
+/// * `f`: the function to run (ex: a user callback).
+///
+/// returns: Result<T, PanicError>
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::result::catch_panic;
+///
+/// let err = catch_panic(|| panic!("bad plugin")).unwrap_err();
+/// assert_eq!(err.message, "bad plugin");
+/// assert!(err.location.is_some());
+/// assert_eq!(catch_panic(|| 42).unwrap(), 42);
+/// ```
+pub fn catch_panic<T, F: FnOnce() -> T + UnwindSafe>(f: F) -> Result<T, PanicError> {
+    install_hook();
+    DEPTH.set(DEPTH.get() + 1);
+    let res = catch_unwind(f);
+    DEPTH.set(DEPTH.get() - 1);
+    res.map_err(|payload| {
+        let location = LOCATION.with_borrow_mut(Option::take);
+        PanicError::from_payload(payload, location)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::result::catch_panic;
+
+    #[test]
+    fn payloads() {
+        let err = catch_panic(|| panic!("code {}", 42)).unwrap_err();
+        assert_eq!(err.message, "code 42");
+        assert!(err.to_string().contains("panic.rs"));
+        let err = catch_panic(|| std::panic::panic_any(1)).unwrap_err();
+        assert_eq!(err.message, "Box<dyn Any>");
+        let nested = catch_panic(|| catch_panic(|| panic!("inner")).is_err());
+        assert!(nested.unwrap());
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// The strategy used to wait between 2 attempts of a [Retry].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Backoff {
+    /// Retries immediately.
+    None,
+
+    /// Waits a fixed duration between attempts.
+    Fixed(Duration),
+
+    /// Waits a duration doubling after each attempt.
+    Exponential {
+        /// The duration to wait after the first attempt.
+        initial: Duration,
+
+        /// The maximum duration to wait between 2 attempts.
+        max: Duration,
+    },
+}
+
+impl Backoff {
+    fn delay(&self, attempt: u32) -> Duration {
+        match self {
+            Backoff::None => Duration::ZERO,
+            Backoff::Fixed(v) => *v,
+            Backoff::Exponential { initial, max } => initial
+                .checked_mul(1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX))
+                .map_or(*max, |v| v.min(*max)),
+        }
+    }
+}
+
+/// Decides if an error should be retried.
+pub trait Retryable<E> {
+    /// Returns true if the operation should be retried after the given error.
+    fn is_retryable(&self, error: &E) -> bool;
+}
+
+/// A [Retryable] which retries any error.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Always;
+
+impl<E> Retryable<E> for Always {
+    fn is_retryable(&self, _: &E) -> bool {
+        true
+    }
+}
+
+impl<E, F: Fn(&E) -> bool> Retryable<E> for F {
+    fn is_retryable(&self, error: &E) -> bool {
+        self(error)
+    }
+}
+
+/// The error returned when all attempts of a [Retry] have failed.
+#[derive(Debug)]
+pub struct RetryError<E> {
+    /// The error of the last attempt.
+    pub error: E,
+
+    /// The number of attempts which were made.
+    pub attempts: u32,
+}
+
+impl<E: Display> Display for RetryError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (after {} attempt(s))", self.error, self.attempts)
+    }
+}
+
+impl<E: Error + 'static> Error for RetryError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A retry policy for fallible operations.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::result::{Backoff, Retry};
+/// use std::time::Duration;
+///
+/// let mut count = 0;
+/// let res = Retry::new(3)
+///     .backoff(Backoff::Fixed(Duration::from_millis(1)))
+///     .retry_if(|e: &&str| *e == "timeout")
+///     .run(|| {
+///         count += 1;
+///         if count < 2 { Err("timeout") } else { Ok(count) }
+///     });
+/// assert_eq!(res.unwrap(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Retry<P = Always> {
+    max_attempts: u32,
+    backoff: Backoff,
+    retryable: P,
+}
+
+impl Retry {
+    /// Creates a new retry policy retrying any error without waiting.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_attempts`: the maximum number of attempts, including the first one.
+    pub fn new(max_attempts: u32) -> Retry {
+        Retry {
+            max_attempts: max_attempts.max(1),
+            backoff: Backoff::None,
+            retryable: Always,
+        }
+    }
+}
+
+impl<P> Retry<P> {
+    /// Sets the strategy used to wait between attempts.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the predicate deciding which errors are retried.
+    pub fn retry_if<P1>(self, retryable: P1) -> Retry<P1> {
+        Retry {
+            max_attempts: self.max_attempts,
+            backoff: self.backoff,
+            retryable,
+        }
+    }
+
+    /// Runs a fallible operation until it succeeds, a non-retryable error occurs or the maximum
+    /// number of attempts is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `op`: the operation to run.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [RetryError] with the last error and the number of attempts made.
+    pub fn run<T, E, F: FnMut() -> Result<T, E>>(&self, mut op: F) -> Result<T, RetryError<E>>
+    where
+        P: Retryable<E>,
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match op() {
+                Ok(v) => return Ok(v),
+                Err(error)
+                    if attempts >= self.max_attempts || !self.retryable.is_retryable(&error) =>
+                {
+                    return Err(RetryError { error, attempts })
+                }
+                Err(_) => std::thread::sleep(self.backoff.delay(attempts)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::result::{Backoff, Retry};
+    use std::time::Duration;
+
+    #[test]
+    fn attempts() {
+        let mut count = 0;
+        let err = Retry::new(3)
+            .run(|| {
+                count += 1;
+                Err::<(), _>(count)
+            })
+            .unwrap_err();
+        assert_eq!(err.attempts, 3);
+        assert_eq!(err.error, 3);
+        let err = Retry::new(5)
+            .retry_if(|e: &u32| *e < 2)
+            .run(|| {
+                count += 1;
+                Err::<(), _>(count - 3)
+            })
+            .unwrap_err();
+        assert_eq!(err.attempts, 2);
+    }
+
+    #[test]
+    fn backoff() {
+        let backoff = Backoff::Exponential {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5),
+        };
+        assert_eq!(backoff.delay(1), Duration::from_secs(1));
+        assert_eq!(backoff.delay(3), Duration::from_secs(4));
+        assert_eq!(backoff.delay(4), Duration::from_secs(5));
+        assert_eq!(backoff.delay(100), Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::Display;
+
+/// A value accompanied by non-fatal warnings accumulated while producing it.
+///
+/// To report warnings before exiting on an error, wrap the result itself, such that
+/// [report](Warned::report) can be called before
+/// [expect_exit](crate::result::ResultExt::expect_exit).
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::result::{ResultExt, Warned};
+///
+/// fn import(data: &str) -> Warned<Result<u32, std::num::ParseIntError>, String> {
+///     let mut warned = Warned::new(data.trim().parse());
+///     if data.starts_with(' ') {
+///         warned.warn("leading whitespace ignored".into());
+///     }
+///     warned
+/// }
+///
+/// let value = import(" 42").report().expect_exit("failed to import", 1);
+/// assert_eq!(value, 42);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warned<T, W> {
+    /// The value.
+    pub value: T,
+
+    /// The warnings accumulated while producing the value.
+    pub warnings: Vec<W>,
+}
+
+impl<T, W> Warned<T, W> {
+    /// Creates a new [Warned] value without warnings.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Creates a new [Warned] value with existing warnings.
+    pub fn with_warnings(value: T, warnings: Vec<W>) -> Self {
+        Self { value, warnings }
+    }
+
+    /// Adds a warning.
+    pub fn warn(&mut self, warning: W) {
+        self.warnings.push(warning);
+    }
+
+    /// Maps the value, keeping the warnings.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Warned<U, W> {
+        Warned {
+            value: f(self.value),
+            warnings: self.warnings,
+        }
+    }
+
+    /// Chains another operation producing warnings; the warnings of both operations are
+    /// merged in order.
+    pub fn and_then<U, F: FnOnce(T) -> Warned<U, W>>(self, f: F) -> Warned<U, W> {
+        let mut warnings = self.warnings;
+        let mut next = f(self.value);
+        warnings.append(&mut next.warnings);
+        Warned {
+            value: next.value,
+            warnings,
+        }
+    }
+
+    /// Extracts the value and the warnings.
+    pub fn into_parts(self) -> (T, Vec<W>) {
+        (self.value, self.warnings)
+    }
+}
+
+impl<T, W: Display> Warned<T, W> {
+    /// Prints all warnings to the standard error and returns the value.
+    pub fn report(self) -> T {
+        for warning in &self.warnings {
+            eprintln!("warning: {}", warning);
+        }
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::result::Warned;
+
+    #[test]
+    fn merge() {
+        let mut first = Warned::new(1);
+        first.warn("a");
+        let res = first
+            .and_then(|v| Warned::with_warnings(v + 1, vec!["b", "c"]))
+            .map(|v| v * 10);
+        assert_eq!(res.into_parts(), (20, vec!["a", "b", "c"]));
+    }
+}
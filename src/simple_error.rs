@@ -27,13 +27,247 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 //! Error umbrella type generation macro.
+//!
+//! The [simple_error](crate::simple_error) macro only relies on `core` and can be used in
+//! `no_std` crates when the `std` feature is disabled; the context helpers require `std`.
 
-//Because Rust macros are a peace of shit.
-/// This macro is internal and called by another macro.
+#[cfg(feature = "std")]
+use crate::extension;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::panic::Location;
+
+/// The category of an error, shared by all error types generated by
+/// [simple_error](crate::simple_error) such that failures can be tagged consistently.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The error was caused by invalid user input or usage.
+    User,
+
+    /// The error was caused by the system (ex: an io error or a missing file).
+    System,
+
+    /// The error was caused by a bug or an unexpected state.
+    Internal,
+
+    /// The error was caused by a network failure.
+    Network,
+}
+
+impl ErrorCategory {
+    /// Returns the name of this category in lower case (ex: `network`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::User => "user",
+            ErrorCategory::System => "system",
+            ErrorCategory::Internal => "internal",
+            ErrorCategory::Network => "network",
+        }
+    }
+}
+
+impl core::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error with an attached context message and optionally the source location where it was
+/// produced, as recorded by [try_context](crate::try_context).
+///
+/// This is designed to be used as the payload of a [simple_error](crate::simple_error) variant.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::simple_error;
+/// use bp3d_util::simple_error::{Context, WithContext};
+/// simple_error!(
+///     /// Doc.
+///     ConfigError {
+///         /// An io error with context.
+///         (impl From) Io(WithContext<std::io::Error>) => "io error: {}"
+///     }
+/// );
+///
+/// fn load() -> Result<Vec<u8>, ConfigError> {
+///     Ok(std::fs::read("/does/not/exist").context("failed to read config")?)
+/// }
+///
+/// assert!(load().unwrap_err().to_string().starts_with("io error: failed to read config: "));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct WithContext<E> {
+    /// The context message.
+    pub context: Cow<'static, str>,
+
+    /// The source location where the error was produced, if recorded.
+    pub location: Option<&'static Location<'static>>,
+
+    /// The underlying error.
+    pub error: E,
+}
+
+#[cfg(feature = "std")]
+impl<E: Display> Display for WithContext<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some(location) => write!(
+                f,
+                "{} ({}:{}): {}",
+                self.context,
+                location.file(),
+                location.line(),
+                self.error
+            ),
+            None => write!(f, "{}: {}", self.context, self.error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Error + 'static> Error for WithContext<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A list of errors reported together, such as all the issues found by a validation pass.
+///
+/// This is designed to be used as the payload of a [simple_error](crate::simple_error) variant
+/// declared with `(impl Aggregate)`, which generates a [From](From) implementation for
+/// `Vec<Self>` and a `from_results` constructor collecting the errors of an iterator of results:
+///
+/// ```
+/// use bp3d_util::simple_error;
+/// use bp3d_util::simple_error::Aggregate;
+/// simple_error!(
+///     /// Doc.
+///     CheckError {
+///         /// Doc.
+///         Missing(&'static str) => "missing field {}",
+///         /// Doc.
+///         (impl Aggregate) Multiple(Aggregate<Self>) => "{}"
+///     }
+/// );
+///
+/// let fields = [Some(1), None, Some(3), None];
+/// let err = CheckError::from_results(["a", "b", "c", "d"].iter().zip(fields).map(|(name, v)| {
+///     v.ok_or(CheckError::Missing(name))
+/// })).unwrap_err();
+/// assert_eq!(err.to_string(), "2 errors:\n  1. missing field b\n  2. missing field d");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Aggregate<E>(pub Vec<E>);
+
+#[cfg(feature = "std")]
+impl<E: Display> Display for Aggregate<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} errors:", self.0.len())?;
+        for (i, e) in self.0.iter().enumerate() {
+            write!(f, "\n  {}. {}", i + 1, e)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+extension! {
+    /// Result extensions to attach context messages to errors.
+    pub extension Context<T, E>: Result<T, E> {
+        /// Attaches a context message to the error of this result.
+        ///
+        /// # Arguments
+        ///
+        /// * `context`: the context message.
+        ///
+        /// returns: Result<T, WithContext<E>>
+        fn context<C: Into<Cow<'static, str>>>(self, context: C) -> Result<T, WithContext<E>>;
+
+        /// Attaches a lazily computed context message to the error of this result.
+        ///
+        /// # Arguments
+        ///
+        /// * `f`: the function computing the context message, only called in case of error.
+        ///
+        /// returns: Result<T, WithContext<E>>
+        fn with_context<C: Into<Cow<'static, str>>, F: FnOnce() -> C>(
+            self,
+            f: F,
+        ) -> Result<T, WithContext<E>>;
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, E> Context<T, E> for Result<T, E> {
+    fn context<C: Into<Cow<'static, str>>>(self, context: C) -> Result<T, WithContext<E>> {
+        self.map_err(|error| WithContext {
+            context: context.into(),
+            location: None,
+            error,
+        })
+    }
+
+    fn with_context<C: Into<Cow<'static, str>>, F: FnOnce() -> C>(
+        self,
+        f: F,
+    ) -> Result<T, WithContext<E>> {
+        self.map_err(|error| WithContext {
+            context: f().into(),
+            location: None,
+            error,
+        })
+    }
+}
+
+/// Unwraps a [Result], or returns early from the current function with the error wrapped in a
+/// [WithContext](crate::simple_error::WithContext) recording a context message and the location
+/// of the macro call, converted with [Into].
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::{simple_error, try_context};
+/// use bp3d_util::simple_error::WithContext;
+/// simple_error!(
+///     /// Doc.
+///     ConfigError {
+///         /// An io error with context.
+///         (impl From) Io(WithContext<std::io::Error>) => "io error: {}"
+///     }
+/// );
+///
+/// fn load() -> Result<Vec<u8>, ConfigError> {
+///     Ok(try_context!(std::fs::read("/does/not/exist"), "failed to read config"))
+/// }
+///
+/// let ConfigError::Io(err) = load().unwrap_err();
+/// assert_eq!(err.context, "failed to read config");
+/// assert_eq!(err.location.unwrap().file(), file!());
+/// assert!(err.to_string().starts_with(&format!("failed to read config ({}:", file!())));
+/// ```
+#[cfg(feature = "std")]
 #[macro_export]
-macro_rules! typed_ident {
-    ($t: ty, $name: ident) => {
-        $name
+macro_rules! try_context {
+    ($res: expr, $context: literal) => {
+        match $res {
+            Ok(v) => v,
+            Err(error) => {
+                return Err($crate::simple_error::WithContext {
+                    context: std::borrow::Cow::Borrowed($context),
+                    location: Some(core::panic::Location::caller()),
+                    error,
+                }
+                .into())
+            }
+        }
     };
 }
 
@@ -41,6 +275,63 @@ macro_rules! typed_ident {
 /// This macro is internal and called by another macro.
 #[macro_export]
 macro_rules! hack_rust_buggy_macros {
+    ($name: ident, $ty: ident, FromBox, $data: ty) => {
+        impl From<$data> for $name {
+            fn from(value: $data) -> Self {
+                Self::$ty(value)
+            }
+        }
+
+        impl $name {
+            /// Wraps any error into the catch-all variant of this error type.
+            pub fn other<E: core::error::Error + Send + Sync + 'static>(error: E) -> Self {
+                Self::$ty(error.into())
+            }
+
+            /// Attempts to downcast the error stored in the catch-all variant of this error type.
+            ///
+            /// Returns None if this error is not the catch-all variant or if the stored error is
+            /// not of type `E`.
+            pub fn downcast_ref<E: core::error::Error + 'static>(&self) -> Option<&E> {
+                #[allow(unreachable_patterns)]
+                match self {
+                    Self::$ty(e) => e.downcast_ref(),
+                    _ => None,
+                }
+            }
+        }
+    };
+    ($name: ident, $ty: ident, Aggregate, $data: ty) => {
+        impl From<Vec<$name>> for $name {
+            fn from(value: Vec<$name>) -> Self {
+                Self::$ty($crate::simple_error::Aggregate(value))
+            }
+        }
+
+        impl $name {
+            /// Collects the values of an iterator of results, or all the errors it contains.
+            ///
+            /// A single error is returned as is, multiple errors are wrapped in the aggregate
+            /// variant of this error type.
+            pub fn from_results<T, I: IntoIterator<Item = Result<T, Self>>>(
+                iter: I,
+            ) -> Result<Vec<T>, Self> {
+                let mut values = Vec::new();
+                let mut errors = Vec::new();
+                for v in iter {
+                    match v {
+                        Ok(v) => values.push(v),
+                        Err(e) => errors.push(e),
+                    }
+                }
+                match errors.len() {
+                    0 => Ok(values),
+                    1 => Err(errors.remove(0)),
+                    _ => Err(errors.into()),
+                }
+            }
+        }
+    };
     ($name: ident, $ty: ident, $e: ident, $data: ty) => {
         impl $e<$data> for $name {
             fn from(value: $data) -> Self {
@@ -48,11 +339,98 @@ macro_rules! hack_rust_buggy_macros {
             }
         }
     };
-    ($name: ident, $ty: ty, $($e: ident)?, $($data: ty)?) => {};
+    ($name: ident, $ty: ident, $e: ident, $($data: ty),*) => {
+        compile_error!(concat!(
+            "variant ",
+            stringify!($ty),
+            " must have exactly one payload to derive ",
+            stringify!($e)
+        ));
+    };
+    ($name: ident, $ty: ty, , $($data: ty),*) => {};
+}
+
+//Because Rust macros are a peace of shit.
+/// This macro is internal and called by another macro.
+#[macro_export]
+macro_rules! simple_error_from_source {
+    ($name: ident, $ty: ident, $src: ty) => {
+        impl From<$src> for $name {
+            fn from(value: $src) -> Self {
+                Self::$ty(value.into())
+            }
+        }
+    };
+    ($name: ident, $ty: ident,) => {};
 }
 
-/// Generates a simple enum which maps multiple error types and implements [Error](std::error::Error) and
-/// [Display](std::fmt::Display) automatically. This optionally can generate [From](From) implementations
+//Because Rust macros are a peace of shit.
+/// This macro is internal and called by another macro.
+#[macro_export]
+macro_rules! simple_error_category {
+    () => {
+        $crate::simple_error::ErrorCategory::Internal
+    };
+    ($cat: ident) => {
+        $crate::simple_error::ErrorCategory::$cat
+    };
+}
+
+//Because Rust macros are a peace of shit.
+/// This macro is internal and called by another macro.
+#[macro_export]
+macro_rules! simple_error_categories {
+    ($name: ident, $($ty: ident []),*) => {};
+    ($name: ident, $($ty: ident [$($cat: ident)?]),*) => {
+        impl $name {
+            /// Returns the category of this error.
+            pub fn category(&self) -> $crate::simple_error::ErrorCategory {
+                match self {
+                    $($name::$ty { .. } => $crate::simple_error_category!($($cat)?)),*
+                }
+            }
+        }
+    };
+}
+
+//Because Rust macros are a peace of shit.
+/// This macro is internal and called by another macro.
+#[macro_export]
+macro_rules! simple_error_fmt {
+    ($f: ident, $value: expr, $name: ident, [$($arms: tt)*]) => {
+        match *$value {
+            $($arms)*
+        }
+    };
+    ($f: ident, $value: expr, $name: ident, [$($arms: tt)*] $ty: ident $desc: literal () $($rest: tt)*) => {
+        $crate::simple_error_fmt!($f, $value, $name, [
+            $($arms)* $name::$ty => write!($f, $desc),
+        ] $($rest)*)
+    };
+    ($f: ident, $value: expr, $name: ident, [$($arms: tt)*] $ty: ident $desc: literal ($a: ty) $($rest: tt)*) => {
+        $crate::simple_error_fmt!($f, $value, $name, [
+            $($arms)* $name::$ty(ref a) => write!($f, $desc, a),
+        ] $($rest)*)
+    };
+    ($f: ident, $value: expr, $name: ident, [$($arms: tt)*] $ty: ident $desc: literal ($a: ty, $b: ty) $($rest: tt)*) => {
+        $crate::simple_error_fmt!($f, $value, $name, [
+            $($arms)* $name::$ty(ref a, ref b) => write!($f, $desc, a, b),
+        ] $($rest)*)
+    };
+    ($f: ident, $value: expr, $name: ident, [$($arms: tt)*] $ty: ident $desc: literal ($a: ty, $b: ty, $c: ty) $($rest: tt)*) => {
+        $crate::simple_error_fmt!($f, $value, $name, [
+            $($arms)* $name::$ty(ref a, ref b, ref c) => write!($f, $desc, a, b, c),
+        ] $($rest)*)
+    };
+    ($f: ident, $value: expr, $name: ident, [$($arms: tt)*] $ty: ident $desc: literal ($a: ty, $b: ty, $c: ty, $d: ty) $($rest: tt)*) => {
+        $crate::simple_error_fmt!($f, $value, $name, [
+            $($arms)* $name::$ty(ref a, ref b, ref c, ref d) => write!($f, $desc, a, b, c, d),
+        ] $($rest)*)
+    };
+}
+
+/// Generates a simple enum which maps multiple error types and implements [Error](core::error::Error) and
+/// [Display](core::fmt::Display) automatically. This optionally can generate [From](From) implementations
 /// on demand.
 ///
 /// # Example
@@ -71,6 +449,141 @@ macro_rules! hack_rust_buggy_macros {
 /// );
 /// println!("{}", TestError::Untyped);
 /// ```
+///
+/// The message of each variant is a format string receiving the payloads as positional
+/// arguments, such that any format spec can be used and variants may carry up to 4 payloads:
+///
+/// ```
+/// use bp3d_util::simple_error;
+/// simple_error!(
+///     /// Doc.
+///     ParseError {
+///         /// A debug formatted payload.
+///         Token(char) => "unexpected token {:?}",
+///         /// Multiple payloads referenced by position.
+///         Range(u32, u32, &'static str) => "{2}: {0}..{1}"
+///     }
+/// );
+/// assert_eq!(ParseError::Token('x').to_string(), "unexpected token 'x'");
+/// assert_eq!(ParseError::Range(1, 4, "bad range").to_string(), "bad range: 1..4");
+/// ```
+///
+/// Each variant may be prefixed with an [ErrorCategory](crate::simple_error::ErrorCategory)
+/// in brackets. When at least one variant declares a category, a `category` method is
+/// generated returning the category of the error, whose
+/// [name](crate::simple_error::ErrorCategory::as_str) can be forwarded to `ErrorCode::category`
+/// in the `result` module to select an exit code; variants without a category are
+/// [Internal](crate::simple_error::ErrorCategory::Internal):
+///
+/// ```
+/// use bp3d_util::simple_error;
+/// use bp3d_util::simple_error::ErrorCategory;
+/// simple_error!(
+///     /// Doc.
+///     FetchError {
+///         /// Doc.
+///         [Network] (impl From) Io(std::io::Error) => "io error {}",
+///         /// Doc.
+///         [User] BadUrl => "bad url",
+///         /// Doc.
+///         Bug => "bug"
+///     }
+/// );
+/// assert_eq!(FetchError::BadUrl.category(), ErrorCategory::User);
+/// assert_eq!(FetchError::Bug.category(), ErrorCategory::Internal);
+/// ```
+///
+/// Without any category, no `category` method is generated:
+///
+/// ```compile_fail
+/// use bp3d_util::simple_error;
+/// simple_error!(
+///     /// Doc.
+///     PlainError {
+///         /// Doc.
+///         Bug => "bug"
+///     }
+/// );
+/// let _ = PlainError::Bug.category();
+/// ```
+///
+/// A single catch-all variant may be declared with `(impl FromBox)` to absorb unforeseen error
+/// types. This generates a [From](From) implementation for the boxed error, an `other`
+/// constructor wrapping any error and a `downcast_ref` helper:
+///
+/// ```
+/// use bp3d_util::simple_error;
+/// simple_error!(
+///     /// Doc.
+///     LibError {
+///         /// Any other error.
+///         (impl FromBox) Other(Box<dyn std::error::Error + Send + Sync>) => "{}"
+///     }
+/// );
+/// let err = LibError::other(std::fmt::Error);
+/// assert!(err.downcast_ref::<std::fmt::Error>().is_some());
+/// let err: LibError = Box::<dyn std::error::Error + Send + Sync>::from("custom").into();
+/// assert_eq!(err.to_string(), "custom");
+/// ```
+///
+/// Messages are checked at compile time: the generated [Display](core::fmt::Display)
+/// implementation passes the payloads to [write] as positional arguments, such that a message
+/// with a missing or unused argument, or a format spec unsupported by the payload type, fails
+/// to compile instead of producing a malformed message when a rare error path is hit:
+///
+/// ```compile_fail
+/// use bp3d_util::simple_error;
+/// simple_error!(
+///     /// Doc.
+///     BadArity {
+///         /// Two placeholders for a single payload.
+///         Range(u32) => "{}..{}"
+///     }
+/// );
+/// ```
+///
+/// ```compile_fail
+/// use bp3d_util::simple_error;
+/// simple_error!(
+///     /// Doc.
+///     UnusedPayload {
+///         /// The payload is never formatted.
+///         Io(std::io::Error) => "io error"
+///     }
+/// );
+/// ```
+///
+/// ```compile_fail
+/// use bp3d_util::simple_error;
+/// simple_error!(
+///     /// Doc.
+///     BadSpec {
+///         /// Strings cannot be formatted as hexadecimal.
+///         Name(&'static str) => "{:x}"
+///     }
+/// );
+/// ```
+///
+/// A source type may also be given to `(impl From<E>)` to generate an additional conversion
+/// from any `E` the payload can be converted from, such as `&str` for a `String` payload or the
+/// error stored in a `Box<E>` payload, which is useful to keep large errors boxed:
+///
+/// ```
+/// use bp3d_util::simple_error;
+/// simple_error!(
+///     /// Doc.
+///     AppError {
+///         /// Doc.
+///         (impl From<&str>) Message(String) => "{}",
+///         /// Doc.
+///         (impl From<std::io::Error>) Io(Box<std::io::Error>) => "io error: {}"
+///     }
+/// );
+/// let err: AppError = "invalid state".into();
+/// assert_eq!(err.to_string(), "invalid state");
+/// let err: AppError = std::io::Error::other("disk full").into();
+/// assert_eq!(err.to_string(), "io error: disk full");
+/// ```
 #[macro_export]
 macro_rules! simple_error {
     (
@@ -78,7 +591,7 @@ macro_rules! simple_error {
         $vis: vis $name: ident {
             $(
                 $(#[$field_meta: meta])*
-                $((impl $e: ident))? $ty: ident $(($data: ty))? => $desc: literal
+                $([$cat: ident])? $((impl $e: ident $(<$src: ty>)?))? $ty: ident $(($($data: ty),+))? => $desc: literal
             ),*
         }
     ) => {
@@ -87,22 +600,23 @@ macro_rules! simple_error {
         $vis enum $name {
             $(
                 $(#[$field_meta])*
-                $ty $(($data))?
+                $ty $(($($data),+))?
             ),*
         }
 
         $(
-            $crate::hack_rust_buggy_macros!($name, $ty, $($e)?, $($data)?);
+            $crate::hack_rust_buggy_macros!($name, $ty, $($e)?, $($($data),+)?);
+            $crate::simple_error_from_source!($name, $ty, $($($src)?)?);
         )*
 
-        impl std::fmt::Display for $name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                match self {
-                    $($name::$ty $(($crate::typed_ident!($data, e)))? => write!(f, $desc $(, $crate::typed_ident!($data, e))?) ),*
-                }
+        impl core::fmt::Display for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                $crate::simple_error_fmt!(f, self, $name, [] $($ty $desc ($($($data),+)?))*)
             }
         }
 
-        impl std::error::Error for $name {}
+        $crate::simple_error_categories!($name, $($ty [$($cat)?]),*);
+
+        impl core::error::Error for $name {}
     };
 }
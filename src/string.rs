@@ -0,0 +1,432 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! String utilities.
+
+mod buf;
+mod cmdline;
+mod fold;
+mod natural;
+mod pos;
+mod semver;
+mod template;
+
+pub use buf::BufTools;
+pub use cmdline::CmdlineError;
+pub use fold::CaseInsensitive;
+pub use pos::{BytePos, CharPos};
+pub use semver::{ParseVersionError, SemVer, VersionReq};
+pub use template::{Missing, RenderError, Template, TemplateError};
+
+use crate::extension;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Write};
+
+extension! {
+    /// Extension trait for [str](str) for common functionality in BP3D software.
+    pub extension StrTools: str {
+        /// Removes the longest matching prefix among a list of candidates.
+        ///
+        /// # Arguments
+        ///
+        /// * `candidates`: the list of prefixes to try.
+        ///
+        /// returns: Option<(&str, &str)> the candidate which matched and the remainder of this
+        /// string, or None if no candidate matched.
+        fn trim_any_prefix<'a>(&self, candidates: &[&'a str]) -> Option<(&'a str, &str)>;
+
+        /// Removes the longest matching suffix among a list of candidates.
+        ///
+        /// # Arguments
+        ///
+        /// * `candidates`: the list of suffixes to try.
+        ///
+        /// returns: Option<(&str, &str)> the candidate which matched and the remainder of this
+        /// string, or None if no candidate matched.
+        fn trim_any_suffix<'a>(&self, candidates: &[&'a str]) -> Option<(&'a str, &str)>;
+
+        /// Same as [trim_any_prefix](StrTools::trim_any_prefix) but ignoring ASCII case.
+        fn trim_any_prefix_ignore_ascii_case<'a>(
+            &self,
+            candidates: &[&'a str],
+        ) -> Option<(&'a str, &str)>;
+
+        /// Same as [trim_any_suffix](StrTools::trim_any_suffix) but ignoring ASCII case.
+        fn trim_any_suffix_ignore_ascii_case<'a>(
+            &self,
+            candidates: &[&'a str],
+        ) -> Option<(&'a str, &str)>;
+
+        /// Converts a character index into a byte index which can be used to slice this string.
+        ///
+        /// Positions past the last character are clamped to the length of this string.
+        fn char_to_byte_index(&self, pos: CharPos) -> BytePos;
+
+        /// Converts a byte index into a character index.
+        ///
+        /// Positions inside a multi-byte character snap to the start of that character and
+        /// positions past the end are clamped to the number of characters of this string.
+        fn byte_to_char_index(&self, pos: BytePos) -> CharPos;
+
+        /// Compares this string with another in constant time with respect to their content.
+        ///
+        /// See [BufTools::ct_eq] for the limits of this comparison.
+        fn ct_eq(&self, other: &str) -> bool;
+
+        /// Splits this string into arguments using shell-like quoting rules.
+        ///
+        /// Arguments are separated by whitespace. Single quotes preserve their content
+        /// literally, double quotes allow escaping `"`, `\`, `$` and `` ` `` with a backslash
+        /// and outside quotes a backslash escapes any character. A backslash followed by a new
+        /// line is removed. No other shell expansion is performed.
+        ///
+        /// returns: Result<Vec<Cow<str>>, CmdlineError> arguments without quotes or escapes
+        /// are borrowed from this string.
+        fn split_cmdline(&self) -> Result<Vec<Cow<'_, str>>, CmdlineError>;
+
+        /// Compares this string with another using natural ordering, such that `file10` sorts
+        /// after `file9`.
+        ///
+        /// Runs of ASCII digits are compared by their numeric value, without any size limit;
+        /// other characters are compared by code point. When 2 strings only differ by leading
+        /// zeros, the one with fewer zeros sorts first.
+        fn natural_cmp(&self, other: &str) -> Ordering;
+
+        /// Converts ASCII letters of this string to upper case, leaving other characters
+        /// untouched, without allocating if no letter needs to change.
+        fn to_uppercase_ascii_cow(&self) -> Cow<'_, str>;
+
+        /// Converts ASCII letters of this string to lower case, leaving other characters
+        /// untouched, without allocating if no letter needs to change.
+        fn to_lowercase_ascii_cow(&self) -> Cow<'_, str>;
+
+        /// Compares this string with another ignoring case, using full Unicode case folding
+        /// unlike [eq_ignore_ascii_case](str::eq_ignore_ascii_case).
+        ///
+        /// Characters are folded by converting them to upper case then to lower case, such
+        /// that `Straße` equals `STRASSE` and `ς` equals `Σ`. Locale specific rules (ex: the
+        /// Turkish dotless i) and Unicode normalization are not applied.
+        fn eq_ignore_case_utf8(&self, other: &str) -> bool;
+    }
+}
+
+fn longest<'a>(candidates: &[&'a str], matches: impl Fn(&str) -> bool) -> Option<&'a str> {
+    candidates
+        .iter()
+        .copied()
+        .filter(|v| matches(v))
+        .max_by_key(|v| v.len())
+}
+
+fn convert_ascii_case(
+    value: &str,
+    needs_change: fn(&u8) -> bool,
+    convert: fn(&mut str),
+) -> Cow<'_, str> {
+    match value.bytes().position(|b| needs_change(&b)) {
+        None => value.into(),
+        Some(pos) => {
+            let mut owned = String::from(value);
+            // Only the tail is converted, in a loop which the compiler can vectorize.
+            convert(&mut owned[pos..]);
+            owned.into()
+        }
+    }
+}
+
+impl StrTools for str {
+    fn eq_ignore_case_utf8(&self, other: &str) -> bool {
+        fold::eq(self, other)
+    }
+
+    fn to_uppercase_ascii_cow(&self) -> Cow<'_, str> {
+        convert_ascii_case(self, u8::is_ascii_lowercase, str::make_ascii_uppercase)
+    }
+
+    fn to_lowercase_ascii_cow(&self) -> Cow<'_, str> {
+        convert_ascii_case(self, u8::is_ascii_uppercase, str::make_ascii_lowercase)
+    }
+
+    fn char_to_byte_index(&self, pos: CharPos) -> BytePos {
+        pos::char_to_byte(self, pos)
+    }
+
+    fn byte_to_char_index(&self, pos: BytePos) -> CharPos {
+        pos::byte_to_char(self, pos)
+    }
+
+    fn ct_eq(&self, other: &str) -> bool {
+        self.as_bytes().ct_eq(other.as_bytes())
+    }
+
+    fn split_cmdline(&self) -> Result<Vec<Cow<'_, str>>, CmdlineError> {
+        cmdline::split(self)
+    }
+
+    fn natural_cmp(&self, other: &str) -> Ordering {
+        natural::compare(self, other)
+    }
+
+    fn trim_any_prefix<'a>(&self, candidates: &[&'a str]) -> Option<(&'a str, &str)> {
+        let matched = longest(candidates, |v| self.starts_with(v))?;
+        Some((matched, &self[matched.len()..]))
+    }
+
+    fn trim_any_suffix<'a>(&self, candidates: &[&'a str]) -> Option<(&'a str, &str)> {
+        let matched = longest(candidates, |v| self.ends_with(v))?;
+        Some((matched, &self[..self.len() - matched.len()]))
+    }
+
+    fn trim_any_prefix_ignore_ascii_case<'a>(
+        &self,
+        candidates: &[&'a str],
+    ) -> Option<(&'a str, &str)> {
+        let matched = longest(candidates, |v| {
+            self.get(..v.len())
+                .is_some_and(|s| s.eq_ignore_ascii_case(v))
+        })?;
+        Some((matched, &self[matched.len()..]))
+    }
+
+    fn trim_any_suffix_ignore_ascii_case<'a>(
+        &self,
+        candidates: &[&'a str],
+    ) -> Option<(&'a str, &str)> {
+        let matched = longest(candidates, |v| {
+            self.len()
+                .checked_sub(v.len())
+                .and_then(|pos| self.get(pos..))
+                .is_some_and(|s| s.eq_ignore_ascii_case(v))
+        })?;
+        Some((matched, &self[..self.len() - matched.len()]))
+    }
+}
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const YEAR: u64 = 365 * DAY;
+
+/// A human-friendly representation of the time elapsed between 2 UNIX timestamps.
+///
+/// See [relative_time](relative_time).
+#[derive(Copy, Clone, Debug)]
+pub struct RelativeTime {
+    from: i64,
+    now: i64,
+}
+
+impl Display for RelativeTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let secs = self.now.abs_diff(self.from);
+        if secs < MINUTE {
+            return f.write_str("just now");
+        }
+        let (value, unit) = match secs {
+            v if v < HOUR => (v / MINUTE, 'm'),
+            v if v < DAY => (v / HOUR, 'h'),
+            v if v < YEAR => (v / DAY, 'd'),
+            v => (v / YEAR, 'y'),
+        };
+        if self.from > self.now {
+            write!(f, "in {}{}", value, unit)
+        } else {
+            write!(f, "{}{} ago", value, unit)
+        }
+    }
+}
+
+/// Formats the time elapsed since a given UNIX timestamp in a human-friendly way.
+///
+/// Thresholds are stable: anything below a minute displays as `just now`, then the largest unit
+/// among minutes, hours, days and years is used, rounded down (ex: `5m ago`, `2d ago`).
+/// Timestamps in the future display as `in 5m`.
+///
+/// # Arguments
+///
+/// * `from_unix`: the UNIX timestamp to format.
+/// * `now_unix`: the current UNIX timestamp.
+///
+/// returns: RelativeTime
+pub fn relative_time(from_unix: i64, now_unix: i64) -> RelativeTime {
+    RelativeTime {
+        from: from_unix,
+        now: now_unix,
+    }
+}
+
+/// A case transformation applied by a [CaseWriter].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Case {
+    /// Converts all characters to upper case.
+    Upper,
+
+    /// Converts all characters to lower case.
+    Lower,
+
+    /// Converts the first character written to upper case and leaves others untouched.
+    CapitalizeFirst,
+}
+
+/// A fmt [Write](Write) adapter which applies a [Case] transformation while forwarding to an
+/// underlying writer.
+pub struct CaseWriter<W: Write> {
+    inner: W,
+    case: Case,
+    first: bool,
+}
+
+impl<W: Write> CaseWriter<W> {
+    /// Creates a new [CaseWriter](CaseWriter).
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the underlying writer to forward transformed characters to.
+    /// * `case`: the case transformation to apply.
+    ///
+    /// returns: CaseWriter<W>
+    pub fn new(inner: W, case: Case) -> Self {
+        Self {
+            inner,
+            case,
+            first: true,
+        }
+    }
+
+    /// Extracts the underlying [Write](Write).
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for CaseWriter<W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        match self.case {
+            Case::Upper => s
+                .chars()
+                .flat_map(char::to_uppercase)
+                .try_for_each(|c| self.inner.write_char(c)),
+            Case::Lower => s
+                .chars()
+                .flat_map(char::to_lowercase)
+                .try_for_each(|c| self.inner.write_char(c)),
+            Case::CapitalizeFirst => {
+                if !self.first {
+                    return self.inner.write_str(s);
+                }
+                let mut chars = s.chars();
+                match chars.next() {
+                    Some(c) => {
+                        self.first = false;
+                        c.to_uppercase()
+                            .try_for_each(|c| self.inner.write_char(c))?;
+                        self.inner.write_str(chars.as_str())
+                    }
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::{relative_time, BytePos, Case, CaseWriter, CharPos, StrTools};
+    use std::fmt::Write;
+
+    #[test]
+    fn relative() {
+        assert_eq!(relative_time(100, 159).to_string(), "just now");
+        assert_eq!(relative_time(0, 300).to_string(), "5m ago");
+        assert_eq!(relative_time(0, 3 * 3600 + 59).to_string(), "3h ago");
+        assert_eq!(relative_time(0, 2 * 86400).to_string(), "2d ago");
+        assert_eq!(relative_time(0, 400 * 86400).to_string(), "1y ago");
+        assert_eq!(relative_time(600, 0).to_string(), "in 10m");
+    }
+
+    #[test]
+    fn case_writer() {
+        let mut w = CaseWriter::new(String::new(), Case::Upper);
+        let _ = w.write_str("hello ");
+        let _ = w.write_str("wörld");
+        assert_eq!(w.into_inner(), "HELLO WÖRLD");
+        let mut w = CaseWriter::new(String::new(), Case::Lower);
+        let _ = w.write_str("HeLLo");
+        assert_eq!(w.into_inner(), "hello");
+        let mut w = CaseWriter::new(String::new(), Case::CapitalizeFirst);
+        let _ = w.write_str("");
+        let _ = w.write_str("élan");
+        let _ = w.write_str(" test");
+        assert_eq!(w.into_inner(), "Élan test");
+    }
+
+    #[test]
+    fn trim_any() {
+        assert_eq!(
+            "rebuild all".trim_any_prefix(&["re", "rebuild"]),
+            Some(("rebuild", " all"))
+        );
+        assert_eq!(
+            "file.tar.gz".trim_any_suffix(&[".gz", ".tar.gz"]),
+            Some((".tar.gz", "file"))
+        );
+        assert_eq!("file.txt".trim_any_suffix(&[".gz"]), None);
+        assert_eq!(
+            "BUILD x".trim_any_prefix_ignore_ascii_case(&["build"]),
+            Some(("build", " x"))
+        );
+        assert_eq!(
+            "a.TXT".trim_any_suffix_ignore_ascii_case(&[".txt"]),
+            Some((".txt", "a"))
+        );
+        assert_eq!("é".trim_any_prefix_ignore_ascii_case(&["e"]), None);
+    }
+
+    #[test]
+    fn positions() {
+        let s = "aé€b";
+        assert_eq!(s.char_to_byte_index(CharPos(2)), BytePos(3));
+        assert_eq!(s.char_to_byte_index(CharPos(10)), BytePos(s.len()));
+        assert_eq!(s.byte_to_char_index(BytePos(4)), CharPos(2));
+        assert_eq!(s.byte_to_char_index(BytePos(6)), CharPos(3));
+        assert_eq!(s.byte_to_char_index(BytePos(100)), CharPos(4));
+    }
+
+    #[test]
+    fn ascii_case_cow() {
+        use std::borrow::Cow;
+        assert!(matches!(
+            "csv,été".to_lowercase_ascii_cow(),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!("Été CSV".to_lowercase_ascii_cow(), "Été csv");
+        assert!(matches!("ABC".to_uppercase_ascii_cow(), Cow::Borrowed(_)));
+        assert_eq!("abc-é".to_uppercase_ascii_cow(), "ABC-é");
+    }
+}
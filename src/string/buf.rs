@@ -0,0 +1,117 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::extension;
+
+extension! {
+    /// Extension trait for byte slices for common functionality in BP3D software.
+    pub extension BufTools: [u8] {
+        /// Compares this buffer with another in constant time with respect to their content.
+        ///
+        /// This is intended for comparing secrets such as authentication tokens. Only the
+        /// content is protected: the comparison returns early when the lengths differ, so the
+        /// length of a secret is not hidden. This is a best-effort implementation relying on
+        /// [black_box](std::hint::black_box) to prevent the compiler from short-circuiting; use
+        /// a dedicated crate when stronger guarantees are required.
+        ///
+        /// # Arguments
+        ///
+        /// * `other`: the buffer to compare with.
+        ///
+        /// returns: bool
+        fn ct_eq(&self, other: &[u8]) -> bool;
+
+        /// Returns the index of the first occurrence of a byte sequence in this buffer.
+        ///
+        /// An empty needle matches at index 0.
+        fn find_subslice(&self, needle: &[u8]) -> Option<usize>;
+
+        /// Splits this buffer in two at the first occurrence of a byte, the byte itself being
+        /// excluded from both parts (ex: `key=value` -> `(key, value)`).
+        ///
+        /// returns: Option<(&[u8], &[u8])> None if the byte was not found.
+        fn split_byte(&self, byte: u8) -> Option<(&[u8], &[u8])>;
+    }
+}
+
+impl BufTools for [u8] {
+    fn ct_eq(&self, other: &[u8]) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        let diff = self
+            .iter()
+            .zip(other)
+            .fold(0u8, |acc, (a, b)| std::hint::black_box(acc | (a ^ b)));
+        std::hint::black_box(diff) == 0
+    }
+
+    fn find_subslice(&self, needle: &[u8]) -> Option<usize> {
+        let (&first, rest) = match needle.split_first() {
+            Some(v) => v,
+            None => return Some(0),
+        };
+        let last = self.len().checked_sub(needle.len())?;
+        // Scanning for the first byte lets the compiler use a fast byte search.
+        (0..=last).find(|&i| self[i] == first && &self[i + 1..i + needle.len()] == rest)
+    }
+
+    fn split_byte(&self, byte: u8) -> Option<(&[u8], &[u8])> {
+        let pos = self.iter().position(|v| *v == byte)?;
+        Some((&self[..pos], &self[pos + 1..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::{BufTools, StrTools};
+
+    #[test]
+    fn ct_eq() {
+        assert!(b"token".ct_eq(b"token"));
+        assert!(!b"token".ct_eq(b"tokem"));
+        assert!(!b"token".ct_eq(b"tok"));
+        assert!([].ct_eq(b""));
+        assert!("secret".ct_eq("secret"));
+        assert!(!"secret".ct_eq("Secret"));
+    }
+
+    #[test]
+    fn search() {
+        let buf = b"GET /index HTTP/1.1\r\n";
+        assert_eq!(buf.find_subslice(b"HTTP"), Some(11));
+        assert_eq!(buf.find_subslice(b""), Some(0));
+        assert_eq!(buf.find_subslice(b"HTTP/2"), None);
+        assert_eq!(b"ab".find_subslice(b"abc"), None);
+        assert_eq!(
+            b"key=a=b".split_byte(b'='),
+            Some((&b"key"[..], &b"a=b"[..]))
+        );
+        assert_eq!(b"key".split_byte(b'='), None);
+    }
+}
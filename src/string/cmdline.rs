@@ -0,0 +1,130 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+/// An error when splitting a command line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CmdlineError {
+    /// A single or double quote is not closed.
+    UnterminatedQuote,
+
+    /// The command line ends with an unescaped backslash.
+    TrailingBackslash,
+}
+
+impl Display for CmdlineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CmdlineError::UnterminatedQuote => f.write_str("unterminated quote"),
+            CmdlineError::TrailingBackslash => f.write_str("trailing backslash"),
+        }
+    }
+}
+
+impl std::error::Error for CmdlineError {}
+
+pub(super) fn split(s: &str) -> Result<Vec<Cow<'_, str>>, CmdlineError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    loop {
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        let start = match chars.peek() {
+            Some((i, _)) => *i,
+            None => break,
+        };
+        let mut end = start;
+        let mut owned: Option<String> = None;
+        while let Some((i, c)) = chars.next_if(|(_, c)| !c.is_whitespace()) {
+            if !matches!(c, '\'' | '"' | '\\') {
+                match &mut owned {
+                    Some(buf) => buf.push(c),
+                    None => end = i + c.len_utf8(),
+                }
+                continue;
+            }
+            let buf = owned.get_or_insert_with(|| s[start..end].into());
+            match c {
+                '\'' => loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => buf.push(c),
+                        None => return Err(CmdlineError::UnterminatedQuote),
+                    }
+                },
+                '"' => loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => {
+                            match chars.next_if(|(_, c)| matches!(c, '"' | '\\' | '$' | '`' | '\n'))
+                            {
+                                Some((_, '\n')) => (),
+                                Some((_, c)) => buf.push(c),
+                                None => buf.push('\\'),
+                            }
+                        }
+                        Some((_, c)) => buf.push(c),
+                        None => return Err(CmdlineError::UnterminatedQuote),
+                    }
+                },
+                _ => match chars.next() {
+                    Some((_, '\n')) => (),
+                    Some((_, c)) => buf.push(c),
+                    None => return Err(CmdlineError::TrailingBackslash),
+                },
+            }
+        }
+        tokens.push(match owned {
+            Some(v) => Cow::Owned(v),
+            None => Cow::Borrowed(&s[start..end]),
+        });
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::{CmdlineError, StrTools};
+    use std::borrow::Cow;
+
+    #[test]
+    fn split() {
+        let tokens = r#"  cc -o "out dir/a.out" 'it''s' a\ b "q\"\n" "" "#
+            .split_cmdline()
+            .unwrap();
+        assert_eq!(
+            tokens,
+            ["cc", "-o", "out dir/a.out", "its", "a b", "q\"\\n", ""]
+        );
+        assert!(matches!(tokens[0], Cow::Borrowed(_)));
+        assert!("".split_cmdline().unwrap().is_empty());
+        assert_eq!("a 'b".split_cmdline(), Err(CmdlineError::UnterminatedQuote));
+        assert_eq!("a\\".split_cmdline(), Err(CmdlineError::TrailingBackslash));
+    }
+}
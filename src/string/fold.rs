@@ -0,0 +1,123 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// Returns the case folded characters of a string.
+///
+/// Each character is converted to upper case then to lower case, which maps characters with
+/// multiple lower case forms to the same one (ex: `ς` and `σ`) and expands characters such as
+/// `ß` to `ss`.
+pub(super) fn fold(value: &str) -> impl Iterator<Item = char> + '_ {
+    value
+        .chars()
+        .flat_map(char::to_uppercase)
+        .flat_map(char::to_lowercase)
+}
+
+pub(super) fn eq(a: &str, b: &str) -> bool {
+    // Fast path for the common case of identical strings or ASCII only strings.
+    if a == b {
+        return true;
+    }
+    if a.is_ascii() && b.is_ascii() {
+        return a.eq_ignore_ascii_case(b);
+    }
+    fold(a).eq(fold(b))
+}
+
+/// A string wrapper which compares and hashes ignoring case, using full Unicode case folding.
+///
+/// This can be used as a key in hash based containers such as
+/// [IndexMap](crate::index_map::IndexMap), see
+/// [eq_ignore_case_utf8](crate::string::StrTools::eq_ignore_case_utf8) for the comparison rules.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::string::CaseInsensitive;
+/// use std::collections::HashSet;
+///
+/// let mut keys = HashSet::new();
+/// keys.insert(CaseInsensitive("Straße"));
+/// assert!(keys.contains(&CaseInsensitive("STRASSE")));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct CaseInsensitive<'a>(pub &'a str);
+
+impl PartialEq for CaseInsensitive<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        eq(self.0, other.0)
+    }
+}
+
+impl Eq for CaseInsensitive<'_> {}
+
+impl Hash for CaseInsensitive<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in fold(self.0) {
+            state.write_u32(c as u32);
+        }
+        // Terminate the string such that sequences of keys hash differently.
+        state.write_u8(0xFF);
+    }
+}
+
+impl Display for CaseInsensitive<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::{CaseInsensitive, StrTools};
+    use std::hash::{BuildHasher, RandomState};
+
+    #[test]
+    fn compare() {
+        assert!("Straße".eq_ignore_case_utf8("STRASSE"));
+        assert!("ΣΊΣΥΦΟΣ".eq_ignore_case_utf8("σίσυφος"));
+        assert!("Éclair".eq_ignore_case_utf8("éCLAIR"));
+        assert!("Asset".eq_ignore_case_utf8("aSSET"));
+        assert!(!"Éclair".eq_ignore_case_utf8("Eclair"));
+        assert!(!"abc".eq_ignore_case_utf8("abcd"));
+    }
+
+    #[test]
+    fn hash() {
+        let state = RandomState::new();
+        assert_eq!(
+            state.hash_one(CaseInsensitive("Straße")),
+            state.hash_one(CaseInsensitive("STRASSE"))
+        );
+        assert_eq!(CaseInsensitive("Ω"), CaseInsensitive("ω"));
+        assert_eq!(CaseInsensitive("Ω").to_string(), "Ω");
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::cmp::Ordering;
+
+fn split_digits(s: &str) -> (&str, &str) {
+    let end = s
+        .bytes()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(s.len());
+    s.split_at(end)
+}
+
+pub(super) fn compare(a: &str, b: &str) -> Ordering {
+    let (mut left, mut right) = (a, b);
+    // Used to break ties between numbers which only differ by their leading zeros.
+    let mut zeros = Ordering::Equal;
+    loop {
+        let (l, r) = match (left.chars().next(), right.chars().next()) {
+            (None, None) => break,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) => (l, r),
+        };
+        if l.is_ascii_digit() && r.is_ascii_digit() {
+            let (ldigits, lrest) = split_digits(left);
+            let (rdigits, rrest) = split_digits(right);
+            let lnum = ldigits.trim_start_matches('0');
+            let rnum = rdigits.trim_start_matches('0');
+            let ord = lnum.len().cmp(&rnum.len()).then_with(|| lnum.cmp(rnum));
+            if ord != Ordering::Equal {
+                return ord;
+            }
+            if zeros == Ordering::Equal {
+                zeros = ldigits.len().cmp(&rdigits.len());
+            }
+            left = lrest;
+            right = rrest;
+        } else {
+            if l != r {
+                return l.cmp(&r);
+            }
+            left = &left[l.len_utf8()..];
+            right = &right[r.len_utf8()..];
+        }
+    }
+    zeros
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::StrTools;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn natural() {
+        let mut files = [
+            "file10.png",
+            "file9.png",
+            "file1.png",
+            "file01.png",
+            "file",
+            "a2b",
+        ];
+        files.sort_by(|a, b| a.natural_cmp(b));
+        assert_eq!(
+            files,
+            [
+                "a2b",
+                "file",
+                "file1.png",
+                "file01.png",
+                "file9.png",
+                "file10.png"
+            ]
+        );
+        assert_eq!("v1.10.0".natural_cmp("v1.9.3"), Ordering::Greater);
+        assert_eq!("x007".natural_cmp("x007"), Ordering::Equal);
+        assert_eq!(
+            "99999999999999999999999".natural_cmp("100000000000000000000000"),
+            Ordering::Less
+        );
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// A position in a string expressed as a number of characters.
+///
+/// See [char_to_byte_index](crate::string::StrTools::char_to_byte_index).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CharPos(pub usize);
+
+/// A position in a string expressed as a number of bytes, which can be used to slice the
+/// string.
+///
+/// See [byte_to_char_index](crate::string::StrTools::byte_to_char_index).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BytePos(pub usize);
+
+impl From<BytePos> for usize {
+    fn from(value: BytePos) -> Self {
+        value.0
+    }
+}
+
+impl From<CharPos> for usize {
+    fn from(value: CharPos) -> Self {
+        value.0
+    }
+}
+
+pub(super) fn char_to_byte(s: &str, pos: CharPos) -> BytePos {
+    BytePos(s.char_indices().nth(pos.0).map_or(s.len(), |(i, _)| i))
+}
+
+pub(super) fn byte_to_char(s: &str, pos: BytePos) -> CharPos {
+    let mut pos = pos.0.min(s.len());
+    while !s.is_char_boundary(pos) {
+        pos -= 1;
+    }
+    CharPos(s[..pos].chars().count())
+}
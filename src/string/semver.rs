@@ -0,0 +1,463 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Possible errors when parsing a [SemVer] or a [VersionReq].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseVersionError {
+    /// A numeric component (major, minor or patch) is missing or invalid.
+    InvalidNumber,
+
+    /// The pre-release or build metadata contains an empty or invalid identifier.
+    InvalidIdentifier,
+
+    /// A version requirement contains an empty or invalid comparator.
+    InvalidComparator,
+}
+
+impl Display for ParseVersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseVersionError::InvalidNumber => f.write_str("invalid version number"),
+            ParseVersionError::InvalidIdentifier => {
+                f.write_str("invalid pre-release or build identifier")
+            }
+            ParseVersionError::InvalidComparator => f.write_str("invalid version comparator"),
+        }
+    }
+}
+
+impl std::error::Error for ParseVersionError {}
+
+/// A semantic version (see [semver.org](https://semver.org)).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct SemVer {
+    /// The major version number.
+    pub major: u64,
+
+    /// The minor version number.
+    pub minor: u64,
+
+    /// The patch version number.
+    pub patch: u64,
+
+    /// The pre-release identifiers separated by dots (ex: `alpha.1`), empty for a release.
+    pub pre: String,
+
+    /// The build metadata separated by dots (ex: `build.5`), empty if none.
+    pub build: String,
+}
+
+impl SemVer {
+    /// Creates a new release version.
+    pub fn new(major: u64, minor: u64, patch: u64) -> SemVer {
+        SemVer {
+            major,
+            minor,
+            patch,
+            pre: String::new(),
+            build: String::new(),
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Result<u64, ParseVersionError> {
+    if s.is_empty() || (s.len() > 1 && s.starts_with('0')) || !s.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(ParseVersionError::InvalidNumber);
+    }
+    s.parse().map_err(|_| ParseVersionError::InvalidNumber)
+}
+
+fn check_identifiers(s: &str) -> Result<(), ParseVersionError> {
+    let valid = s
+        .split('.')
+        .all(|v| !v.is_empty() && v.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-'));
+    match valid {
+        true => Ok(()),
+        false => Err(ParseVersionError::InvalidIdentifier),
+    }
+}
+
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        _ => (),
+    }
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ord = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemVer {
+    /// Compares versions by precedence; build metadata is only used as a tie-breaker to stay
+    /// consistent with [Eq].
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| compare_pre(&self.pre, &other.pre))
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl Display for SemVer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+/// A version with optional minor and patch numbers, as found in version requirements.
+struct Partial {
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: String,
+}
+
+impl Partial {
+    fn parse(s: &str) -> Result<Partial, ParseVersionError> {
+        let s = s.split_once('+').map(|v| v.0).unwrap_or(s);
+        let (s, pre) = match s.split_once('-') {
+            Some((s, pre)) => {
+                check_identifiers(pre)?;
+                (s, pre)
+            }
+            None => (s, ""),
+        };
+        let mut numbers = s.split('.');
+        let major = parse_number(numbers.next().unwrap_or_default())?;
+        let minor = numbers.next().map(parse_number).transpose()?;
+        let patch = numbers.next().map(parse_number).transpose()?;
+        if numbers.next().is_some() || (patch.is_none() && !pre.is_empty()) {
+            return Err(ParseVersionError::InvalidNumber);
+        }
+        Ok(Partial {
+            major,
+            minor,
+            patch,
+            pre: pre.into(),
+        })
+    }
+
+    fn to_version(&self) -> SemVer {
+        SemVer {
+            pre: self.pre.clone(),
+            ..SemVer::new(self.major, self.minor.unwrap_or(0), self.patch.unwrap_or(0))
+        }
+    }
+}
+
+impl FromStr for SemVer {
+    type Err = ParseVersionError;
+
+    /// Parses a full semantic version; a leading `v` is accepted (ex: `v1.2.3-alpha.1+build.5`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix('v').unwrap_or(s);
+        let (s, build) = match s.split_once('+') {
+            Some((s, build)) => {
+                check_identifiers(build)?;
+                (s, build)
+            }
+            None => (s, ""),
+        };
+        let partial = Partial::parse(s)?;
+        if partial.patch.is_none() {
+            return Err(ParseVersionError::InvalidNumber);
+        }
+        Ok(SemVer {
+            build: build.into(),
+            ..partial.to_version()
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Comparator {
+    lower: Option<(SemVer, bool)>,
+    upper: Option<(SemVer, bool)>,
+    pre: Option<(u64, u64, u64)>,
+}
+
+impl Comparator {
+    fn parse(s: &str) -> Result<Comparator, ParseVersionError> {
+        const OPS: [(&str, Op); 7] = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("~", Op::Tilde),
+            ("^", Op::Caret),
+        ];
+        let s = s.trim();
+        let (op, rest) = OPS
+            .iter()
+            .find_map(|(p, op)| s.strip_prefix(p).map(|v| (*op, v)))
+            .unwrap_or((Op::Caret, s));
+        let rest = rest.trim();
+        if rest == "*" && op == Op::Caret {
+            return Ok(Comparator {
+                lower: None,
+                upper: None,
+                pre: None,
+            });
+        }
+        let p = Partial::parse(rest).map_err(|_| ParseVersionError::InvalidComparator)?;
+        let v = p.to_version();
+        // None when the bump overflows: there is then no version above the range.
+        let next_major = || Some(SemVer::new(p.major.checked_add(1)?, 0, 0));
+        let next_minor = || {
+            Some(SemVer::new(
+                p.major,
+                p.minor.unwrap_or(0).checked_add(1)?,
+                0,
+            ))
+        };
+        let exclusive = |v: Option<SemVer>| v.map(|v| (v, false));
+        let above = |v: Option<SemVer>| v.ok_or(ParseVersionError::InvalidComparator);
+        let (lower, upper) = match (op, p.minor, p.patch) {
+            (Op::Exact, Some(_), Some(_)) => (Some((v.clone(), true)), Some((v, true))),
+            (Op::Exact | Op::Tilde, Some(_), None) => (Some((v, true)), exclusive(next_minor())),
+            (Op::Exact | Op::Tilde | Op::Caret, None, _) => {
+                (Some((v, true)), exclusive(next_major()))
+            }
+            (Op::Greater, Some(_), Some(_)) => (Some((v, false)), None),
+            (Op::Greater, Some(_), None) => (Some((above(next_minor())?, true)), None),
+            (Op::Greater, None, _) => (Some((above(next_major())?, true)), None),
+            (Op::GreaterEq, _, _) => (Some((v, true)), None),
+            (Op::Less, _, _) => (None, Some((v, false))),
+            (Op::LessEq, Some(_), Some(_)) => (None, Some((v, true))),
+            (Op::LessEq, Some(_), None) => (None, exclusive(next_minor())),
+            (Op::LessEq, None, _) => (None, exclusive(next_major())),
+            (Op::Tilde, Some(_), Some(_)) => (Some((v, true)), exclusive(next_minor())),
+            (Op::Caret, Some(minor), patch) => {
+                let upper = if p.major > 0 {
+                    next_major()
+                } else if minor > 0 || patch.is_none() {
+                    next_minor()
+                } else {
+                    p.patch
+                        .unwrap_or(0)
+                        .checked_add(1)
+                        .map(|patch| SemVer::new(0, 0, patch))
+                };
+                (Some((v, true)), exclusive(upper))
+            }
+        };
+        Ok(Comparator {
+            lower,
+            upper,
+            pre: (!p.pre.is_empty()).then(|| (p.major, p.minor.unwrap_or(0), p.patch.unwrap_or(0))),
+        })
+    }
+
+    fn matches(&self, version: &SemVer) -> bool {
+        let lower = match &self.lower {
+            None => true,
+            Some((v, true)) => version >= v,
+            Some((v, false)) => version > v,
+        };
+        let upper = match &self.upper {
+            None => true,
+            // A pre-release of the upper bound is below the bound but should not match.
+            Some((v, false)) if v.pre.is_empty() && !version.pre.is_empty() => {
+                (version.major, version.minor, version.patch) < (v.major, v.minor, v.patch)
+            }
+            Some((v, true)) => version <= v,
+            Some((v, false)) => version < v,
+        };
+        lower && upper
+    }
+}
+
+/// A version requirement made of comma separated comparators (ex: `^1.2`, `>=2,<3`).
+///
+/// Supported operators are `=`, `>`, `>=`, `<`, `<=`, `~`, `^` and `*`; a bare version is treated
+/// as `^`. Partial versions follow the same rules as Cargo (ex: `^1.2` matches `>=1.2.0, <2.0.0`).
+/// Pre-release versions only match if a comparator targets the same major, minor and patch
+/// numbers with a pre-release.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionReq(Vec<Comparator>);
+
+impl VersionReq {
+    /// Checks if a version matches this requirement.
+    pub fn matches(&self, version: &SemVer) -> bool {
+        if !version.pre.is_empty()
+            && !self
+                .0
+                .iter()
+                .any(|c| c.pre == Some((version.major, version.minor, version.patch)))
+        {
+            return false;
+        }
+        self.0.iter().all(|c| c.matches(version))
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(Comparator::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(VersionReq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::{ParseVersionError, SemVer, VersionReq};
+
+    fn v(s: &str) -> SemVer {
+        s.parse().unwrap()
+    }
+
+    fn req(r: &str, s: &str) -> bool {
+        r.parse::<VersionReq>().unwrap().matches(&v(s))
+    }
+
+    #[test]
+    fn parse() {
+        let version = v("v1.2.3-alpha.1+build.5");
+        assert_eq!(version.major, 1);
+        assert_eq!(version.pre, "alpha.1");
+        assert_eq!(version.build, "build.5");
+        assert_eq!(version.to_string(), "1.2.3-alpha.1+build.5");
+        assert!("1.2".parse::<SemVer>().is_err());
+        assert!("01.2.3".parse::<SemVer>().is_err());
+        assert!("1.2.3-".parse::<SemVer>().is_err());
+        assert!("1.2.3+".parse::<SemVer>().is_err());
+    }
+
+    #[test]
+    fn ordering() {
+        let mut versions = [
+            "1.0.0",
+            "1.0.0-rc.1",
+            "1.0.0-beta.11",
+            "1.0.0-beta.2",
+            "1.0.0-alpha",
+            "0.9.9",
+        ]
+        .map(v);
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            sorted,
+            [
+                "0.9.9",
+                "1.0.0-alpha",
+                "1.0.0-beta.2",
+                "1.0.0-beta.11",
+                "1.0.0-rc.1",
+                "1.0.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn ranges() {
+        assert!(req("^1.2", "1.9.0"));
+        assert!(!req("^1.2", "2.0.0"));
+        assert!(!req("^1.2", "1.1.9"));
+        assert!(req(">=2,<3", "2.5.1"));
+        assert!(!req(">=2,<3", "3.0.0"));
+        assert!(!req(">=2,<3", "3.0.0-alpha"));
+        assert!(req("^0.2.3", "0.2.9"));
+        assert!(!req("^0.2.3", "0.3.0"));
+        assert!(!req("^0.0.3", "0.0.4"));
+        assert!(req("~1.2.3", "1.2.9"));
+        assert!(!req("~1.2.3", "1.3.0"));
+        assert!(req("=1.2.3", "1.2.3"));
+        assert!(req("*", "42.0.0"));
+        assert!(req(">1", "2.0.0"));
+        assert!(!req(">1", "1.9.0"));
+        assert!(!req("^1.2", "1.5.0-beta"));
+        assert!(req(">=1.5.0-alpha", "1.5.0-beta"));
+        assert!("^1.x".parse::<VersionReq>().is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        let max = u64::MAX;
+        assert!(req(&format!("^{}.0.0", max), &format!("{}.5.0", max)));
+        assert!(req(&format!("~1.{}", max), &format!("1.{}.7", max)));
+        assert!(req(&format!("<={}", max), &format!("{}.1.0", max)));
+        assert!(req(&format!("^0.0.{}", max), &format!("0.0.{}", max)));
+        assert_eq!(
+            format!(">{}", max).parse::<VersionReq>().unwrap_err(),
+            ParseVersionError::InvalidComparator
+        );
+    }
+}
@@ -0,0 +1,229 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter, Write};
+
+/// Possible errors when parsing a [Template].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A `{` is not closed; includes the byte offset of the `{`.
+    Unclosed(usize),
+
+    /// A `}` is not escaped and does not close a placeholder; includes its byte offset.
+    Unopened(usize),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::Unclosed(pos) => write!(f, "unclosed '{{' at offset {}", pos),
+            TemplateError::Unopened(pos) => write!(f, "unexpected '}}' at offset {}", pos),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Possible errors when rendering a [Template].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RenderError {
+    /// A formatting error.
+    Fmt(std::fmt::Error),
+
+    /// A key has no value; only returned in [Missing::Error] mode.
+    MissingKey(String),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::Fmt(e) => write!(f, "format error: {}", e),
+            RenderError::MissingKey(key) => write!(f, "missing value for key '{}'", key),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// How to handle keys without a value when rendering a [Template].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Missing {
+    /// Fails with [RenderError::MissingKey] (strict mode).
+    Error,
+
+    /// Writes the placeholder unchanged (lenient mode).
+    Keep,
+
+    /// Writes nothing (lenient mode).
+    Empty,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Part<'a> {
+    Literal(&'a str),
+    Key(&'a str),
+}
+
+/// A parsed template with `{key}` placeholders; `{{` and `}}` are escapes for `{` and `}`.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::string::{Missing, Template};
+/// use std::borrow::Cow;
+///
+/// let template = Template::parse("Hello {name}, {count} items {{}}").unwrap();
+/// let mut out = String::new();
+/// template.render(&mut out, Missing::Error, |key| match key {
+///     "name" => Some(Cow::Borrowed("Bob")),
+///     "count" => Some(Cow::Owned(42.to_string())),
+///     _ => None,
+/// }).unwrap();
+/// assert_eq!(out, "Hello Bob, 42 items {}");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template<'a> {
+    parts: Vec<Part<'a>>,
+}
+
+impl<'a> Template<'a> {
+    /// Parses a template.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: the template string.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TemplateError] if a brace is not balanced.
+    pub fn parse(source: &'a str) -> Result<Template<'a>, TemplateError> {
+        let mut parts = Vec::new();
+        let mut rest = source;
+        while let Some(pos) = rest.find(['{', '}']) {
+            let offset = source.len() - rest.len() + pos;
+            if pos > 0 {
+                parts.push(Part::Literal(&rest[..pos]));
+            }
+            let brace = &rest[pos..pos + 1];
+            rest = &rest[pos + 1..];
+            if rest.starts_with(brace) {
+                parts.push(Part::Literal(brace));
+                rest = &rest[1..];
+            } else if brace == "}" {
+                return Err(TemplateError::Unopened(offset));
+            } else {
+                let end = rest
+                    .find(['{', '}'])
+                    .ok_or(TemplateError::Unclosed(offset))?;
+                if &rest[end..end + 1] == "{" {
+                    return Err(TemplateError::Unclosed(offset));
+                }
+                parts.push(Part::Key(rest[..end].trim()));
+                rest = &rest[end + 1..];
+            }
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest));
+        }
+        Ok(Template { parts })
+    }
+
+    /// Returns an iterator over all keys referenced by this template.
+    pub fn keys(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.parts.iter().filter_map(|v| match v {
+            Part::Key(key) => Some(*key),
+            Part::Literal(_) => None,
+        })
+    }
+
+    /// Renders this template into a fmt [Write](Write).
+    ///
+    /// # Arguments
+    ///
+    /// * `w`: the writer to render into.
+    /// * `missing`: how to handle keys without a value.
+    /// * `lookup`: the function returning the value of a key.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [RenderError] if writing failed or a key has no value in strict mode.
+    pub fn render<'b, W: Write, F: Fn(&str) -> Option<Cow<'b, str>>>(
+        &self,
+        w: &mut W,
+        missing: Missing,
+        lookup: F,
+    ) -> Result<(), RenderError> {
+        for part in &self.parts {
+            match part {
+                Part::Literal(v) => w.write_str(v),
+                Part::Key(key) => match (lookup(key), missing) {
+                    (Some(value), _) => w.write_str(&value),
+                    (None, Missing::Error) => return Err(RenderError::MissingKey((*key).into())),
+                    (None, Missing::Keep) => write!(w, "{{{}}}", key),
+                    (None, Missing::Empty) => Ok(()),
+                },
+            }
+            .map_err(RenderError::Fmt)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::{Missing, RenderError, Template, TemplateError};
+    use std::borrow::Cow;
+
+    fn lookup(key: &str) -> Option<Cow<'static, str>> {
+        (key == "a").then_some(Cow::Borrowed("1"))
+    }
+
+    #[test]
+    fn render() {
+        let template = Template::parse("{a}-{ b }-{{x}}").unwrap();
+        assert_eq!(template.keys().collect::<Vec<_>>(), ["a", "b"]);
+        let mut out = String::new();
+        template.render(&mut out, Missing::Keep, lookup).unwrap();
+        assert_eq!(out, "1-{b}-{x}");
+        out.clear();
+        template.render(&mut out, Missing::Empty, lookup).unwrap();
+        assert_eq!(out, "1--{x}");
+        assert_eq!(
+            template.render(&mut out, Missing::Error, lookup),
+            Err(RenderError::MissingKey("b".into()))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Template::parse("a {b"), Err(TemplateError::Unclosed(2)));
+        assert_eq!(Template::parse("{a{b}"), Err(TemplateError::Unclosed(0)));
+        assert_eq!(Template::parse("a}"), Err(TemplateError::Unopened(1)));
+    }
+}
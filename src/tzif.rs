@@ -30,6 +30,25 @@
 //!
 //! **See [RFC](https://www.rfc-editor.org/rfc/rfc8536.html)**
 
+#[cfg(feature = "tzif-async")]
+mod async_read;
+mod builder;
+mod bundle;
+mod compiled;
+pub mod diff;
+mod embed;
+pub mod posix;
+mod strict;
+#[cfg(feature = "tzif-testing")]
+pub mod testing;
+pub mod windows;
+
+pub use builder::{BuildError, TZIFBuilder};
+pub use bundle::{Bundle, BundleEntry};
+pub use compiled::{Compiled, OffsetRange};
+pub use embed::EmbeddedZones;
+pub use strict::{Corruption, Section};
+
 use bytesutil::ReadBytes;
 use std::{fmt::Display, io::Read};
 
@@ -65,6 +84,9 @@ pub enum Error {
 
     /// The signature of the file cannot be recognized.
     InvalidSignature,
+
+    /// A header count or the total size of the stream exceeds the configured [Limits].
+    LimitExceeded(&'static str),
 }
 
 impl Display for Error {
@@ -72,12 +94,91 @@ impl Display for Error {
         match self {
             Error::Io(e) => write!(f, "io error: {}", e),
             Error::InvalidSignature => f.write_str("invalid TZIF signature"),
+            Error::LimitExceeded(name) => write!(f, "limit exceeded: {}", name),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+/// A non-fatal issue encountered when reading a TZIF stream in lenient mode.
+#[derive(Debug)]
+pub enum Warning {
+    /// The V1 header announces a V2+ block but its header could not be read, the V2+ block was
+    /// skipped.
+    V2Header(Error),
+
+    /// The V2+ data block is corrupt or truncated, the V2+ block was skipped.
+    V2Data(Error),
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::V2Header(e) => write!(f, "skipped V2+ block: bad header ({})", e),
+            Warning::V2Data(e) => write!(f, "skipped V2+ block: bad data ({})", e),
+        }
+    }
+}
+
+/// Limits applied when decoding an untrusted TZIF stream with
+/// [read_untrusted](TZIF::read_untrusted).
+///
+/// All header counts are checked before any allocation is made.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum number of transition times in a data block.
+    pub max_timecnt: u32,
+
+    /// The maximum number of local time type records (and UT/local and standard/wall
+    /// indicators) in a data block.
+    pub max_typecnt: u32,
+
+    /// The maximum number of octets of time zone designations in a data block.
+    pub max_charcnt: u32,
+
+    /// The maximum number of leap-second records in a data block.
+    pub max_leapcnt: u32,
+
+    /// The maximum total number of octets of the stream, excluding the footer.
+    pub max_total_bytes: u64,
+}
+
+impl Default for Limits {
+    /// Returns limits comfortably above the largest time zones of the IANA database.
+    fn default() -> Self {
+        Limits {
+            max_timecnt: 4096,
+            max_typecnt: 256,
+            max_charcnt: 256,
+            max_leapcnt: 256,
+            max_total_bytes: 128 * 1024,
+        }
+    }
+}
+
+/// Whether the transition times associated with a local time type were specified as standard
+/// time or wall clock time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StdWall {
+    /// Standard time.
+    Standard,
+
+    /// Wall clock time.
+    Wall,
+}
+
+/// Whether the transition times associated with a local time type were specified as UT or
+/// local time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UtLocal {
+    /// Universal time.
+    Ut,
+
+    /// Local time.
+    Local,
+}
+
 /// A data block.
 pub struct Data {
     /// A series of four- or eight-octet UNIX leap-time values sorted in strictly ascending order.
@@ -91,6 +192,12 @@ pub struct Data {
 
     /// A series of eight- or twelve-octet records specifying the corrections that need to be applied to UTC in order to determine TAI.
     pub leap_second_records: Vec<LeapSecondRecord>,
+
+    /// A series of one-octet values indicating whether the transition times associated with a local time type were specified as standard time or wall clock time.
+    pub std_wall_indicators: Vec<StdWall>,
+
+    /// A series of one-octet values indicating whether the transition times associated with a local time type were specified as UT or local time.
+    pub ut_local_indicators: Vec<UtLocal>,
 }
 
 /// TZIF header.
@@ -117,6 +224,20 @@ pub struct Header {
     pub charcnt: u32, //40..44
 }
 
+/// A transition resolved with its local time type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Transition {
+    /// The UNIX time at which the transition occurs.
+    pub time: i64,
+
+    /// The number of seconds to be added to UT in order to determine local time after the
+    /// transition.
+    pub utoff: i32,
+
+    /// Whether local time after the transition is Daylight Saving Time.
+    pub dst: bool,
+}
+
 /// Combined header and data block.
 ///
 /// According to RFC, there can be currently 2 blocks: 1 for V1 header/data and 1 for V2+ header/data.
@@ -137,6 +258,15 @@ pub struct TZIF {
     pub block_v2p: Option<Block>,
 }
 
+/// The result of reading a TZIF stream in lenient mode.
+pub struct Lenient {
+    /// The successfully decoded part of the TZIF stream.
+    pub tzif: TZIF,
+
+    /// The list of issues encountered while decoding.
+    pub warnings: Vec<Warning>,
+}
+
 impl Header {
     fn time_size(&self) -> usize {
         match self.version {
@@ -145,6 +275,39 @@ impl Header {
         }
     }
 
+    fn data_size(&self) -> u64 {
+        let size = self.time_size() as u64;
+        (size + 1) * self.timecnt as u64
+            + 6 * self.typecnt as u64
+            + self.charcnt as u64
+            + (size + 4) * self.leapcnt as u64
+            + self.isstdcnt as u64
+            + self.isutcnt as u64
+    }
+
+    fn check(&self, limits: &Limits, total_bytes: &mut u64) -> Result<(), Error> {
+        if self.timecnt > limits.max_timecnt {
+            return Err(Error::LimitExceeded("timecnt"));
+        }
+        if self.typecnt > limits.max_typecnt
+            || self.isstdcnt > limits.max_typecnt
+            || self.isutcnt > limits.max_typecnt
+        {
+            return Err(Error::LimitExceeded("typecnt"));
+        }
+        if self.charcnt > limits.max_charcnt {
+            return Err(Error::LimitExceeded("charcnt"));
+        }
+        if self.leapcnt > limits.max_leapcnt {
+            return Err(Error::LimitExceeded("leapcnt"));
+        }
+        *total_bytes += 44 + self.data_size();
+        if *total_bytes > limits.max_total_bytes {
+            return Err(Error::LimitExceeded("total bytes"));
+        }
+        Ok(())
+    }
+
     fn read<R: Read>(mut reader: R) -> Result<Header, Error> {
         let mut header: [u8; 44] = [0; 44];
         reader.read_exact(&mut header).map_err(Error::Io)?;
@@ -164,6 +327,59 @@ impl Header {
 }
 
 impl Data {
+    /// Returns an iterator over all transitions resolved with their local time type.
+    ///
+    /// Transitions referencing a non-existent local time type are skipped.
+    pub fn transitions(&self) -> impl Iterator<Item = Transition> + '_ {
+        self.transition_times
+            .iter()
+            .zip(self.transition_types.iter())
+            .filter_map(|(time, ty)| {
+                let record = self.local_time_type_records.get(*ty as usize)?;
+                Some(Transition {
+                    time: *time,
+                    utoff: record.utoff,
+                    dst: record.dst,
+                })
+            })
+    }
+
+    /// Returns the standard/wall indicator of a local time type.
+    ///
+    /// Per RFC 8536, all local time types are wall clock time when no indicators are present.
+    /// Returns None if the local time type does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `ty`: the index of the local time type.
+    pub fn std_wall(&self, ty: u8) -> Option<StdWall> {
+        self.local_time_type_records.get(ty as usize)?;
+        Some(
+            self.std_wall_indicators
+                .get(ty as usize)
+                .copied()
+                .unwrap_or(StdWall::Wall),
+        )
+    }
+
+    /// Returns the UT/local indicator of a local time type.
+    ///
+    /// Per RFC 8536, all local time types are local time when no indicators are present.
+    /// Returns None if the local time type does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `ty`: the index of the local time type.
+    pub fn ut_local(&self, ty: u8) -> Option<UtLocal> {
+        self.local_time_type_records.get(ty as usize)?;
+        Some(
+            self.ut_local_indicators
+                .get(ty as usize)
+                .copied()
+                .unwrap_or(UtLocal::Local),
+        )
+    }
+
     fn read<R: Read>(mut reader: R, header: &Header) -> Result<Data, Error> {
         let size = header.time_size();
         let mut transition_times = vec![0; size * header.timecnt as usize];
@@ -192,9 +408,23 @@ impl Data {
             .map_err(Error::Io)?;
         let mut ut_indicators = vec![0; header.isutcnt as usize];
         reader.read_exact(&mut ut_indicators).map_err(Error::Io)?;
+        let std_wall_indicators = std_wall_indicators
+            .into_iter()
+            .map(|v| match v {
+                1 => StdWall::Standard,
+                _ => StdWall::Wall,
+            })
+            .collect();
+        let ut_local_indicators = ut_indicators
+            .into_iter()
+            .map(|v| match v {
+                1 => UtLocal::Ut,
+                _ => UtLocal::Local,
+            })
+            .collect();
         let local_time_type_records = local_time_type_records
             .as_slice()
-            .chunks(6)
+            .chunks_exact(6)
             .map(|v| LocalTimeTypeRecord {
                 utoff: i32::read_bytes_be(&v[0..4]),
                 dst: v[4] == 1,
@@ -206,42 +436,52 @@ impl Data {
                 transition_types,
                 transition_times: transition_times
                     .as_slice()
-                    .chunks(4)
+                    .chunks_exact(4)
                     .map(|v| i32::read_bytes_be(v) as i64)
                     .collect(),
                 leap_second_records: leap_second_records
                     .as_slice()
-                    .chunks(8)
+                    .chunks_exact(8)
                     .map(|v| LeapSecondRecord {
                         occurrence: i32::read_bytes_be(&v[0..4]) as i64,
                         correction: i32::read_bytes_be(&v[4..8]),
                     })
                     .collect(),
                 local_time_type_records,
+                std_wall_indicators,
+                ut_local_indicators,
             })
         } else {
             Ok(Data {
                 transition_types,
                 transition_times: transition_times
                     .as_slice()
-                    .chunks(8)
+                    .chunks_exact(8)
                     .map(i64::read_bytes_be)
                     .collect(),
                 leap_second_records: leap_second_records
                     .as_slice()
-                    .chunks(12)
+                    .chunks_exact(12)
                     .map(|v| LeapSecondRecord {
                         occurrence: i64::read_bytes_be(&v[0..8]),
                         correction: i32::read_bytes_be(&v[8..12]),
                     })
                     .collect(),
                 local_time_type_records,
+                std_wall_indicators,
+                ut_local_indicators,
             })
         }
     }
 }
 
 impl TZIF {
+    /// Returns the most precise block available: the V2+ block if present, the V1 block
+    /// otherwise.
+    pub fn block(&self) -> &Block {
+        self.block_v2p.as_ref().unwrap_or(&self.block_v1)
+    }
+
     /// Reads and decodes a TZIF stream.
     ///
     /// # Arguments
@@ -251,18 +491,50 @@ impl TZIF {
     /// # Errors
     ///
     /// This function returns an [Error](Error) if the simplified TZIF structure could not be decoded.
-    pub fn read<R: Read>(mut reader: R) -> Result<TZIF, Error> {
+    pub fn read<R: Read>(reader: R) -> Result<TZIF, Error> {
+        Self::read_with_limits(reader, None)
+    }
+
+    /// Reads and decodes a TZIF stream from an untrusted source.
+    ///
+    /// Unlike [read](TZIF::read), the header counts are checked against the given [Limits]
+    /// before allocating, such that a hostile stream cannot request large allocations.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader`: the [Read](Read) to read and decode from.
+    /// * `limits`: the limits to apply.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [LimitExceeded](Error::LimitExceeded) if the stream exceeds any of
+    /// the limits, or any other [Error](Error) if the simplified TZIF structure could not be
+    /// decoded.
+    pub fn read_untrusted<R: Read>(reader: R, limits: &Limits) -> Result<TZIF, Error> {
+        Self::read_with_limits(reader, Some(limits))
+    }
+
+    fn read_with_limits<R: Read>(mut reader: R, limits: Option<&Limits>) -> Result<TZIF, Error> {
+        let mut total_bytes = 0;
         let mut header_v1 = Header::read(&mut reader)?;
         header_v1.version = 0x00; //RFC is badly broken it says bullshit.
+        if let Some(limits) = limits {
+            header_v1.check(limits, &mut total_bytes)?;
+        }
         let block_v1 = Block {
             data: Data::read(&mut reader, &header_v1)?,
             header: header_v1,
         };
         let block_v2p = match Header::read(&mut reader) {
-            Ok(header_v2) => Some(Block {
-                data: Data::read(&mut reader, &header_v2)?,
-                header: header_v2,
-            }),
+            Ok(header_v2) => {
+                if let Some(limits) = limits {
+                    header_v2.check(limits, &mut total_bytes)?;
+                }
+                Some(Block {
+                    data: Data::read(&mut reader, &header_v2)?,
+                    header: header_v2,
+                })
+            }
             _ => None,
         };
         Ok(TZIF {
@@ -270,4 +542,124 @@ impl TZIF {
             block_v2p,
         })
     }
+
+    /// Reads and decodes a TZIF stream in lenient mode.
+    ///
+    /// Unlike [read](TZIF::read), a corrupt or truncated V2+ block does not fail the entire
+    /// decoding; instead the V2+ block is skipped and a [Warning] is recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader`: the [Read](Read) to read and decode from.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [Error](Error) if the V1 block could not be decoded.
+    pub fn read_lenient<R: Read>(mut reader: R) -> Result<Lenient, Error> {
+        let mut header_v1 = Header::read(&mut reader)?;
+        let has_v2p = header_v1.version != 0x00;
+        header_v1.version = 0x00;
+        let block_v1 = Block {
+            data: Data::read(&mut reader, &header_v1)?,
+            header: header_v1,
+        };
+        let mut warnings = Vec::new();
+        let block_v2p = match Header::read(&mut reader) {
+            Ok(header_v2) => match Data::read(&mut reader, &header_v2) {
+                Ok(data) => Some(Block {
+                    data,
+                    header: header_v2,
+                }),
+                Err(e) => {
+                    warnings.push(Warning::V2Data(e));
+                    None
+                }
+            },
+            Err(e) => {
+                if has_v2p {
+                    warnings.push(Warning::V2Header(e));
+                }
+                None
+            }
+        };
+        Ok(Lenient {
+            tzif: TZIF {
+                block_v1,
+                block_v2p,
+            },
+            warnings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::{Error, Limits, StdWall, UtLocal, Warning, TZIF};
+
+    pub(crate) fn block(version: u8, time_size: usize) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"TZif");
+        buf.push(version);
+        buf.extend_from_slice(&[0; 15]);
+        for count in [0u32, 0, 0, 1, 1, 4] {
+            buf.extend_from_slice(&count.to_be_bytes());
+        }
+        buf.extend_from_slice(&vec![0; time_size]);
+        buf.push(0);
+        buf.extend_from_slice(&3600i32.to_be_bytes());
+        buf.extend_from_slice(&[0, 0]);
+        buf.extend_from_slice(b"CET\0");
+        buf
+    }
+
+    #[test]
+    fn lenient() {
+        let mut data = block(b'2', 4);
+        data.extend_from_slice(&block(b'2', 8));
+        let res = TZIF::read_lenient(&*data).unwrap();
+        assert!(res.warnings.is_empty());
+        assert!(res.tzif.block_v2p.is_some());
+        data.truncate(data.len() - 4);
+        assert!(TZIF::read(&*data).is_err());
+        let res = TZIF::read_lenient(&*data).unwrap();
+        assert!(matches!(res.warnings[..], [Warning::V2Data(_)]));
+        assert!(res.tzif.block_v2p.is_none());
+        let v1 = block(0, 4);
+        let res = TZIF::read_lenient(&*v1).unwrap();
+        assert!(res.warnings.is_empty());
+        let res = TZIF::read_lenient(&data[..data.len() - 40]).unwrap();
+        assert!(matches!(res.warnings[..], [Warning::V2Header(_)]));
+    }
+
+    #[test]
+    fn untrusted() {
+        let mut data = block(b'2', 4);
+        data.extend_from_slice(&block(b'2', 8));
+        assert!(TZIF::read_untrusted(&*data, &Limits::default()).is_ok());
+        let mut hostile = block(b'2', 4);
+        hostile[32..36].copy_from_slice(&u32::MAX.to_be_bytes());
+        let res = TZIF::read_untrusted(&*hostile, &Limits::default());
+        assert!(matches!(res, Err(Error::LimitExceeded("timecnt"))));
+        let limits = Limits {
+            max_total_bytes: 80,
+            ..Limits::default()
+        };
+        let res = TZIF::read_untrusted(&*data, &limits);
+        assert!(matches!(res, Err(Error::LimitExceeded("total bytes"))));
+    }
+
+    #[test]
+    fn indicators() {
+        let mut data = block(0, 4);
+        data[20..24].copy_from_slice(&1u32.to_be_bytes());
+        data[24..28].copy_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(&[1, 1]);
+        let tzif = TZIF::read(&*data).unwrap();
+        assert_eq!(tzif.block_v1.data.std_wall(0), Some(StdWall::Standard));
+        assert_eq!(tzif.block_v1.data.ut_local(0), Some(UtLocal::Ut));
+        assert_eq!(tzif.block_v1.data.ut_local(1), None);
+        let tzif = TZIF::read(&*block(0, 4)).unwrap();
+        assert_eq!(tzif.block_v1.data.std_wall(0), Some(StdWall::Wall));
+        assert_eq!(tzif.block_v1.data.ut_local(0), Some(UtLocal::Local));
+    }
 }
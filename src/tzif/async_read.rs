@@ -0,0 +1,98 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::tzif::{Error, Limits, TZIF};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// The size of a TZIF header in bytes.
+const HEADER_SIZE: u64 = 44;
+
+impl TZIF {
+    /// Reads and decodes a TZIF stream from an [AsyncRead] source.
+    ///
+    /// The stream is read entirely into memory before being decoded with [read](TZIF::read).
+    ///
+    /// # Arguments
+    ///
+    /// * `reader`: the [AsyncRead] to read and decode from.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [Error](Error) if the stream could not be read or the
+    /// simplified TZIF structure could not be decoded.
+    pub async fn read_async<R: AsyncRead + Unpin>(mut reader: R) -> Result<TZIF, Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await.map_err(Error::Io)?;
+        TZIF::read(&*buf)
+    }
+
+    /// Reads and decodes a TZIF stream from an untrusted [AsyncRead] source.
+    ///
+    /// At most the number of bytes allowed by [Limits::max_total_bytes] plus the size of the
+    /// headers is read before decoding with [read_untrusted](TZIF::read_untrusted).
+    ///
+    /// # Arguments
+    ///
+    /// * `reader`: the [AsyncRead] to read and decode from.
+    /// * `limits`: the limits to apply.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [Error](Error) if the stream could not be read, exceeds any of
+    /// the limits or the simplified TZIF structure could not be decoded.
+    pub async fn read_async_untrusted<R: AsyncRead + Unpin>(
+        reader: R,
+        limits: &Limits,
+    ) -> Result<TZIF, Error> {
+        let mut buf = Vec::new();
+        let max = limits.max_total_bytes.saturating_add(2 * HEADER_SIZE);
+        reader
+            .take(max)
+            .read_to_end(&mut buf)
+            .await
+            .map_err(Error::Io)?;
+        TZIF::read_untrusted(&*buf, limits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::tests::block;
+    use crate::tzif::{Limits, TZIF};
+
+    #[tokio::test]
+    async fn read_async() {
+        let mut data = block(b'2', 4);
+        data.extend_from_slice(&block(b'2', 8));
+        let tzif = TZIF::read_async(&*data).await.unwrap();
+        assert!(tzif.block_v2p.is_some());
+        let limits = Limits::default();
+        assert!(TZIF::read_async_untrusted(&*data, &limits).await.is_ok());
+        assert!(TZIF::read_async(&data[..10]).await.is_err());
+    }
+}
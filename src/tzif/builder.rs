@@ -0,0 +1,404 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::tzif::{Block, Data, Header, LeapSecondRecord, LocalTimeTypeRecord, TZIF};
+use std::fmt::Display;
+
+// A local time type record: utoff, dst and the index of the designation.
+type Record = (i32, bool, u8);
+
+fn write_block(
+    out: &mut Vec<u8>,
+    version: u8,
+    wide: bool,
+    transitions: &[(i64, u8)],
+    types: &[Record],
+    designations: &[u8],
+    leap_seconds: &[(i64, i32)],
+) {
+    let fits = |time: i64| wide || i32::try_from(time).is_ok();
+    let transitions: Vec<_> = transitions.iter().filter(|v| fits(v.0)).collect();
+    let leap_seconds: Vec<_> = leap_seconds.iter().filter(|v| fits(v.0)).collect();
+    out.extend_from_slice(b"TZif");
+    out.push(version);
+    out.extend_from_slice(&[0; 15]);
+    let counts = [
+        0,
+        0,
+        leap_seconds.len(),
+        transitions.len(),
+        types.len(),
+        designations.len(),
+    ];
+    for count in counts {
+        out.extend_from_slice(&(count as u32).to_be_bytes());
+    }
+    let write_time = |out: &mut Vec<u8>, time: i64| match wide {
+        true => out.extend_from_slice(&time.to_be_bytes()),
+        false => out.extend_from_slice(&(time as i32).to_be_bytes()),
+    };
+    for (time, _) in &transitions {
+        write_time(out, *time);
+    }
+    out.extend(transitions.iter().map(|v| v.1));
+    for (utoff, dst, idx) in types {
+        out.extend_from_slice(&utoff.to_be_bytes());
+        out.push(*dst as u8);
+        out.push(*idx);
+    }
+    out.extend_from_slice(designations);
+    for (occurrence, correction) in &leap_seconds {
+        write_time(out, *occurrence);
+        out.extend_from_slice(&correction.to_be_bytes());
+    }
+}
+
+/// Writes a TZIF byte stream, without any validation.
+///
+/// For V2+ streams, the V1 block only contains the transitions and leap seconds which fit in 32
+/// bits.
+fn write(
+    version: u8,
+    transitions: &[(i64, u8)],
+    types: &[Record],
+    designations: &[u8],
+    leap_seconds: &[(i64, i32)],
+    footer: &str,
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    let block = |out: &mut Vec<u8>, wide| {
+        write_block(
+            out,
+            version,
+            wide,
+            transitions,
+            types,
+            designations,
+            leap_seconds,
+        )
+    };
+    block(&mut out, false);
+    if version != 0 {
+        block(&mut out, true);
+        out.push(b'\n');
+        out.extend_from_slice(footer.as_bytes());
+        out.push(b'\n');
+    }
+    out
+}
+
+/// Possible errors when building a [TZIF] with a [TZIFBuilder].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// No local time type was added.
+    NoLocalTimeType,
+
+    /// More than 256 local time types were added.
+    TooManyLocalTimeTypes,
+
+    /// A time zone designation is empty, not ASCII or contains a NUL character.
+    InvalidDesignation(String),
+
+    /// The time zone designations exceed the 256 octets addressable by a local time type.
+    DesignationsTooLong,
+
+    /// A transition references a local time type which does not exist.
+    InvalidTransitionType(u8),
+
+    /// Transitions are not in strictly ascending time order.
+    UnsortedTransitions,
+
+    /// Leap seconds are not in strictly ascending time order.
+    UnsortedLeapSeconds,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::NoLocalTimeType => f.write_str("no local time type"),
+            BuildError::TooManyLocalTimeTypes => f.write_str("too many local time types"),
+            BuildError::InvalidDesignation(name) => {
+                write!(f, "invalid time zone designation '{}'", name)
+            }
+            BuildError::DesignationsTooLong => f.write_str("time zone designations too long"),
+            BuildError::InvalidTransitionType(ty) => {
+                write!(f, "transition references unknown local time type {}", ty)
+            }
+            BuildError::UnsortedTransitions => f.write_str("transitions are not sorted"),
+            BuildError::UnsortedLeapSeconds => f.write_str("leap seconds are not sorted"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// A builder to construct a validated [TZIF] programmatically.
+///
+/// The built [TZIF] contains a V2 block with all transitions and leap seconds and a V1 block
+/// with only those which fit in 32 bits. As [TZIF] does not retain the time zone designations,
+/// use [to_bytes](TZIFBuilder::to_bytes) to obtain a complete TZIF file.
+///
+/// # Example
+///
+/// ```
+/// use bp3d_util::tzif::TZIFBuilder;
+///
+/// let tzif = TZIFBuilder::new()
+///     .local_time_type(3600, false, "CET")
+///     .local_time_type(7200, true, "CEST")
+///     .transition(1711846800, 1)
+///     .build()
+///     .unwrap();
+/// assert_eq!(tzif.block().data.transitions().next().unwrap().utoff, 7200);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TZIFBuilder {
+    transitions: Vec<(i64, u8)>,
+    types: Vec<(i32, bool, String)>,
+    leap_seconds: Vec<(i64, i32)>,
+    footer: String,
+}
+
+impl TZIFBuilder {
+    /// Creates a new empty builder.
+    pub fn new() -> TZIFBuilder {
+        TZIFBuilder::default()
+    }
+
+    /// Adds a transition.
+    ///
+    /// # Arguments
+    ///
+    /// * `time`: the UNIX time of the transition.
+    /// * `ty`: the index of the local time type applied from this transition.
+    pub fn transition(mut self, time: i64, ty: u8) -> Self {
+        self.transitions.push((time, ty));
+        self
+    }
+
+    /// Adds a local time type; the first type added has index 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `utoff`: the number of seconds to add to UT in order to determine local time.
+    /// * `dst`: whether this local time type is Daylight Saving Time.
+    /// * `name`: the time zone designation (ex: `CEST`).
+    pub fn local_time_type(mut self, utoff: i32, dst: bool, name: &str) -> Self {
+        self.types.push((utoff, dst, name.into()));
+        self
+    }
+
+    /// Adds a leap second record.
+    ///
+    /// # Arguments
+    ///
+    /// * `occurrence`: the UNIX time at which the correction occurs.
+    /// * `correction`: the value of LEAPCORR on or after the occurrence.
+    pub fn leap_second(mut self, occurrence: i64, correction: i32) -> Self {
+        self.leap_seconds.push((occurrence, correction));
+        self
+    }
+
+    /// Sets the TZ string footer written by [to_bytes](TZIFBuilder::to_bytes) (ex:
+    /// `CET-1CEST,M3.5.0,M10.5.0/3`).
+    pub fn footer(mut self, footer: &str) -> Self {
+        self.footer = footer.into();
+        self
+    }
+
+    fn block(&self, version: u8, records: &[Record], charcnt: u32) -> Block {
+        let fits = |time: i64| version != 0x00 || i32::try_from(time).is_ok();
+        let transitions: Vec<_> = self.transitions.iter().filter(|v| fits(v.0)).collect();
+        let leap_second_records: Vec<_> = self
+            .leap_seconds
+            .iter()
+            .filter(|v| fits(v.0))
+            .map(|(occurrence, correction)| LeapSecondRecord {
+                occurrence: *occurrence,
+                correction: *correction,
+            })
+            .collect();
+        Block {
+            header: Header {
+                version,
+                isutcnt: 0,
+                isstdcnt: 0,
+                leapcnt: leap_second_records.len() as u32,
+                timecnt: transitions.len() as u32,
+                typecnt: records.len() as u32,
+                charcnt,
+            },
+            data: Data {
+                transition_times: transitions.iter().map(|v| v.0).collect(),
+                transition_types: transitions.iter().map(|v| v.1).collect(),
+                local_time_type_records: records
+                    .iter()
+                    .map(|(utoff, dst, idx)| LocalTimeTypeRecord {
+                        utoff: *utoff,
+                        dst: *dst,
+                        idx: *idx,
+                    })
+                    .collect(),
+                leap_second_records,
+                std_wall_indicators: Vec::new(),
+                ut_local_indicators: Vec::new(),
+            },
+        }
+    }
+
+    // Returns the local time type records and the time zone designations they index.
+    fn validate(&self) -> Result<(Vec<Record>, Vec<u8>), BuildError> {
+        if self.types.is_empty() {
+            return Err(BuildError::NoLocalTimeType);
+        }
+        if self.types.len() > 256 {
+            return Err(BuildError::TooManyLocalTimeTypes);
+        }
+        let mut designations = Vec::new();
+        let mut records = Vec::with_capacity(self.types.len());
+        for (utoff, dst, name) in &self.types {
+            if name.is_empty() || !name.is_ascii() || name.contains('\0') {
+                return Err(BuildError::InvalidDesignation(name.clone()));
+            }
+            let idx =
+                u8::try_from(designations.len()).map_err(|_| BuildError::DesignationsTooLong)?;
+            records.push((*utoff, *dst, idx));
+            designations.extend_from_slice(name.as_bytes());
+            designations.push(0);
+        }
+        if let Some((_, ty)) = self
+            .transitions
+            .iter()
+            .find(|v| v.1 as usize >= self.types.len())
+        {
+            return Err(BuildError::InvalidTransitionType(*ty));
+        }
+        if self.transitions.windows(2).any(|v| v[0].0 >= v[1].0) {
+            return Err(BuildError::UnsortedTransitions);
+        }
+        if self.leap_seconds.windows(2).any(|v| v[0].0 >= v[1].0) {
+            return Err(BuildError::UnsortedLeapSeconds);
+        }
+        Ok((records, designations))
+    }
+
+    /// Validates and builds the [TZIF].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BuildError] if the data added to this builder does not form a valid TZIF.
+    pub fn build(&self) -> Result<TZIF, BuildError> {
+        let (records, designations) = self.validate()?;
+        let charcnt = designations.len() as u32;
+        Ok(TZIF {
+            block_v1: self.block(0x00, &records, charcnt),
+            block_v2p: Some(self.block(b'2', &records, charcnt)),
+        })
+    }
+
+    /// Validates and writes a version 2 TZIF file, including the time zone designations and
+    /// the footer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BuildError] if the data added to this builder does not form a valid TZIF.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BuildError> {
+        self.to_bytes_version(b'2')
+    }
+
+    // Validates and writes a TZIF file with the given version byte; the footer is only written
+    // for V2+ files.
+    pub(super) fn to_bytes_version(&self, version: u8) -> Result<Vec<u8>, BuildError> {
+        let (records, designations) = self.validate()?;
+        Ok(write(
+            version,
+            &self.transitions,
+            &records,
+            &designations,
+            &self.leap_seconds,
+            &self.footer,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::{BuildError, TZIFBuilder, TZIF};
+
+    #[test]
+    fn build() {
+        let tzif = TZIFBuilder::new()
+            .local_time_type(0, false, "UTC")
+            .local_time_type(3600, false, "CET")
+            .transition(-(1 << 40), 1)
+            .transition(0, 0)
+            .leap_second(78796800, 1)
+            .build()
+            .unwrap();
+        assert_eq!(tzif.block_v1.data.transition_times, [0]);
+        let v2 = tzif.block_v2p.unwrap();
+        assert_eq!(v2.data.transition_times, [-(1 << 40), 0]);
+        assert_eq!(v2.data.local_time_type_records[1].idx, 4);
+        assert_eq!(v2.header.charcnt, 8);
+    }
+
+    #[test]
+    fn to_bytes() {
+        let builder = TZIFBuilder::new()
+            .local_time_type(3600, false, "CET")
+            .local_time_type(7200, true, "CEST")
+            .transition(1711846800, 1)
+            .footer("CET-1CEST,M3.5.0,M10.5.0/3");
+        let bytes = builder.to_bytes().unwrap();
+        assert!(bytes.windows(9).any(|v| v == b"CET\0CEST\0"));
+        assert!(bytes.ends_with(b"\nCET-1CEST,M3.5.0,M10.5.0/3\n"));
+        let v2 = TZIF::read(&*bytes).unwrap().block_v2p.unwrap();
+        assert_eq!(v2.header.charcnt, 9);
+        assert_eq!(v2.data.transition_times, [1711846800]);
+        assert_eq!(v2.data.local_time_type_records[1].idx, 4);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = |b: TZIFBuilder| b.build().err().unwrap();
+        assert_eq!(err(TZIFBuilder::new()), BuildError::NoLocalTimeType);
+        let builder = TZIFBuilder::new().local_time_type(0, false, "UTC");
+        assert_eq!(
+            err(builder.clone().transition(0, 1)),
+            BuildError::InvalidTransitionType(1)
+        );
+        assert_eq!(
+            err(builder.clone().transition(1, 0).transition(1, 0)),
+            BuildError::UnsortedTransitions
+        );
+        assert_eq!(
+            err(builder.local_time_type(0, false, "")),
+            BuildError::InvalidDesignation("".into())
+        );
+    }
+}
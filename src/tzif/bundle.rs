@@ -0,0 +1,215 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::tzif::{Error, TZIF};
+use bytesutil::ReadBytes;
+use std::io::{Read, Seek, SeekFrom};
+
+const HEADER_SIZE: usize = 24;
+const ENTRY_SIZE: usize = 52;
+const NAME_SIZE: usize = 40;
+
+/// An entry of the index of a [Bundle].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleEntry {
+    /// The name of the zone (ex: `Europe/Paris`).
+    pub name: String,
+
+    /// The offset of the TZIF stream, relative to the start of the bundle.
+    pub offset: u64,
+
+    /// The length of the TZIF stream in bytes.
+    pub length: u32,
+}
+
+/// A reader for tzdata bundles storing many TZIF streams back-to-back behind an index table,
+/// such as the combined `tzdata` file found on Android.
+///
+/// The bundle starts with a 24 bytes header made of the `tzdata` signature followed by a NUL
+/// terminated version, then the big-endian offsets of the index, of the data and of the zone
+/// table. Each index entry is made of a NUL padded 40 bytes zone name followed by the
+/// big-endian offset relative to the data, length and raw UTC offset of the zone.
+pub struct Bundle<R> {
+    reader: R,
+    version: String,
+    entries: Vec<BundleEntry>,
+}
+
+impl<R: Read + Seek> Bundle<R> {
+    /// Opens a bundle by reading its header and index.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader`: the [Read](Read) + [Seek](Seek) to read the bundle from.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [InvalidSignature](Error::InvalidSignature) if the stream is not a
+    /// tzdata bundle or its index is inconsistent, and [Io](Error::Io) if it could not be read.
+    pub fn open(mut reader: R) -> Result<Bundle<R>, Error> {
+        let mut header = [0; HEADER_SIZE];
+        reader.read_exact(&mut header).map_err(Error::Io)?;
+        if &header[..6] != b"tzdata" || header[11] != 0 {
+            return Err(Error::InvalidSignature);
+        }
+        let version = String::from_utf8_lossy(&header[6..11]).into_owned();
+        let index_offset = u32::read_bytes_be(&header[12..16]) as u64;
+        let data_offset = u32::read_bytes_be(&header[16..20]) as u64;
+        if data_offset < index_offset
+            || !(data_offset - index_offset).is_multiple_of(ENTRY_SIZE as u64)
+        {
+            return Err(Error::InvalidSignature);
+        }
+        // The index must fit in the stream, which bounds the preallocation below.
+        let end = reader.seek(SeekFrom::End(0)).map_err(Error::Io)?;
+        if data_offset > end {
+            return Err(Error::InvalidSignature);
+        }
+        reader
+            .seek(SeekFrom::Start(index_offset))
+            .map_err(Error::Io)?;
+        let count = (data_offset - index_offset) as usize / ENTRY_SIZE;
+        let mut entries = Vec::with_capacity(count);
+        let mut entry = [0; ENTRY_SIZE];
+        for _ in 0..count {
+            reader.read_exact(&mut entry).map_err(Error::Io)?;
+            let name = &entry[..NAME_SIZE];
+            let len = name.iter().position(|v| *v == 0).unwrap_or(NAME_SIZE);
+            entries.push(BundleEntry {
+                name: String::from_utf8_lossy(&name[..len]).into_owned(),
+                offset: data_offset + u32::read_bytes_be(&entry[40..44]) as u64,
+                length: u32::read_bytes_be(&entry[44..48]),
+            });
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Bundle {
+            reader,
+            version,
+            entries,
+        })
+    }
+
+    /// Returns the version of the time zone database (ex: `2024a`).
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns the index entries of this bundle, sorted by name.
+    pub fn entries(&self) -> &[BundleEntry] {
+        &self.entries
+    }
+
+    /// Returns the index entry of a zone.
+    pub fn entry(&self, name: &str) -> Option<&BundleEntry> {
+        self.entries
+            .binary_search_by(|v| v.name.as_str().cmp(name))
+            .ok()
+            .map(|i| &self.entries[i])
+    }
+
+    /// Reads and decodes the TZIF stream of a zone.
+    ///
+    /// Returns None if the zone does not exist in this bundle.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [Error](Error) if the TZIF stream of the zone could not be
+    /// decoded.
+    pub fn read(&mut self, name: &str) -> Option<Result<TZIF, Error>> {
+        let entry = self.entry(name)?;
+        let (offset, length) = (entry.offset, entry.length);
+        Some(self.read_at(offset, length))
+    }
+
+    fn read_at(&mut self, offset: u64, length: u32) -> Result<TZIF, Error> {
+        self.reader
+            .seek(SeekFrom::Start(offset))
+            .map_err(Error::Io)?;
+        TZIF::read((&mut self.reader).take(length as u64))
+    }
+
+    /// Extracts the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::tests::block;
+    use crate::tzif::{Bundle, Error};
+    use std::io::Cursor;
+
+    fn bundle() -> Vec<u8> {
+        let zones = [("Europe/Paris", block(0, 4)), ("Asia/Tokyo", block(0, 4))];
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"tzdata2024a\0");
+        let index_offset = 24u32;
+        let data_offset = index_offset + 52 * zones.len() as u32;
+        for offset in [index_offset, data_offset, 0] {
+            buf.extend_from_slice(&offset.to_be_bytes());
+        }
+        let mut start = 0u32;
+        for (name, data) in &zones {
+            let mut entry = [0; 52];
+            entry[..name.len()].copy_from_slice(name.as_bytes());
+            entry[40..44].copy_from_slice(&start.to_be_bytes());
+            entry[44..48].copy_from_slice(&(data.len() as u32).to_be_bytes());
+            buf.extend_from_slice(&entry);
+            start += data.len() as u32;
+        }
+        for (_, data) in &zones {
+            buf.extend_from_slice(data);
+        }
+        buf
+    }
+
+    #[test]
+    fn lookup() {
+        let mut bundle = Bundle::open(Cursor::new(bundle())).unwrap();
+        assert_eq!(bundle.version(), "2024a");
+        assert_eq!(bundle.entries()[0].name, "Asia/Tokyo");
+        assert!(bundle.read("Europe/Paris").unwrap().is_ok());
+        assert!(bundle.read("Asia/Tokyo").unwrap().is_ok());
+        assert!(bundle.read("Europe/London").is_none());
+        let res = Bundle::open(Cursor::new(b"TZif".repeat(8)));
+        assert!(matches!(res, Err(Error::InvalidSignature)));
+    }
+
+    #[test]
+    fn truncated() {
+        // An index claiming millions of entries in a stream ending right after the header.
+        let mut bytes = bundle();
+        bytes[16..20].copy_from_slice(&(24 + 52 * 1_000_000u32).to_be_bytes());
+        bytes.truncate(24);
+        assert!(matches!(
+            Bundle::open(Cursor::new(bytes)),
+            Err(Error::InvalidSignature)
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::tzif::TZIF;
+
+/// A range of UNIX times during which a time zone has a constant offset.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OffsetRange {
+    /// The UNIX time at which this range starts (inclusive).
+    pub start: i64,
+
+    /// The UNIX time at which this range ends (exclusive).
+    pub end: i64,
+
+    /// The number of seconds to be added to UT in order to determine local time.
+    pub utoff: i32,
+
+    /// Whether local time is Daylight Saving Time.
+    pub dst: bool,
+}
+
+/// A compact table of [OffsetRange] covering all UNIX times, optimized for repeated offset
+/// queries.
+///
+/// See [compile](TZIF::compile).
+#[derive(Clone, Debug)]
+pub struct Compiled {
+    ranges: Vec<OffsetRange>,
+}
+
+impl Compiled {
+    /// Returns the sorted, contiguous and non-overlapping ranges of this table.
+    pub fn ranges(&self) -> &[OffsetRange] {
+        &self.ranges
+    }
+
+    /// Returns the range containing a given UNIX time.
+    pub fn lookup(&self, time: i64) -> &OffsetRange {
+        let index = self.ranges.partition_point(|v| v.end <= time);
+        &self.ranges[index.min(self.ranges.len() - 1)]
+    }
+
+    /// Returns the number of seconds to be added to UT in order to determine local time at a
+    /// given UNIX time.
+    pub fn offset_at(&self, time: i64) -> i32 {
+        self.lookup(time).utoff
+    }
+
+    /// Returns true if local time is Daylight Saving Time at a given UNIX time.
+    pub fn is_dst_at(&self, time: i64) -> bool {
+        self.lookup(time).dst
+    }
+}
+
+impl TZIF {
+    /// Compiles the transitions of the most precise block into a [Compiled] table.
+    ///
+    /// Times before the first transition use the first local time type, as recommended by
+    /// RFC 8536, and times after the last transition keep the last local time type (the TZ
+    /// string footer is not taken into account). Consecutive transitions with identical offset
+    /// and DST flag are merged.
+    pub fn compile(&self) -> Compiled {
+        let data = &self.block().data;
+        let (utoff, dst) = data
+            .local_time_type_records
+            .first()
+            .map_or((0, false), |v| (v.utoff, v.dst));
+        let mut ranges = vec![OffsetRange {
+            start: i64::MIN,
+            end: i64::MAX,
+            utoff,
+            dst,
+        }];
+        for transition in data.transitions() {
+            let last = ranges.last_mut().unwrap();
+            if last.utoff == transition.utoff && last.dst == transition.dst {
+                continue;
+            }
+            if transition.time <= last.start {
+                last.utoff = transition.utoff;
+                last.dst = transition.dst;
+                continue;
+            }
+            last.end = transition.time;
+            ranges.push(OffsetRange {
+                start: transition.time,
+                end: i64::MAX,
+                utoff: transition.utoff,
+                dst: transition.dst,
+            });
+        }
+        Compiled { ranges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::TZIFBuilder;
+
+    #[test]
+    fn compile() {
+        let tzif = TZIFBuilder::new()
+            .local_time_type(3600, false, "CET")
+            .local_time_type(7200, true, "CEST")
+            .transition(100, 0)
+            .transition(200, 1)
+            .transition(300, 0)
+            .build()
+            .unwrap();
+        let compiled = tzif.compile();
+        assert_eq!(compiled.ranges().len(), 3);
+        assert_eq!(compiled.offset_at(i64::MIN), 3600);
+        assert_eq!(compiled.offset_at(199), 3600);
+        assert_eq!(compiled.offset_at(200), 7200);
+        assert!(compiled.is_dst_at(299));
+        assert_eq!(compiled.offset_at(i64::MAX), 3600);
+    }
+}
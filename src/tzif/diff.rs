@@ -0,0 +1,150 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Comparison of TZIF structures (ex: to audit tzdata updates).
+
+use crate::tzif::{Transition, TZIF};
+
+/// A difference between 2 TZIF structures.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// A transition only present in the new structure.
+    Added(Transition),
+
+    /// A transition only present in the old structure.
+    Removed(Transition),
+
+    /// A transition present at the same time in both structures but with a different offset or
+    /// DST flag.
+    Changed {
+        /// The transition in the old structure.
+        old: Transition,
+
+        /// The transition in the new structure.
+        new: Transition,
+    },
+}
+
+/// Computes the transition-by-transition difference between 2 TZIF structures.
+///
+/// The most precise block of each structure is compared (see [TZIF::block]).
+///
+/// # Arguments
+///
+/// * `old`: the old TZIF structure.
+/// * `new`: the new TZIF structure.
+///
+/// returns: Vec<Change> the list of changes sorted by transition time.
+pub fn diff(old: &TZIF, new: &TZIF) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut old = old.block().data.transitions().peekable();
+    let mut new = new.block().data.transitions().peekable();
+    loop {
+        match (old.peek().copied(), new.peek().copied()) {
+            (None, None) => break,
+            (Some(o), Some(n)) if o.time == n.time => {
+                if o != n {
+                    changes.push(Change::Changed { old: o, new: n });
+                }
+                old.next();
+                new.next();
+            }
+            (Some(o), n) if n.is_none_or(|n| o.time < n.time) => {
+                changes.push(Change::Removed(o));
+                old.next();
+            }
+            (_, Some(n)) => {
+                changes.push(Change::Added(n));
+                new.next();
+            }
+            (_, None) => unreachable!(),
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::diff::{diff, Change};
+    use crate::tzif::{Block, Data, Header, LocalTimeTypeRecord, Transition, TZIF};
+
+    fn tzif(transitions: &[(i64, u8)]) -> TZIF {
+        TZIF {
+            block_v1: Block {
+                header: Header {
+                    version: 0,
+                    isutcnt: 0,
+                    isstdcnt: 0,
+                    leapcnt: 0,
+                    timecnt: transitions.len() as _,
+                    typecnt: 2,
+                    charcnt: 0,
+                },
+                data: Data {
+                    transition_times: transitions.iter().map(|v| v.0).collect(),
+                    transition_types: transitions.iter().map(|v| v.1).collect(),
+                    local_time_type_records: vec![
+                        LocalTimeTypeRecord {
+                            utoff: 3600,
+                            dst: false,
+                            idx: 0,
+                        },
+                        LocalTimeTypeRecord {
+                            utoff: 7200,
+                            dst: true,
+                            idx: 0,
+                        },
+                    ],
+                    leap_second_records: Vec::new(),
+                    std_wall_indicators: Vec::new(),
+                    ut_local_indicators: Vec::new(),
+                },
+            },
+            block_v2p: None,
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let old = tzif(&[(10, 1), (20, 0), (30, 1)]);
+        let new = tzif(&[(20, 1), (30, 1), (40, 0)]);
+        let t = |time, utoff, dst| Transition { time, utoff, dst };
+        assert_eq!(
+            diff(&old, &new),
+            [
+                Change::Removed(t(10, 7200, true)),
+                Change::Changed {
+                    old: t(20, 3600, false),
+                    new: t(20, 7200, true)
+                },
+                Change::Added(t(40, 3600, false))
+            ]
+        );
+        assert!(diff(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::tzif::{Error, TZIF};
+
+/// A set of raw TZIF streams baked into the binary, for platforms where no zoneinfo database
+/// exists at runtime.
+///
+/// See [tzif_embed](crate::tzif_embed) to embed zones from a zoneinfo directory at compile time.
+#[derive(Copy, Clone, Debug)]
+pub struct EmbeddedZones {
+    zones: &'static [(&'static str, &'static [u8])],
+}
+
+impl EmbeddedZones {
+    /// Creates a new set of embedded zones from (name, raw TZIF stream) pairs.
+    pub const fn new(zones: &'static [(&'static str, &'static [u8])]) -> EmbeddedZones {
+        EmbeddedZones { zones }
+    }
+
+    /// Returns an iterator over the names of all embedded zones.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        self.zones.iter().map(|(name, _)| *name)
+    }
+
+    /// Returns the raw TZIF stream of a zone.
+    pub fn raw(&self, name: &str) -> Option<&'static [u8]> {
+        self.zones
+            .iter()
+            .find(|(v, _)| *v == name)
+            .map(|(_, data)| *data)
+    }
+
+    /// Decodes the TZIF stream of a zone.
+    ///
+    /// Returns None if the zone is not embedded.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [Error](Error) if the embedded stream could not be decoded.
+    pub fn get(&self, name: &str) -> Option<Result<TZIF, Error>> {
+        self.raw(name).map(TZIF::read)
+    }
+}
+
+/// Embeds TZIF streams from a zoneinfo directory into the binary at compile time, producing an
+/// [EmbeddedZones](crate::tzif::EmbeddedZones).
+///
+/// The directory must be a string literal; relative paths are resolved from the file invoking
+/// the macro, as done by [include_bytes]. A missing zone fails the build.
+///
+/// # Example
+///
+/// ```ignore
+/// use bp3d_util::tzif::EmbeddedZones;
+/// use bp3d_util::tzif_embed;
+///
+/// static ZONES: EmbeddedZones = tzif_embed!("/usr/share/zoneinfo", "Europe/Paris", "UTC");
+///
+/// let paris = ZONES.get("Europe/Paris").unwrap().unwrap();
+/// ```
+#[macro_export]
+macro_rules! tzif_embed {
+    ($dir: literal, $($name: literal),+ $(,)?) => {
+        $crate::tzif::EmbeddedZones::new(&[
+            $(($name, include_bytes!(concat!($dir, "/", $name)))),+
+        ])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::tests::block;
+    use crate::tzif::EmbeddedZones;
+
+    #[test]
+    fn embedded() {
+        let data: &'static [u8] = Box::leak(block(0, 4).into_boxed_slice());
+        let zones: &'static [(&str, &[u8])] =
+            Box::leak(Box::new([("CET", data), ("Bad", &[][..])]));
+        let zones = EmbeddedZones::new(zones);
+        assert_eq!(zones.names().collect::<Vec<_>>(), ["CET", "Bad"]);
+        assert!(zones.get("CET").unwrap().is_ok());
+        assert!(zones.get("Bad").unwrap().is_err());
+        assert!(zones.get("UTC").is_none());
+    }
+
+    #[test]
+    fn embed_macro() {
+        static ZONES: EmbeddedZones = crate::tzif_embed!("fixtures", "Etc/UTC");
+        assert_eq!(ZONES.names().collect::<Vec<_>>(), ["Etc/UTC"]);
+        assert_eq!(
+            ZONES.raw("Etc/UTC"),
+            Some(&include_bytes!("fixtures/Etc/UTC")[..])
+        );
+        let tzif = ZONES.get("Etc/UTC").unwrap().unwrap();
+        assert_eq!(tzif.block().data.local_time_type_records[0].utoff, 0);
+    }
+}
@@ -0,0 +1,414 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A parser for POSIX TZ strings (ex: `CET-1CEST,M3.5.0,M10.5.0/3`), as found in the `TZ`
+//! environment variable and in the footer of TZIF V2+ files.
+//!
+//! **See [RFC](https://www.rfc-editor.org/rfc/rfc8536.html#section-3.3)**
+
+use crate::civil::civil_from_days;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The error returned when a POSIX TZ string could not be parsed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PosixTzError;
+
+impl Display for PosixTzError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid POSIX TZ string")
+    }
+}
+
+impl std::error::Error for PosixTzError {}
+
+/// The date at which a DST transition occurs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RuleDate {
+    /// `Jn`: the Julian day n (1 to 365), February 29 is never counted.
+    Julian1(u16),
+
+    /// `n`: the zero-based Julian day n (0 to 365), February 29 is counted in leap years.
+    Julian0(u16),
+
+    /// `Mm.w.d`: the day d (0 is Sunday) of week w (1 to 5, 5 is the last week) of month m.
+    MonthWeekDay {
+        /// The month (1 to 12).
+        month: u8,
+
+        /// The week (1 to 5).
+        week: u8,
+
+        /// The day of the week (0 to 6, 0 is Sunday).
+        day: u8,
+    },
+}
+
+/// A transition rule: a date and a local time of day.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// The date of the transition.
+    pub date: RuleDate,
+
+    /// The local time of the transition in seconds since midnight (may be negative or exceed 24
+    /// hours as allowed by RFC 8536).
+    pub time: i32,
+}
+
+/// Daylight Saving Time information of a [PosixTz].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dst {
+    /// The designation of DST (ex: `CEST`).
+    pub name: String,
+
+    /// The number of seconds to add to UT in order to determine local time during DST.
+    pub utoff: i32,
+
+    /// The rule for the start of DST, expressed in local standard time.
+    pub start: Rule,
+
+    /// The rule for the end of DST, expressed in local DST time.
+    pub end: Rule,
+}
+
+/// A parsed POSIX TZ string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PosixTz {
+    /// The designation of standard time (ex: `CET`).
+    pub name: String,
+
+    /// The number of seconds to add to UT in order to determine local standard time.
+    ///
+    /// Note that this is the opposite sign of the offset found in the TZ string.
+    pub utoff: i32,
+
+    /// The Daylight Saving Time information, None if the zone does not observe DST.
+    pub dst: Option<Dst>,
+}
+
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl RuleDate {
+    /// Returns the number of days since the UNIX epoch of this date in the given year.
+    fn days(&self, year: i64) -> i64 {
+        let jan1 = days_from_civil(year, 1, 1);
+        match *self {
+            RuleDate::Julian1(n) => {
+                let leap_shift = (is_leap(year) && n >= 60) as i64;
+                jan1 + n as i64 - 1 + leap_shift
+            }
+            RuleDate::Julian0(n) => jan1 + n as i64,
+            RuleDate::MonthWeekDay { month, week, day } => {
+                let first = days_from_civil(year, month as u32, 1);
+                let first_dow = (first + 4).rem_euclid(7);
+                let mut mday = 1 + (day as i64 - first_dow).rem_euclid(7) + (week as i64 - 1) * 7;
+                while mday > days_in_month(year, month as u32) as i64 {
+                    mday -= 7;
+                }
+                first + mday - 1
+            }
+        }
+    }
+}
+
+struct Parser<'a>(&'a [u8]);
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.0.first().copied()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.0 = &self.0[1..];
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), PosixTzError> {
+        match self.eat(c) {
+            true => Ok(()),
+            false => Err(PosixTzError),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, PosixTzError> {
+        let len = if self.eat(b'<') {
+            self.0.iter().position(|c| *c == b'>').ok_or(PosixTzError)?
+        } else {
+            self.0
+                .iter()
+                .position(|c| !c.is_ascii_alphabetic())
+                .unwrap_or(self.0.len())
+        };
+        let name = std::str::from_utf8(&self.0[..len]).map_err(|_| PosixTzError)?;
+        if name.len() < 3 {
+            return Err(PosixTzError);
+        }
+        self.0 = &self.0[len..];
+        self.eat(b'>');
+        Ok(name.into())
+    }
+
+    fn number(&mut self, max: u32) -> Result<u32, PosixTzError> {
+        let len = self
+            .0
+            .iter()
+            .position(|c| !c.is_ascii_digit())
+            .unwrap_or(self.0.len());
+        if len == 0 {
+            return Err(PosixTzError);
+        }
+        let value = std::str::from_utf8(&self.0[..len])
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|v| *v <= max)
+            .ok_or(PosixTzError)?;
+        self.0 = &self.0[len..];
+        Ok(value)
+    }
+
+    fn time(&mut self, max_hours: u32) -> Result<i32, PosixTzError> {
+        let sign = if self.eat(b'-') {
+            -1
+        } else {
+            self.eat(b'+');
+            1
+        };
+        let mut secs = self.number(max_hours)? * 3600;
+        if self.eat(b':') {
+            secs += self.number(59)? * 60;
+            if self.eat(b':') {
+                secs += self.number(59)?;
+            }
+        }
+        Ok(sign * secs as i32)
+    }
+
+    fn rule(&mut self) -> Result<Rule, PosixTzError> {
+        let date = if self.eat(b'J') {
+            match self.number(365)? {
+                0 => return Err(PosixTzError),
+                day => RuleDate::Julian1(day as u16),
+            }
+        } else if self.eat(b'M') {
+            let month = self.number(12)? as u8;
+            self.expect(b'.')?;
+            let week = self.number(5)? as u8;
+            self.expect(b'.')?;
+            let day = self.number(6)? as u8;
+            if month == 0 || week == 0 {
+                return Err(PosixTzError);
+            }
+            RuleDate::MonthWeekDay { month, week, day }
+        } else {
+            RuleDate::Julian0(self.number(365)? as u16)
+        };
+        let time = match self.eat(b'/') {
+            true => self.time(167)?,
+            false => 7200,
+        };
+        Ok(Rule { date, time })
+    }
+}
+
+impl FromStr for PosixTz {
+    type Err = PosixTzError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut p = Parser(s.as_bytes());
+        let name = p.name()?;
+        let utoff = -p.time(24)?;
+        if p.peek().is_none() {
+            return Ok(PosixTz {
+                name,
+                utoff,
+                dst: None,
+            });
+        }
+        let dst_name = p.name()?;
+        let dst_utoff = match p.peek() {
+            Some(b',') | None => utoff + 3600,
+            _ => -p.time(24)?,
+        };
+        let (start, end) = if p.eat(b',') {
+            let start = p.rule()?;
+            p.expect(b',')?;
+            (start, p.rule()?)
+        } else {
+            // POSIX leaves the default rule implementation-defined, use the US rules like glibc.
+            let rule = |month, week| Rule {
+                date: RuleDate::MonthWeekDay {
+                    month,
+                    week,
+                    day: 0,
+                },
+                time: 7200,
+            };
+            (rule(3, 2), rule(11, 1))
+        };
+        if p.peek().is_some() {
+            return Err(PosixTzError);
+        }
+        Ok(PosixTz {
+            name,
+            utoff,
+            dst: Some(Dst {
+                name: dst_name,
+                utoff: dst_utoff,
+                start,
+                end,
+            }),
+        })
+    }
+}
+
+impl PosixTz {
+    /// Checks if DST is in effect at the given UNIX timestamp.
+    ///
+    /// Standard time is assumed for timestamps whose year is too far away for the DST
+    /// transitions to be representable.
+    pub fn is_dst_at(&self, timestamp: i64) -> bool {
+        let dst = match &self.dst {
+            Some(v) => v,
+            None => return false,
+        };
+        // Returns the UNIX time of a transition, given the offset in effect before it.
+        let transition = |rule: &Rule, year: i64, utoff: i32| {
+            rule.date
+                .days(year)
+                .checked_mul(86400)?
+                .checked_add(rule.time as i64 - utoff as i64)
+        };
+        let bounds = timestamp.checked_add(self.utoff as i64).and_then(|local| {
+            let (year, _, _) = civil_from_days(local.div_euclid(86400));
+            let start = transition(&dst.start, year, self.utoff)?;
+            let end = transition(&dst.end, year, dst.utoff)?;
+            Some((start, end))
+        });
+        match bounds {
+            Some((start, end)) if start < end => timestamp >= start && timestamp < end,
+            Some((start, end)) => !(timestamp >= end && timestamp < start),
+            None => false,
+        }
+    }
+
+    /// Returns the number of seconds to add to UT in order to determine local time at the given
+    /// UNIX timestamp.
+    pub fn offset_at(&self, timestamp: i64) -> i32 {
+        match (&self.dst, self.is_dst_at(timestamp)) {
+            (Some(dst), true) => dst.utoff,
+            _ => self.utoff,
+        }
+    }
+
+    /// Returns the designation in effect at the given UNIX timestamp (ex: `CEST`).
+    pub fn name_at(&self, timestamp: i64) -> &str {
+        match (&self.dst, self.is_dst_at(timestamp)) {
+            (Some(dst), true) => &dst.name,
+            _ => &self.name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::posix::{days_from_civil, PosixTz, RuleDate};
+
+    #[test]
+    fn civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 3, 31), 19813);
+    }
+
+    #[test]
+    fn parse() {
+        let tz: PosixTz = "CET-1CEST,M3.5.0,M10.5.0/3".parse().unwrap();
+        assert_eq!(tz.utoff, 3600);
+        let dst = tz.dst.as_ref().unwrap();
+        assert_eq!(dst.utoff, 7200);
+        assert_eq!(dst.end.time, 3 * 3600);
+        let tz: PosixTz = "<+0330>-3:30".parse().unwrap();
+        assert_eq!(tz.name, "+0330");
+        assert_eq!(tz.utoff, 3 * 3600 + 1800);
+        assert!(tz.dst.is_none());
+        let tz: PosixTz = "EST5EDT,J60/-1,300/167:59:59".parse().unwrap();
+        let dst = tz.dst.unwrap();
+        assert_eq!(dst.start.date, RuleDate::Julian1(60));
+        assert_eq!(dst.start.time, -3600);
+        assert_eq!(dst.end.date, RuleDate::Julian0(300));
+        assert!("CET".parse::<PosixTz>().is_err());
+        assert!("CET-1CEST,M3.5.0".parse::<PosixTz>().is_err());
+        assert!("CET-1CEST,M13.5.0,M10.5.0".parse::<PosixTz>().is_err());
+        assert!("EST5EDT,J0,J365".parse::<PosixTz>().is_err());
+        assert!("EST5EDT,J1,J365".parse::<PosixTz>().is_ok());
+    }
+
+    #[test]
+    fn offset() {
+        let tz: PosixTz = "CET-1CEST,M3.5.0,M10.5.0/3".parse().unwrap();
+        // 2024-03-31T00:59:59Z and 2024-03-31T01:00:00Z.
+        assert_eq!(tz.offset_at(1711846799), 3600);
+        assert_eq!(tz.offset_at(1711846800), 7200);
+        // 2024-10-27T00:59:59Z and 2024-10-27T01:00:00Z.
+        assert_eq!(tz.offset_at(1729990799), 7200);
+        assert_eq!(tz.offset_at(1729990800), 3600);
+        assert_eq!(tz.name_at(1720000000), "CEST");
+        let tz: PosixTz = "AEST-10AEDT,M10.1.0,M4.1.0/3".parse().unwrap();
+        assert_eq!(tz.offset_at(1704067200), 11 * 3600);
+        assert_eq!(tz.offset_at(1719792000), 10 * 3600);
+        assert_eq!(tz.offset_at(i64::MAX), 10 * 3600);
+        let tz: PosixTz = "CET-1CEST,M3.5.0,M10.5.0/3".parse().unwrap();
+        assert_eq!(tz.offset_at(i64::MIN), 3600);
+        assert_eq!(tz.offset_at(i64::MAX), 3600);
+        assert_eq!(tz.name_at(i64::MAX), "CET");
+    }
+}
@@ -0,0 +1,484 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::tzif::{Block, Data, Header, TZIF};
+use bytesutil::ReadBytes;
+use std::fmt::{Display, Formatter};
+
+/// A section of a TZIF data block.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Section {
+    /// The 44 octets header.
+    Header,
+
+    /// The transition times.
+    TransitionTimes,
+
+    /// The transition types.
+    TransitionTypes,
+
+    /// The local time type records.
+    LocalTimeTypeRecords,
+
+    /// The time zone designations.
+    TimeZoneDesignations,
+
+    /// The leap-second records.
+    LeapSecondRecords,
+
+    /// The standard/wall indicators.
+    StdWallIndicators,
+
+    /// The UT/local indicators.
+    UtLocalIndicators,
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Section::Header => "header",
+            Section::TransitionTimes => "transition times",
+            Section::TransitionTypes => "transition types",
+            Section::LocalTimeTypeRecords => "local time type records",
+            Section::TimeZoneDesignations => "time zone designations",
+            Section::LeapSecondRecords => "leap-second records",
+            Section::StdWallIndicators => "standard/wall indicators",
+            Section::UtLocalIndicators => "UT/local indicators",
+        })
+    }
+}
+
+/// A precise reason why a TZIF stream was rejected by [parse](TZIF::parse).
+///
+/// Offsets are in octets from the start of the stream; indices are relative to the section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Corruption {
+    /// A section extends past the end of the stream.
+    Truncated {
+        /// The truncated section.
+        section: Section,
+
+        /// The offset of the start of the section.
+        offset: usize,
+
+        /// The number of octets needed by the section.
+        needed: u64,
+
+        /// The number of octets left in the stream.
+        available: usize,
+    },
+
+    /// The signature of a header is not `TZif`.
+    InvalidSignature {
+        /// The offset of the header.
+        offset: usize,
+    },
+
+    /// The "typecnt" or "charcnt" field of a header is zero.
+    ZeroCount {
+        /// The offset of the header.
+        offset: usize,
+
+        /// The name of the field.
+        field: &'static str,
+    },
+
+    /// The "isutcnt" or "isstdcnt" field of a header is neither zero nor equal to "typecnt".
+    CountMismatch {
+        /// The offset of the header.
+        offset: usize,
+
+        /// The name of the field.
+        field: &'static str,
+    },
+
+    /// Transition times are not sorted in strictly ascending order.
+    UnsortedTransitions {
+        /// The offset of the section.
+        offset: usize,
+
+        /// The index of the first out of order transition.
+        index: usize,
+    },
+
+    /// Leap-second occurrences are not sorted in strictly ascending order.
+    UnsortedLeapSeconds {
+        /// The offset of the section.
+        offset: usize,
+
+        /// The index of the first out of order record.
+        index: usize,
+    },
+
+    /// A transition type references a non-existent local time type.
+    InvalidTransitionType {
+        /// The offset of the section.
+        offset: usize,
+
+        /// The index of the transition.
+        index: usize,
+    },
+
+    /// A local time type record has an invalid offset, DST flag or designation index.
+    InvalidLocalTimeType {
+        /// The offset of the section.
+        offset: usize,
+
+        /// The index of the record.
+        index: usize,
+    },
+
+    /// An indicator is neither 0 nor 1.
+    InvalidIndicator {
+        /// The section of the indicator.
+        section: Section,
+
+        /// The offset of the section.
+        offset: usize,
+
+        /// The index of the indicator.
+        index: usize,
+    },
+}
+
+impl Display for Corruption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Corruption::Truncated {
+                section,
+                offset,
+                needed,
+                available,
+            } => write!(
+                f,
+                "truncated {} at offset {}: needs {} octets, {} available",
+                section, offset, needed, available
+            ),
+            Corruption::InvalidSignature { offset } => {
+                write!(f, "invalid TZIF signature at offset {}", offset)
+            }
+            Corruption::ZeroCount { offset, field } => {
+                write!(f, "{} is zero in header at offset {}", field, offset)
+            }
+            Corruption::CountMismatch { offset, field } => write!(
+                f,
+                "{} is neither 0 nor typecnt in header at offset {}",
+                field, offset
+            ),
+            Corruption::UnsortedTransitions { offset, index } => write!(
+                f,
+                "transition {} is out of order in section at offset {}",
+                index, offset
+            ),
+            Corruption::UnsortedLeapSeconds { offset, index } => write!(
+                f,
+                "leap-second record {} is out of order in section at offset {}",
+                index, offset
+            ),
+            Corruption::InvalidTransitionType { offset, index } => write!(
+                f,
+                "transition {} has an invalid type in section at offset {}",
+                index, offset
+            ),
+            Corruption::InvalidLocalTimeType { offset, index } => write!(
+                f,
+                "local time type record {} is invalid in section at offset {}",
+                index, offset
+            ),
+            Corruption::InvalidIndicator {
+                section,
+                offset,
+                index,
+            } => write!(
+                f,
+                "invalid value in {} at offset {} index {}",
+                section, offset, index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Corruption {}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, section: Section, len: u64) -> Result<(usize, &'a [u8]), Corruption> {
+        let available = self.data.len() - self.pos;
+        if len > available as u64 {
+            return Err(Corruption::Truncated {
+                section,
+                offset: self.pos,
+                needed: len,
+                available,
+            });
+        }
+        let offset = self.pos;
+        self.pos += len as usize;
+        Ok((offset, &self.data[offset..self.pos]))
+    }
+}
+
+fn check_indicators(section: Section, (offset, data): (usize, &[u8])) -> Result<(), Corruption> {
+    match data.iter().position(|v| *v > 1) {
+        Some(index) => Err(Corruption::InvalidIndicator {
+            section,
+            offset,
+            index,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn parse_header(cursor: &mut Cursor, v1: bool) -> Result<Header, Corruption> {
+    let (offset, data) = cursor.take(Section::Header, 44)?;
+    if &data[..4] != b"TZif" {
+        return Err(Corruption::InvalidSignature { offset });
+    }
+    let header = Header {
+        version: if v1 { 0x00 } else { data[4] },
+        isutcnt: u32::read_bytes_be(&data[20..24]),
+        isstdcnt: u32::read_bytes_be(&data[24..28]),
+        leapcnt: u32::read_bytes_be(&data[28..32]),
+        timecnt: u32::read_bytes_be(&data[32..36]),
+        typecnt: u32::read_bytes_be(&data[36..40]),
+        charcnt: u32::read_bytes_be(&data[40..44]),
+    };
+    if header.typecnt == 0 {
+        return Err(Corruption::ZeroCount {
+            offset,
+            field: "typecnt",
+        });
+    }
+    if header.charcnt == 0 {
+        return Err(Corruption::ZeroCount {
+            offset,
+            field: "charcnt",
+        });
+    }
+    if header.isutcnt != 0 && header.isutcnt != header.typecnt {
+        return Err(Corruption::CountMismatch {
+            offset,
+            field: "isutcnt",
+        });
+    }
+    if header.isstdcnt != 0 && header.isstdcnt != header.typecnt {
+        return Err(Corruption::CountMismatch {
+            offset,
+            field: "isstdcnt",
+        });
+    }
+    Ok(header)
+}
+
+fn parse_data(cursor: &mut Cursor, header: &Header) -> Result<Data, Corruption> {
+    let start = cursor.pos;
+    let size = header.time_size() as u64;
+    // Every section is bounds checked before anything is decoded or allocated, such that the
+    // decoder below can no longer fail and allocates at most the size of the input.
+    let times = cursor.take(Section::TransitionTimes, size * header.timecnt as u64)?;
+    let types = cursor.take(Section::TransitionTypes, header.timecnt as u64)?;
+    let records = cursor.take(Section::LocalTimeTypeRecords, 6 * header.typecnt as u64)?;
+    cursor.take(Section::TimeZoneDesignations, header.charcnt as u64)?;
+    let leaps = cursor.take(
+        Section::LeapSecondRecords,
+        (size + 4) * header.leapcnt as u64,
+    )?;
+    let std_wall = cursor.take(Section::StdWallIndicators, header.isstdcnt as u64)?;
+    let ut_local = cursor.take(Section::UtLocalIndicators, header.isutcnt as u64)?;
+    check_indicators(Section::StdWallIndicators, std_wall)?;
+    check_indicators(Section::UtLocalIndicators, ut_local)?;
+    for (index, record) in records.1.chunks_exact(6).enumerate() {
+        if i32::read_bytes_be(&record[..4]) == i32::MIN
+            || record[4] > 1
+            || record[5] as u32 >= header.charcnt
+        {
+            return Err(Corruption::InvalidLocalTimeType {
+                offset: records.0,
+                index,
+            });
+        }
+    }
+    if let Some(index) = types.1.iter().position(|v| *v as u32 >= header.typecnt) {
+        return Err(Corruption::InvalidTransitionType {
+            offset: types.0,
+            index,
+        });
+    }
+    let data = match Data::read(&cursor.data[start..cursor.pos], header) {
+        Ok(data) => data,
+        Err(_) => unreachable!("all sections were bounds checked"),
+    };
+    if let Some(index) = data.transition_times.windows(2).position(|v| v[0] >= v[1]) {
+        return Err(Corruption::UnsortedTransitions {
+            offset: times.0,
+            index: index + 1,
+        });
+    }
+    if let Some(index) = data
+        .leap_second_records
+        .windows(2)
+        .position(|v| v[0].occurrence >= v[1].occurrence)
+    {
+        return Err(Corruption::UnsortedLeapSeconds {
+            offset: leaps.0,
+            index: index + 1,
+        });
+    }
+    Ok(data)
+}
+
+fn parse_block(cursor: &mut Cursor, v1: bool) -> Result<(u8, Block), Corruption> {
+    let version = cursor.data.get(cursor.pos + 4).copied().unwrap_or(0);
+    let header = parse_header(cursor, v1)?;
+    let data = parse_data(cursor, &header)?;
+    Ok((version, Block { header, data }))
+}
+
+impl TZIF {
+    /// Decodes and validates a TZIF stream held in memory, designed for untrusted input and
+    /// fuzzing.
+    ///
+    /// This function never panics and never allocates more than a small multiple of the size
+    /// of `data`: every section is bounds checked against the input before it is decoded.
+    /// Unlike [read](TZIF::read), the structural constraints of RFC 8536 are enforced and the
+    /// first violation is reported as a precise [Corruption]. A V2+ block is required when the
+    /// V1 header announces one; the footer is ignored.
+    ///
+    /// # Example
+    ///
+    /// A cargo-fuzz target:
+    ///
+    /// ```ignore
+    /// #![no_main]
+    /// use libfuzzer_sys::fuzz_target;
+    ///
+    /// fuzz_target!(|data: &[u8]| {
+    ///     let _ = bp3d_util::tzif::TZIF::parse(data);
+    /// });
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns a [Corruption] describing the first issue found in the stream.
+    pub fn parse(data: &[u8]) -> Result<TZIF, Corruption> {
+        let mut cursor = Cursor { data, pos: 0 };
+        let (version, block_v1) = parse_block(&mut cursor, true)?;
+        let block_v2p = match version {
+            0x00 => None,
+            _ => Some(parse_block(&mut cursor, false)?.1),
+        };
+        Ok(TZIF {
+            block_v1,
+            block_v2p,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::tests::block;
+    use crate::tzif::{Corruption, Section, TZIF};
+
+    #[test]
+    fn valid() {
+        let mut data = block(b'2', 4);
+        data.extend_from_slice(&block(b'2', 8));
+        data.extend_from_slice(b"\nCET-1\n");
+        let tzif = TZIF::parse(&data).unwrap();
+        assert!(tzif.block_v2p.is_some());
+        assert!(TZIF::parse(&block(0, 4)).unwrap().block_v2p.is_none());
+    }
+
+    #[test]
+    fn corrupt() {
+        let mut data = block(b'2', 4);
+        data.extend_from_slice(&block(b'2', 8));
+        assert_eq!(
+            TZIF::parse(&data[..57]).err(),
+            Some(Corruption::Truncated {
+                section: Section::TimeZoneDesignations,
+                offset: 55,
+                needed: 4,
+                available: 2,
+            })
+        );
+        assert_eq!(
+            TZIF::parse(&data[..59]).err(),
+            Some(Corruption::Truncated {
+                section: Section::Header,
+                offset: 59,
+                needed: 44,
+                available: 0,
+            })
+        );
+        let mut bad = data.clone();
+        bad[48] = 1;
+        assert_eq!(
+            TZIF::parse(&bad).err(),
+            Some(Corruption::InvalidTransitionType {
+                offset: 48,
+                index: 0
+            })
+        );
+        let mut bad = data.clone();
+        bad[32..36].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            TZIF::parse(&bad).err(),
+            Some(Corruption::Truncated {
+                section: Section::TransitionTimes,
+                ..
+            })
+        ));
+        let mut bad = data.clone();
+        bad[36..40].copy_from_slice(&0u32.to_be_bytes());
+        assert_eq!(
+            TZIF::parse(&bad).err().unwrap().to_string(),
+            "typecnt is zero in header at offset 0"
+        );
+    }
+
+    #[test]
+    fn never_panics() {
+        let mut data = block(b'2', 4);
+        data.extend_from_slice(&block(b'2', 8));
+        for len in 0..data.len() {
+            assert!(TZIF::parse(&data[..len]).is_err());
+        }
+        for i in 0..data.len() {
+            for value in [0x00, 0x01, 0x7F, 0xFF] {
+                let mut bad = data.clone();
+                bad[i] = value;
+                let _ = TZIF::parse(&bad);
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright (c) 2024, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Utilities to synthesize valid TZIF byte streams for testing time handling code.
+//!
+//! # Example
+//!
+//! ```
+//! use bp3d_util::tzif::TZIF;
+//! use bp3d_util::tzif::testing::Builder;
+//!
+//! let bytes = Builder::new(b'2')
+//!     .local_time_type(3600, false, "CET")
+//!     .local_time_type(7200, true, "CEST")
+//!     .transition(1711846800, 1)
+//!     .footer("CET-1CEST,M3.5.0,M10.5.0/3")
+//!     .build()
+//!     .unwrap();
+//! let tzif = TZIF::read(&*bytes).unwrap();
+//! assert_eq!(tzif.block_v2p.unwrap().data.transition_times, [1711846800]);
+//! ```
+
+use crate::tzif::{BuildError, TZIFBuilder};
+
+/// A builder to synthesize TZIF byte streams of any version.
+///
+/// This is a thin wrapper around [TZIFBuilder], sharing its validation, which also allows
+/// choosing the version of the written stream.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    version: u8,
+    inner: TZIFBuilder,
+}
+
+impl Builder {
+    /// Creates a new empty builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `version`: the TZIF version byte (`0` for V1, `b'2'`, `b'3'` or `b'4'` for V2+).
+    pub fn new(version: u8) -> Builder {
+        Builder {
+            version,
+            inner: TZIFBuilder::new(),
+        }
+    }
+
+    /// Adds a transition; see [TZIFBuilder::transition].
+    pub fn transition(mut self, time: i64, ty: u8) -> Self {
+        self.inner = self.inner.transition(time, ty);
+        self
+    }
+
+    /// Adds a local time type; see [TZIFBuilder::local_time_type].
+    pub fn local_time_type(mut self, utoff: i32, dst: bool, name: &str) -> Self {
+        self.inner = self.inner.local_time_type(utoff, dst, name);
+        self
+    }
+
+    /// Adds a leap second record; see [TZIFBuilder::leap_second].
+    pub fn leap_second(mut self, occurrence: i64, correction: i32) -> Self {
+        self.inner = self.inner.leap_second(occurrence, correction);
+        self
+    }
+
+    /// Sets the TZ string footer, only written for V2+ streams.
+    pub fn footer(mut self, footer: &str) -> Self {
+        self.inner = self.inner.footer(footer);
+        self
+    }
+
+    /// Validates and builds the TZIF byte stream.
+    ///
+    /// For V2+ streams, the V1 block only contains the transitions and leap seconds which fit
+    /// in 32 bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BuildError] if the data added to this builder does not form a valid TZIF.
+    pub fn build(&self) -> Result<Vec<u8>, BuildError> {
+        self.inner.to_bytes_version(self.version)
+    }
+}
+
+/// Generates a pseudo-random but valid V2 TZIF byte stream, suitable for property-based tests.
+///
+/// The same seed always generates the same stream.
+///
+/// # Arguments
+///
+/// * `seed`: the seed of the pseudo-random generator.
+/// * `transitions`: the number of transitions to generate.
+pub fn generate(seed: u64, transitions: usize) -> Vec<u8> {
+    let mut state = seed | 1;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let type_count = (next() % 4 + 1) as u8;
+    let mut builder = Builder::new(b'2');
+    for i in 0..type_count {
+        let utoff = (next() % (26 * 3600)) as i32 - 12 * 3600;
+        builder = builder.local_time_type(utoff, i % 2 == 1, &format!("Z{}", i));
+    }
+    let mut time = -(1i64 << 33);
+    for _ in 0..transitions {
+        time += (next() % (1 << 28)) as i64 + 1;
+        builder = builder.transition(time, (next() % type_count as u64) as u8);
+    }
+    builder.build().expect("generated TZIF should be valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tzif::testing::{generate, Builder};
+    use crate::tzif::BuildError;
+    use crate::tzif::TZIF;
+
+    #[test]
+    fn roundtrip() {
+        let bytes = Builder::new(b'2')
+            .local_time_type(0, false, "UTC")
+            .transition(-(1 << 40), 0)
+            .transition(0, 0)
+            .leap_second(78796800, 1)
+            .build()
+            .unwrap();
+        let tzif = TZIF::read(&*bytes).unwrap();
+        assert_eq!(tzif.block_v1.data.transition_times, [0]);
+        let v2 = tzif.block_v2p.unwrap();
+        assert_eq!(v2.data.transition_times, [-(1 << 40), 0]);
+        assert_eq!(v2.data.leap_second_records[0].correction, 1);
+        let v1 = Builder::new(0).local_time_type(0, false, "UTC");
+        assert!(TZIF::read(&*v1.build().unwrap())
+            .unwrap()
+            .block_v2p
+            .is_none());
+        assert_eq!(
+            Builder::new(b'2').transition(0, 0).build(),
+            Err(BuildError::NoLocalTimeType)
+        );
+    }
+
+    #[test]
+    fn designations_too_long() {
+        let name = "A".repeat(127);
+        let builder = Builder::new(b'2')
+            .local_time_type(0, false, &name)
+            .local_time_type(0, false, &name);
+        assert!(builder.build().is_ok());
+        assert_eq!(
+            builder.local_time_type(0, false, "B").build(),
+            Err(BuildError::DesignationsTooLong)
+        );
+    }
+
+    #[test]
+    fn generated() {
+        for seed in 0..16 {
+            let tzif = TZIF::read(&*generate(seed, seed as usize * 10)).unwrap();
+            let v2 = tzif.block_v2p.unwrap();
+            assert_eq!(v2.data.transition_times.len(), seed as usize * 10);
+            assert!(v2.data.transition_times.windows(2).all(|v| v[0] < v[1]));
+        }
+    }
+}